@@ -0,0 +1,476 @@
+extern crate va_ts as ts;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+/// system allocator counting allocations
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const SZ: usize = 188;
+
+/// programs in synthetic multiplex
+const PROGRAMS: u16 = 4;
+/// video/audio frames per program
+const FRAMES: usize = 200;
+/// PSI repetition (frames between PAT/PMT/SDT)
+const PSI_INTERVAL: usize = 40;
+
+const VIDEO_FRAME_SZ: usize = 4000;
+const AUDIO_FRAME_SZ: usize = 400;
+
+/// 40ms @ 90kHz
+const FRAME_DURATION: u64 = 3600;
+
+#[derive(Default)]
+struct Events {
+    tables: usize,
+    packets: usize,
+}
+
+impl ts::DemuxerEvents for Events {
+    fn on_table(&mut self, _: ts::SubtableID, _: &ts::DemuxedTable) {
+        self.tables += 1;
+    }
+
+    fn on_packet(&mut self, pkt: &ts::DemuxedPacket) {
+        self.packets += pkt.buf.sz();
+    }
+}
+
+fn pmt_pid(program_number: u16) -> u16 {
+    0x100 + program_number
+}
+
+fn video_pid(program_number: u16) -> u16 {
+    0x200 + program_number * 2
+}
+
+fn audio_pid(program_number: u16) -> u16 {
+    0x201 + program_number * 2
+}
+
+/// split payload into ts-packets;
+/// adaptation (without length byte) goes into the first packet,
+/// tail is stuffed via adaptation field
+fn packetize(
+    pid: u16,
+    cc: &mut u8,
+    adaptation: Option<&[u8]>,
+    payload: &[u8],
+    out: &mut Vec<[u8; SZ]>,
+) {
+    let mut rest = payload;
+    let mut first = true;
+
+    while first || !rest.is_empty() {
+        let mut af = if first {
+            adaptation.map(<[u8]>::to_vec)
+        } else {
+            None
+        };
+        let used = af.as_ref().map_or(0, |af| 1 + af.len());
+        let mut room = SZ - 4 - used;
+
+        if rest.len() < room {
+            let af = af.get_or_insert_with(Vec::new);
+            if used == 0 {
+                room -= 1;
+            }
+            if rest.len() < room && af.is_empty() {
+                af.push(0x00);
+                room -= 1;
+            }
+            af.resize(af.len() + room - rest.len(), 0xFF);
+            room = rest.len();
+        }
+
+        let mut raw = [0xFFu8; SZ];
+        raw[0] = 0x47;
+        raw[1] = if first { 0x40 } else { 0x00 } | (pid >> 8) as u8;
+        raw[2] = pid as u8;
+        raw[3] = if af.is_some() { 0x30 } else { 0x10 } | *cc;
+
+        let mut pos = 4;
+        if let Some(af) = af {
+            raw[pos] = af.len() as u8;
+            raw[pos + 1..pos + 1 + af.len()].copy_from_slice(&af);
+            pos += 1 + af.len();
+        }
+        raw[pos..pos + room].copy_from_slice(&rest[..room]);
+
+        out.push(raw);
+
+        *cc = (*cc + 1) & 0x0F;
+        rest = &rest[room..];
+        first = false;
+    }
+}
+
+/// MPEG-2 CRC32 (poly 0x04C11DB7, no reflection)
+fn crc32(buf: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+
+    for b in buf {
+        crc ^= u32::from(*b) << 24;
+
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ 0x04C1_1DB7
+            } else {
+                crc << 1
+            };
+        }
+    }
+
+    crc
+}
+
+/// long-form section;
+/// prefixed with pointer-field
+fn section(table_id: u8, ext: u16, body: &[u8]) -> Vec<u8> {
+    let section_length = 5 + body.len() + 4;
+
+    let mut buf = vec![
+        0x00,
+        table_id,
+        0xB0 | (section_length >> 8) as u8,
+        section_length as u8,
+        (ext >> 8) as u8,
+        ext as u8,
+        0xC1,
+        0x00,
+        0x00,
+    ];
+    buf.extend_from_slice(body);
+    let crc = crc32(&buf[1..]);
+    buf.extend_from_slice(&crc.to_be_bytes());
+    buf
+}
+
+fn pat() -> Vec<u8> {
+    let body = (1..=PROGRAMS)
+        .flat_map(|n| {
+            let pid = pmt_pid(n);
+            [(n >> 8) as u8, n as u8, 0xE0 | (pid >> 8) as u8, pid as u8]
+        })
+        .collect::<Vec<_>>();
+
+    section(0x00, 1, &body)
+}
+
+/// h264 video + mp2 audio; PCR on video
+fn pmt(program_number: u16) -> Vec<u8> {
+    let (vpid, apid) = (video_pid(program_number), audio_pid(program_number));
+
+    #[rustfmt::skip]
+    let body = [
+        0xE0 | (vpid >> 8) as u8, vpid as u8, 0xF0, 0x00,
+        0x1B, 0xE0 | (vpid >> 8) as u8, vpid as u8, 0xF0, 0x00,
+        0x03, 0xE0 | (apid >> 8) as u8, apid as u8, 0xF0, 0x00,
+    ];
+
+    section(0x02, program_number, &body)
+}
+
+/// SDT actual with enough services to span several ts-packets
+fn sdt() -> Vec<u8> {
+    let mut body = vec![0x00, 0x01, 0xFF];
+
+    for service_id in 1..=16u16 {
+        let provider = b"va-ts synthetic provider";
+        let name = format!("synthetic service {:02}", service_id);
+        let desc_sz = 3 + provider.len() + name.len();
+
+        body.extend_from_slice(&[
+            (service_id >> 8) as u8,
+            service_id as u8,
+            0xFC,
+            0x80 | ((desc_sz + 2) >> 8) as u8,
+            (desc_sz + 2) as u8,
+            0x48,
+            desc_sz as u8,
+            0x01,
+            provider.len() as u8,
+        ]);
+        body.extend_from_slice(provider);
+        body.push(name.len() as u8);
+        body.extend_from_slice(name.as_bytes());
+    }
+
+    section(0x42, 1, &body)
+}
+
+/// EIT schedule actual (service 1) section
+/// of 8-section segment with single event;
+/// prefixed with pointer-field
+fn eit(version_number: u8, section_number: u8) -> Vec<u8> {
+    let name = format!("synthetic event {}/{}", version_number, section_number);
+    let text = [b'x'; 160];
+    let desc_sz = 3 + 1 + name.len() + 1 + text.len();
+
+    let mut body = vec![
+        // tsid, onid, segment-last-section-number, last-table-id
+        0x00, 0x01, 0x00, 0x01, 0x07, 0x50,
+    ];
+    #[rustfmt::skip]
+    body.extend_from_slice(&[
+        0x00, section_number,
+        // start: MJD 2021-01-01, 12:00:00
+        0xE4, 0x80, 0x12, 0x00, 0x00,
+        // duration: 00:30:00
+        0x00, 0x30, 0x00,
+        0x10 | ((desc_sz + 2) >> 8) as u8, (desc_sz + 2) as u8,
+        0x4D, desc_sz as u8, b'e', b'n', b'g', name.len() as u8,
+    ]);
+    body.extend_from_slice(name.as_bytes());
+    body.push(text.len() as u8);
+    body.extend_from_slice(&text);
+
+    let mut buf = section(0x50, 1, &body);
+    buf[6] = 0xC1 | ((version_number & 0x1F) << 1);
+    buf[7] = section_number;
+    buf[8] = 0x07;
+
+    let crc_pos = buf.len() - 4;
+    let crc = crc32(&buf[1..crc_pos]);
+    buf[crc_pos..].copy_from_slice(&crc.to_be_bytes());
+    buf
+}
+
+/// EIT schedule re-versioned every cycle,
+/// as seen by long-running EPG collector
+fn eit_versions() -> Vec<[u8; SZ]> {
+    let mut cc = 0;
+    let mut out = Vec::new();
+
+    for version_number in 0..32 {
+        for section_number in 0..8 {
+            packetize(
+                0x0012,
+                &mut cc,
+                None,
+                &eit(version_number, section_number),
+                &mut out,
+            );
+        }
+    }
+
+    out
+}
+
+fn pcr(v: u64) -> [u8; 6] {
+    let (base, ext) = (v / 300, v % 300);
+
+    [
+        (base >> 25) as u8,
+        (base >> 17) as u8,
+        (base >> 9) as u8,
+        (base >> 1) as u8,
+        ((base & 1) << 7) as u8 | 0x7E | (ext >> 8) as u8,
+        ext as u8,
+    ]
+}
+
+/// PES with PTS only
+fn pes_frame(stream_id: u8, pts: u64, sz: usize) -> Vec<u8> {
+    let mut buf = vec![
+        0x00,
+        0x00,
+        0x01,
+        stream_id,
+        0x00,
+        0x00,
+        0x80,
+        0x80,
+        0x05,
+        0x21 | ((pts >> 29) & 0x0E) as u8,
+        (pts >> 22) as u8,
+        ((pts >> 14) & 0xFE) as u8 | 0x01,
+        (pts >> 7) as u8,
+        ((pts << 1) & 0xFE) as u8 | 0x01,
+    ];
+    buf.resize(buf.len() + sz, 0xAA);
+    buf
+}
+
+/// deterministic multi-program transport stream
+struct Synthetic {
+    psi: Vec<[u8; SZ]>,
+    pes: Vec<[u8; SZ]>,
+    mux: Vec<[u8; SZ]>,
+}
+
+impl Synthetic {
+    fn new() -> Synthetic {
+        let mut cc = [0u8; 0x2000];
+        let mut psi = Vec::new();
+        let mut pes = Vec::new();
+        let mut mux = Vec::new();
+
+        let mut psi_burst = |cc: &mut [u8; 0x2000], out: &mut Vec<[u8; SZ]>| {
+            let start = out.len();
+
+            packetize(0x0000, &mut cc[0x0000], None, &pat(), out);
+            for n in 1..=PROGRAMS {
+                let pid = pmt_pid(n);
+                packetize(pid, &mut cc[usize::from(pid)], None, &pmt(n), out);
+            }
+            packetize(0x0011, &mut cc[0x0011], None, &sdt(), out);
+
+            psi.extend_from_slice(&out[start..]);
+        };
+
+        for frame in 0..FRAMES {
+            if frame % PSI_INTERVAL == 0 {
+                psi_burst(&mut cc, &mut mux);
+            }
+
+            let pts = frame as u64 * FRAME_DURATION;
+
+            for n in 1..=PROGRAMS {
+                let start = mux.len();
+
+                let (vpid, apid) = (video_pid(n), audio_pid(n));
+                let random_access = if frame % 25 == 0 { 0x40 } else { 0x00 };
+                let mut af = vec![0x10 | random_access];
+                af.extend_from_slice(&pcr(pts * 300));
+
+                packetize(
+                    vpid,
+                    &mut cc[usize::from(vpid)],
+                    Some(&af),
+                    &pes_frame(0xE0, pts, VIDEO_FRAME_SZ),
+                    &mut mux,
+                );
+                packetize(
+                    apid,
+                    &mut cc[usize::from(apid)],
+                    None,
+                    &pes_frame(0xC0, pts, AUDIO_FRAME_SZ),
+                    &mut mux,
+                );
+
+                pes.extend_from_slice(&mux[start..]);
+            }
+        }
+
+        Synthetic { psi, pes, mux }
+    }
+}
+
+fn demux(c: &mut Criterion) {
+    let stream = Synthetic::new();
+
+    let mut group = c.benchmark_group("demux");
+    group.throughput(Throughput::Elements(stream.mux.len() as u64));
+    group.bench_function("multi-program", |b| {
+        b.iter(|| {
+            let mut demuxer = ts::Demuxer::new(Events::default());
+
+            for raw in stream.mux.iter() {
+                demuxer.demux(raw).unwrap();
+            }
+
+            black_box(demuxer)
+        })
+    });
+    group.finish();
+}
+
+fn sections(c: &mut Criterion) {
+    let stream = Synthetic::new();
+
+    let mut group = c.benchmark_group("sections");
+    group.throughput(Throughput::Elements(stream.psi.len() as u64));
+    group.bench_function("pat-pmt-sdt", |b| {
+        b.iter(|| {
+            let mut demuxer = ts::Demuxer::new(Events::default());
+
+            for raw in stream.psi.iter() {
+                demuxer.demux_tables(raw).unwrap();
+            }
+
+            black_box(demuxer)
+        })
+    });
+    group.finish();
+}
+
+fn pes_assembly(c: &mut Criterion) {
+    let stream = Synthetic::new();
+
+    let mut group = c.benchmark_group("pes");
+    group.throughput(Throughput::Elements(stream.pes.len() as u64));
+    group.bench_function("video-audio", |b| {
+        b.iter_batched(
+            || {
+                let mut demuxer = ts::Demuxer::new(Events::default());
+
+                for raw in stream.psi.iter() {
+                    demuxer.demux_tables(raw).unwrap();
+                }
+
+                demuxer
+            },
+            |mut demuxer| {
+                for raw in stream.pes.iter() {
+                    demuxer.demux_packets(raw).unwrap();
+                }
+
+                demuxer
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn eit_pool(c: &mut Criterion) {
+    let stream = eit_versions();
+
+    let run = |pool: usize| {
+        let mut demuxer = ts::Demuxer::new(Events::default());
+        demuxer.set_section_pool(pool);
+
+        for raw in stream.iter() {
+            demuxer.demux_tables(raw).unwrap();
+        }
+
+        demuxer
+    };
+
+    let mut group = c.benchmark_group("eit-versions");
+    group.throughput(Throughput::Elements(stream.len() as u64));
+
+    for (name, pool) in [("default", 0), ("pool", 16)] {
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        black_box(run(pool));
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+
+        eprintln!("eit-versions/{}: {} allocations", name, allocations);
+
+        group.bench_function(name, |b| b.iter(|| black_box(run(pool))));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, demux, sections, pes_assembly, eit_pool);
+criterion_main!(benches);
@@ -30,6 +30,7 @@ pub enum Kind {
     Encoding(Utf8Error),
     SyncPoison, // TODO: rewrite
     Ts(TsError),
+    Json,
 
     Unknown(Box<dyn StdError + Send + Sync>),
 }
@@ -42,7 +43,7 @@ pub struct Error {
 impl Error {
     pub fn new(kind: Kind) -> Error {
         Error {
-            kind: kind,
+            kind,
             details: None,
         }
     }
@@ -52,7 +53,7 @@ impl Error {
         I: Into<Cow<'static, str>>,
     {
         Error {
-            kind: kind,
+            kind,
             details: Some(details.into()),
         }
     }
@@ -65,6 +66,7 @@ impl fmt::Display for Error {
 }
 
 impl fmt::Debug for Error {
+    #[allow(deprecated)]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, r#"(:error {:?} ("{}""#, self.kind, self.description())?;
 
@@ -76,6 +78,7 @@ impl fmt::Debug for Error {
     }
 }
 
+#[allow(deprecated)]
 impl StdError for Error {
     fn description(&self) -> &str {
         match self.kind {
@@ -86,6 +89,7 @@ impl StdError for Error {
             Kind::Fmt(ref err) => err.description(),
             Kind::SyncPoison => "sync lock/condvar poison error",
             Kind::Ts(ref err) => err.description(),
+            Kind::Json => "json output error",
 
             Kind::Unknown(ref err) => err.description(),
         }
@@ -100,6 +104,7 @@ impl StdError for Error {
             Kind::Fmt(ref err) => Some(err),
             Kind::SyncPoison => None,
             Kind::Ts(ref err) => Some(err),
+            Kind::Json => None,
 
             Kind::Unknown(ref err) => Some(err.as_ref()),
         }
@@ -2,16 +2,19 @@ extern crate va_ts as ts;
 
 mod error;
 
+#[cfg(feature = "serde")]
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::fmt;
 use std::net::{Ipv4Addr, UdpSocket};
+use std::path::PathBuf;
 use std::process;
 use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
 use std::time::Duration;
 
-use clap::{App, Arg};
+use clap::{Arg, ArgAction, Command};
 use url::{Host, Url};
 
 use error::{Error, Kind as ErrorKind, Result};
@@ -19,70 +22,91 @@ use error::{Error, Kind as ErrorKind, Result};
 trait Input {
     fn open(&mut self) -> Result<()>;
     fn read(&mut self) -> Result<()>;
+    #[allow(dead_code)]
     fn close(&mut self) -> Result<()>;
 }
 
+#[derive(Default)]
 struct DemuxerTSEvents {
     done_once: HashSet<ts::SubtableID>,
+    params_once: HashSet<ts::PID>,
+
+    /// collect EIT events for JSON document instead of printing
+    json: bool,
+    /// service-id -> events
+    #[cfg(feature = "serde")]
+    eit: BTreeMap<u16, Vec<ts::EventInfo>>,
 }
 
-impl Default for DemuxerTSEvents {
-    fn default() -> Self {
+impl DemuxerTSEvents {
+    fn new(json: bool) -> DemuxerTSEvents {
         DemuxerTSEvents {
-            done_once: Default::default(),
+            json,
+            ..Default::default()
         }
     }
 }
 
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct ServiceJSON<'d> {
+    #[serde(flatten)]
+    service: &'d ts::Service,
+    events: &'d [ts::EventInfo],
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct ProbeJSON<'d> {
+    services: Vec<ServiceJSON<'d>>,
+}
+
+/// services, streams and EIT events
+#[cfg(feature = "serde")]
+fn print_json(demuxer: &ts::Demuxer<DemuxerTSEvents>) -> Result<()> {
+    let eit = &demuxer.events().eit;
+    let doc = ProbeJSON {
+        services: demuxer
+            .services()
+            .map(|service| ServiceJSON {
+                service,
+                events: eit
+                    .get(&service.service_id)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+            })
+            .collect(),
+    };
+
+    serde_json::to_writer_pretty(std::io::stdout(), &doc)
+        .map_err(|e| Error::new_with_details(ErrorKind::Json, e.to_string()))?;
+    println!();
+
+    Ok(())
+}
+
+#[cfg(not(feature = "serde"))]
+fn print_json(_: &ts::Demuxer<DemuxerTSEvents>) -> Result<()> {
+    Err(Error::new_with_details(
+        ErrorKind::Json,
+        "serde feature is disabled",
+    ))
+}
+
 struct EITFmt<'t>(&'t ts::DemuxedTable);
 
 impl<'t> fmt::Display for EITFmt<'t> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for section_ref in self.0.sections.0.iter() {
-            let section = (*section_ref).borrow();
-            let raw = section.buf.0.get_ref().as_slice();
-
-            let eit = ts::EIT::new(raw);
-
-            for event in eit.events().filter_map(ts::Result::ok) {
-                write!(
-                    f,
-                    "  {} ~ {}\n",
-                    event.start_time(),
-                    ts::DurationFmt::from(event.duration()),
-                )?;
-
-                if let Some(descs) = event.descriptors() {
-                    for desc in descs
-                        .filter_map(ts::Result::ok)
-                        .filter(|d| d.is_dvb_short_event())
-                    {
-                        match desc.tag() {
-                            ts::Tag::DVB(ts::TagDVB::ShortEvent) => {
-                                let desc = ts::DescDVB0x4D::new(desc.buf_data());
-
-                                let mut dst_buf = [0u8; 256];
-                                let mut dst_str = std::str::from_utf8_mut(&mut dst_buf).unwrap();
-
-                                match ts::AnnexA2::decode(desc.event_name(), &mut dst_str) {
-                                    Ok(..) => write!(f, r#"    "{}""#, dst_str),
-                                    Err(err) => write!(f, "  (error: {:?})", err),
-                                }?;
-
-                                dst_buf = [0u8; 256];
-                                dst_str = std::str::from_utf8_mut(&mut dst_buf).unwrap();
-
-                                match ts::AnnexA2::decode(desc.text(), &mut dst_str) {
-                                    Ok(..) => write!(f, r#" "{}""#, dst_str),
-                                    Err(err) => write!(f, " (error: {})", err),
-                                }?;
-
-                                write!(f, "\n")?;
-                            }
-                            _ => {}
-                        }
-                    }
-                }
+        for event in self.0.view().events().filter_map(ts::Result::ok) {
+            writeln!(
+                f,
+                "  {} ~ {}",
+                event.start_time(),
+                ts::DurationFmt::from(event.duration()),
+            )?;
+
+            if let Some(short) = event.short_event() {
+                writeln!(f, r#"    "{}" "{}""#, short.event_name, short.text)?;
             }
         }
 
@@ -92,6 +116,22 @@ impl<'t> fmt::Display for EITFmt<'t> {
 
 impl ts::DemuxerEvents for DemuxerTSEvents {
     fn on_table(&mut self, id: ts::SubtableID, tbl: &ts::DemuxedTable) {
+        if self.json {
+            #[cfg(feature = "serde")]
+            if let ts::SubtableID::EIT(_, service_id, ..) = id {
+                if self.done_once.insert(id) {
+                    self.eit.entry(service_id).or_default().extend(
+                        tbl.view()
+                            .events()
+                            .filter_map(ts::Result::ok)
+                            .map(|e| e.info()),
+                    );
+                }
+            }
+
+            return;
+        }
+
         if self.done_once.contains(&id) {
             return;
         } else {
@@ -103,9 +143,8 @@ impl ts::DemuxerEvents for DemuxerTSEvents {
                 print!(":EIT\n{}", EITFmt(tbl));
             }
             _ => {
-                for section_ref in tbl.sections.0.iter() {
-                    let section = (*section_ref).borrow();
-                    let raw = section.buf.0.get_ref().as_slice();
+                for raw in tbl.sections_bytes() {
+                    let raw = &raw[..];
 
                     match id {
                         ts::SubtableID::PAT(..) => {
@@ -126,34 +165,108 @@ impl ts::DemuxerEvents for DemuxerTSEvents {
         }
     }
 
+    fn on_table_update(&mut self, old_version: u8, id: ts::SubtableID, _: &ts::DemuxedTable) {
+        if self.json {
+            return;
+        }
+
+        println!(
+            ":table-update (:version {} -> {}) {:?}",
+            old_version,
+            id.version_number(),
+            id
+        );
+    }
+
+    fn on_probe_complete(&mut self) {
+        if self.json {
+            return;
+        }
+
+        println!(":probe-complete");
+    }
+
+    fn on_probe_failed(&mut self) {
+        eprintln!(":probe-failed (no PAT/PMT)");
+    }
+
+    fn on_rtp_gap(&mut self, expected: u16, got: u16) {
+        if self.json {
+            return;
+        }
+
+        println!(":rtp-gap (:expected {} :got {})", expected, got);
+    }
+
     fn on_packet(&mut self, pkt: &ts::DemuxedPacket) {
+        if self.json {
+            return;
+        }
+
         println!(
-            "(0x{:016X}) :pid {:?} :pts {:?} :dts {:?} :sz {}",
+            "(0x{:016X}) :program {} :pid {:?} :stream-id {:?} :pts {:?} :dts {:?} :random-access {} :sz {}",
             pkt.offset,
+            pkt.program_number,
             pkt.pid,
+            pkt.stream_id,
             pkt.pts.map(ts::DurationFmt::from),
             pkt.dts.map(ts::DurationFmt::from),
+            pkt.random_access,
             pkt.buf.sz(),
         );
+
+        if self.params_once.contains(&pkt.pid) {
+            return;
+        }
+
+        if let Some(v) = pkt.video_params {
+            println!(
+                ":video :pid {:?} :width {} :height {} :fps {:?}",
+                pkt.pid, v.width, v.height, v.fps
+            );
+        } else if let Some(a) = pkt.audio_params {
+            println!(
+                ":audio :pid {:?} {} {}Hz :channels {:?}",
+                pkt.pid,
+                a.object_type_name(),
+                a.sample_rate,
+                a.channels()
+            );
+        } else {
+            return;
+        }
+
+        self.params_once.insert(pkt.pid);
     }
 }
 
 struct InputUDP {
     url: Url,
 
-    // circullar-buffer / fifo
-    buf: Arc<(Mutex<VecDeque<[u8; ts::Packet::SZ]>>, Condvar)>,
+    // circullar-buffer / fifo of datagrams
+    buf: Arc<(Mutex<VecDeque<Vec<u8>>>, Condvar)>,
 
     demuxer: ts::Demuxer<DemuxerTSEvents>,
 }
 
 impl InputUDP {
-    pub fn new(url: Url, buf_cap: usize) -> InputUDP {
+    pub fn new(url: Url, buf_cap: usize, json: bool) -> InputUDP {
+        let mut demuxer = ts::Demuxer::new(DemuxerTSEvents::new(json));
+
+        // service names are part of JSON document
+        if json {
+            demuxer.set_probe_tables(&[
+                ts::SubtableKind::PAT,
+                ts::SubtableKind::PMT,
+                ts::SubtableKind::SDT,
+            ]);
+        }
+
         InputUDP {
-            url: url,
+            url,
             buf: Arc::new((Mutex::new(VecDeque::with_capacity(buf_cap)), Condvar::new())),
 
-            demuxer: ts::Demuxer::new(Default::default()),
+            demuxer,
         }
     }
 }
@@ -176,7 +289,7 @@ impl Input for InputUDP {
         // let socket = try!(UdpSocket::bind((input_host_domain, input_port)));;
 
         // let iface = Ipv4Addr::new(127, 0, 0, 1);
-        println!(
+        eprintln!(
             "[<] {:?}: {:?} @ {:?}",
             input_host_domain, input_port, iface
         );
@@ -191,15 +304,13 @@ impl Input for InputUDP {
 
         let pair = self.buf.clone();
         thread::spawn(move || {
-            let mut ts_pkt_raw: [u8; ts::Packet::SZ] = [0; ts::Packet::SZ];
-
             loop {
                 // MTU (maximum transmission unit) == 1500 for Ethertnet
                 // 7*ts::Packet::SZ = 7*188 = 1316 < 1500 => OK
                 let mut pkts_raw = [0; 7 * ts::Packet::SZ];
-                let (_, _) = socket.recv_from(&mut pkts_raw).unwrap();
+                let (n, _) = socket.recv_from(&mut pkts_raw).unwrap();
 
-                let &(ref lock, ref cvar) = &*pair;
+                let (lock, cvar) = &*pair;
                 let mut buf = match lock.lock() {
                     Err(e) => {
                         eprintln!("lock and get buffer failed: {}", e);
@@ -208,13 +319,7 @@ impl Input for InputUDP {
                     Ok(buf) => buf,
                 };
 
-                for pkt_index in 0..7 * ts::Packet::SZ / ts::Packet::SZ {
-                    let ts_pkt_raw_src =
-                        &pkts_raw[pkt_index * ts::Packet::SZ..(pkt_index + 1) * ts::Packet::SZ];
-
-                    ts_pkt_raw.copy_from_slice(ts_pkt_raw_src);
-                    buf.push_back(ts_pkt_raw);
-                }
+                buf.push_back(pkts_raw[..n].to_vec());
 
                 cvar.notify_all();
             }
@@ -225,7 +330,7 @@ impl Input for InputUDP {
 
     fn read(&mut self) -> Result<()> {
         let pair = self.buf.clone();
-        let &(ref lock, ref cvar) = &*pair;
+        let (lock, cvar) = &*pair;
         let mut buf = lock.lock().ok().ok_or(Error::new_with_details(
             ErrorKind::SyncPoison,
             "udp read lock error",
@@ -236,14 +341,27 @@ impl Input for InputUDP {
             "udp read cwar wait error",
         ))?;
 
-        while !buf.is_empty() {
-            let ts_pkt_raw = buf.pop_front().unwrap();
+        while let Some(datagram) = buf.pop_front() {
+            let res = if self.url.scheme() == "rtp" {
+                self.demuxer.demux_rtp(&datagram)
+            } else {
+                self.demuxer.demux_datagram(&datagram)
+            };
 
-            if let Err(e) = self.demuxer.demux(&ts_pkt_raw) {
-                eprintln!("error demux ts-packet: ({:?})", e);
+            if let Err(e) = res {
+                eprintln!("error demux datagram: ({:?})", e);
             }
         }
 
+        // live input never ends - emit document once probed
+        // (or what is known after probe timeout)
+        if self.demuxer.events().json
+            && (self.demuxer.is_probe_complete() || self.demuxer.is_probe_failed())
+        {
+            print_json(&self.demuxer)?;
+            process::exit(0);
+        }
+
         Ok(())
     }
 
@@ -254,6 +372,75 @@ impl Input for InputUDP {
     }
 }
 
+/// offline capture; demuxed at once until EOF
+struct InputFile {
+    path: PathBuf,
+
+    /// memory-mapped (requires "mmap" feature)
+    mmap: bool,
+
+    demuxer: ts::Demuxer<DemuxerTSEvents>,
+}
+
+impl InputFile {
+    pub fn new(path: PathBuf, mmap: bool, json: bool) -> InputFile {
+        InputFile {
+            path,
+            mmap,
+
+            demuxer: ts::Demuxer::new(DemuxerTSEvents::new(json)),
+        }
+    }
+
+    #[cfg(feature = "mmap")]
+    fn demux_mmap(&mut self) -> Result<ts::InputStats> {
+        Ok(self.demuxer.demux_file_mmap(&self.path)?)
+    }
+
+    #[cfg(not(feature = "mmap"))]
+    fn demux_mmap(&mut self) -> Result<ts::InputStats> {
+        eprintln!("mmap feature is disabled; fallback to buffered read");
+
+        Ok(self.demuxer.demux_file(&self.path)?)
+    }
+}
+
+impl Input for InputFile {
+    fn open(&mut self) -> Result<()> {
+        eprintln!("[<] {:?}", self.path);
+
+        Ok(())
+    }
+
+    fn read(&mut self) -> Result<()> {
+        let stats = if self.mmap {
+            self.demux_mmap()?
+        } else {
+            self.demuxer.demux_file(&self.path)?
+        };
+
+        if self.demuxer.events().json {
+            return print_json(&self.demuxer);
+        }
+
+        println!(
+            ":summary (:packets {} :skipped {} :errors {})",
+            stats.packets, stats.skipped, stats.errors
+        );
+        for (pid, packets) in stats.pids.iter() {
+            println!("  :pid {} :packets {}", ts::PID::from(*pid), packets);
+        }
+
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        println!("<<< file close");
+
+        Ok(())
+    }
+}
+
 struct Wrkr<I> {
     input: Arc<Mutex<I>>,
 }
@@ -275,12 +462,9 @@ where
         }
 
         thread::spawn(move || loop {
-            match input.lock().unwrap().read() {
-                Err(err) => {
-                    eprintln!("error read {}", err);
-                    return;
-                }
-                Ok(_) => {}
+            if let Err(err) = input.lock().unwrap().read() {
+                eprintln!("error read {}", err);
+                return;
             }
         });
 
@@ -288,34 +472,60 @@ where
     }
 }
 
+fn run_file(path: PathBuf, mmap: bool, json: bool) -> ! {
+    let mut input = InputFile::new(path, mmap, json);
+
+    if let Err(err) = input.open().and_then(|_| input.read()) {
+        eprintln!("error read file: {}\n", err);
+        process::exit(1);
+    }
+
+    process::exit(0);
+}
+
 fn main() {
     // let args: Vec<String> = env::args().collect();
     // println!("{:?}", args);
-    let matches = App::new("V/A tool")
+    let matches = Command::new("V/A tool")
         .version("0.0.3")
         .author("Ivan Egorov <vany.egorov@gmail.com>")
         .about("simple mpeg-ts mcast probe")
         .arg(
-            Arg::with_name("input")
+            Arg::new("input")
                 // .index(1)
-                .short("i")
+                .short('i')
                 .long("input")
-                .help("Sets the input file to use")
-                .required(true)
-                .takes_value(true),
+                .help("Sets the input file to use: udp://<group>:<port>, rtp://<group>:<port>, file://<path> or <path>")
+                .required(true),
+        )
+        .arg(
+            Arg::new("mmap")
+                .long("mmap")
+                .help("Memory-map file input")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Print services, streams and EIT events as JSON (requires \"serde\" feature)")
+                .action(ArgAction::SetTrue),
         )
         .get_matches();
 
-    let input_raw = matches.value_of("input").unwrap();
+    let json = matches.get_flag("json");
+
+    let input_raw = matches.get_one::<String>("input").unwrap();
     let input_url = match Url::parse(input_raw) {
-        Ok(v) => v,
-        Err(err) => {
-            eprintln!("error parse input url: {:?}\n", err);
-            process::exit(1);
+        Ok(v) if v.scheme() != "file" => v,
+        Ok(v) => {
+            let path = v.to_file_path().unwrap_or_else(|_| PathBuf::from(v.path()));
+            run_file(path, matches.get_flag("mmap"), json);
         }
+        // not an url - file path
+        Err(_) => run_file(PathBuf::from(input_raw), matches.get_flag("mmap"), json),
     };
 
-    let input = InputUDP::new(input_url, 5000 * 7);
+    let input = InputUDP::new(input_url, 5000 * 7, json);
 
     let wrkr = Wrkr::new(input);
 
@@ -1,7 +1,74 @@
 use crate::error::{Error, Kind as ErrorKind};
-use std::convert::TryFrom;
+use crate::iso_6937;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+/// character set decoder
+#[derive(Clone, Copy)]
+pub enum Charset {
+    Encoding(&'static encoding_rs::Encoding),
+
+    /// character table not provided by encoding_rs;
+    /// transcode source buffer into UTF-8, return written sz
+    Transcoder(fn(&[u8], &mut [u8]) -> Result<usize, Error>),
+}
+
+/// single byte character table into UTF-8
+#[inline(always)]
+fn transcode_single_byte<F>(src: &[u8], dst: &mut [u8], f: F) -> Result<usize, Error>
+where
+    F: Fn(u8) -> Option<char>,
+{
+    let mut n = 0;
+
+    for b in src.iter() {
+        let c = f(*b).ok_or_else(|| Error::new(ErrorKind::AnnexA2Decode))?;
+
+        if n + c.len_utf8() > dst.len() {
+            break;
+        }
+
+        n += c.encode_utf8(&mut dst[n..]).len();
+    }
+
+    Ok(n)
+}
+
+/// ISO/IEC 8859-1;
+/// encoding_rs maps it into windows-1252
+fn iso_8859_1(src: &[u8], dst: &mut [u8]) -> Result<usize, Error> {
+    transcode_single_byte(src, dst, |b| Some(char::from(b)))
+}
+
+/// ISO/IEC 8859-9;
+/// encoding_rs maps it into windows-1254
+fn iso_8859_9(src: &[u8], dst: &mut [u8]) -> Result<usize, Error> {
+    transcode_single_byte(src, dst, |b| {
+        Some(match b {
+            0xD0 => '\u{011E}', // Ğ
+            0xDD => '\u{0130}', // İ
+            0xDE => '\u{015E}', // Ş
+            0xF0 => '\u{011F}', // ğ
+            0xFD => '\u{0131}', // ı
+            0xFE => '\u{015F}', // ş
+            _ => char::from(b),
+        })
+    })
+}
+
+/// ISO/IEC 8859-11;
+/// encoding_rs maps it into windows-874
+fn iso_8859_11(src: &[u8], dst: &mut [u8]) -> Result<usize, Error> {
+    transcode_single_byte(src, dst, |b| match b {
+        0x00..=0xA0 => Some(char::from(b)),
+        0xA1..=0xDA | 0xDF..=0xFB => core::char::from_u32(0x0E00 + u32::from(b - 0xA0)),
+        _ => None,
+    })
+}
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum TableA3 {
     IsoIec8859_5,
     IsoIec8859_6,
@@ -25,18 +92,21 @@ pub enum TableA3 {
 }
 
 impl TableA3 {
-    pub fn encoding(self) -> Option<&'static encoding_rs::Encoding> {
+    pub fn charset(self) -> Option<Charset> {
         match self {
-            TableA3::IsoIec8859_5 => Some(encoding_rs::ISO_8859_5),
-            TableA3::IsoIec8859_6 => Some(encoding_rs::ISO_8859_6),
-            TableA3::IsoIec8859_7 => Some(encoding_rs::ISO_8859_7),
-            TableA3::IsoIec8859_8 => Some(encoding_rs::ISO_8859_8),
-            TableA3::IsoIec8859_13 => Some(encoding_rs::ISO_8859_13),
-            TableA3::IsoIec8859_14 => Some(encoding_rs::ISO_8859_14),
-            TableA3::IsoIec8859_15 => Some(encoding_rs::ISO_8859_15),
-            TableA3::Big5subsetOfIsoIec10646 => Some(encoding_rs::BIG5),
-            TableA3::Gb2312_1980 => Some(encoding_rs::GBK),
-            TableA3::Utf8encodingOfIsoIec10646 => Some(encoding_rs::UTF_8),
+            TableA3::IsoIec8859_5 => Some(Charset::Encoding(encoding_rs::ISO_8859_5)),
+            TableA3::IsoIec8859_6 => Some(Charset::Encoding(encoding_rs::ISO_8859_6)),
+            TableA3::IsoIec8859_7 => Some(Charset::Encoding(encoding_rs::ISO_8859_7)),
+            TableA3::IsoIec8859_8 => Some(Charset::Encoding(encoding_rs::ISO_8859_8)),
+            TableA3::IsoIec8859_9 => Some(Charset::Transcoder(iso_8859_9)),
+            TableA3::IsoIec8859_10 => Some(Charset::Encoding(encoding_rs::ISO_8859_10)),
+            TableA3::IsoIec8859_11 => Some(Charset::Transcoder(iso_8859_11)),
+            TableA3::IsoIec8859_13 => Some(Charset::Encoding(encoding_rs::ISO_8859_13)),
+            TableA3::IsoIec8859_14 => Some(Charset::Encoding(encoding_rs::ISO_8859_14)),
+            TableA3::IsoIec8859_15 => Some(Charset::Encoding(encoding_rs::ISO_8859_15)),
+            TableA3::Big5subsetOfIsoIec10646 => Some(Charset::Encoding(encoding_rs::BIG5)),
+            TableA3::Gb2312_1980 => Some(Charset::Encoding(encoding_rs::GBK)),
+            TableA3::Utf8encodingOfIsoIec10646 => Some(Charset::Encoding(encoding_rs::UTF_8)),
             _ => None,
         }
     }
@@ -82,7 +152,7 @@ impl TryFrom<u8> for TableA3 {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum TableA4 {
     IsoIec8859_1,
     IsoIec8859_2,
@@ -107,20 +177,22 @@ impl TableA4 {
 }
 
 impl TableA4 {
-    pub fn encoding(self) -> Option<&'static encoding_rs::Encoding> {
+    pub fn charset(self) -> Option<Charset> {
         match self {
-            TableA4::IsoIec8859_1 => Some(encoding_rs::UTF_8),
-            TableA4::IsoIec8859_2 => Some(encoding_rs::ISO_8859_2),
-            TableA4::IsoIec8859_3 => Some(encoding_rs::ISO_8859_3),
-            TableA4::IsoIec8859_4 => Some(encoding_rs::ISO_8859_4),
-            TableA4::IsoIec8859_5 => Some(encoding_rs::ISO_8859_5),
-            TableA4::IsoIec8859_6 => Some(encoding_rs::ISO_8859_6),
-            TableA4::IsoIec8859_7 => Some(encoding_rs::ISO_8859_7),
-            TableA4::IsoIec8859_8 => Some(encoding_rs::ISO_8859_8),
-            TableA4::IsoIec8859_10 => Some(encoding_rs::ISO_8859_10),
-            TableA4::IsoIec8859_13 => Some(encoding_rs::ISO_8859_13),
-            TableA4::IsoIec8859_14 => Some(encoding_rs::ISO_8859_14),
-            TableA4::IsoIec8859_15 => Some(encoding_rs::ISO_8859_15),
+            TableA4::IsoIec8859_1 => Some(Charset::Transcoder(iso_8859_1)),
+            TableA4::IsoIec8859_2 => Some(Charset::Encoding(encoding_rs::ISO_8859_2)),
+            TableA4::IsoIec8859_3 => Some(Charset::Encoding(encoding_rs::ISO_8859_3)),
+            TableA4::IsoIec8859_4 => Some(Charset::Encoding(encoding_rs::ISO_8859_4)),
+            TableA4::IsoIec8859_5 => Some(Charset::Encoding(encoding_rs::ISO_8859_5)),
+            TableA4::IsoIec8859_6 => Some(Charset::Encoding(encoding_rs::ISO_8859_6)),
+            TableA4::IsoIec8859_7 => Some(Charset::Encoding(encoding_rs::ISO_8859_7)),
+            TableA4::IsoIec8859_8 => Some(Charset::Encoding(encoding_rs::ISO_8859_8)),
+            TableA4::IsoIec8859_9 => Some(Charset::Transcoder(iso_8859_9)),
+            TableA4::IsoIec8859_10 => Some(Charset::Encoding(encoding_rs::ISO_8859_10)),
+            TableA4::IsoIec8859_11 => Some(Charset::Transcoder(iso_8859_11)),
+            TableA4::IsoIec8859_13 => Some(Charset::Encoding(encoding_rs::ISO_8859_13)),
+            TableA4::IsoIec8859_14 => Some(Charset::Encoding(encoding_rs::ISO_8859_14)),
+            TableA4::IsoIec8859_15 => Some(Charset::Encoding(encoding_rs::ISO_8859_15)),
             _ => None,
         }
     }
@@ -164,10 +236,64 @@ impl<'buf> TryFrom<&'buf [u8]> for TableA4 {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+/// ETSI EN 300 468 V1.15.1 (Table A.3, 0x1F)
+///
+/// encoding_type_id (ETSI TS 101 162) following the 0x1F first byte;
+/// UTF-8 / ISO/IEC 6937 ids mirror first byte values
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum EncodingTypeId {
+    /// 0x00 - default character table
+    IsoIec6937,
+    /// 0x15
+    Utf8encodingOfIsoIec10646,
+
+    Reserved(u8),
+}
+
+impl EncodingTypeId {
+    const SYNC_BYTE: u8 = 0x1F;
+
+    pub fn charset(self) -> Option<Charset> {
+        match self {
+            EncodingTypeId::IsoIec6937 => AnnexA2::Default.charset(),
+            EncodingTypeId::Utf8encodingOfIsoIec10646 => {
+                Some(Charset::Encoding(encoding_rs::UTF_8))
+            }
+            EncodingTypeId::Reserved(..) => None,
+        }
+    }
+}
+
+impl<'buf> TryFrom<&'buf [u8]> for EncodingTypeId {
+    type Error = Error;
+
+    fn try_from(buf: &'buf [u8]) -> Result<Self, self::Error> {
+        if buf.len() < 2 {
+            return Err(Error::new(ErrorKind::AnnexA2EncodingTypeIdBuf(
+                buf.len(),
+                2,
+            )));
+        }
+
+        let (b1, b2) = (buf[0], buf[1]);
+
+        if b1 != Self::SYNC_BYTE {
+            return Err(Error::new(ErrorKind::AnnexA2TableA3Unexpected(b1)));
+        }
+
+        Ok(match b2 {
+            0x00 => EncodingTypeId::IsoIec6937,
+            0x15 => EncodingTypeId::Utf8encodingOfIsoIec10646,
+            _ => EncodingTypeId::Reserved(b2),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum AnnexA2 {
     A3(TableA3),
     A4(TableA4),
+    EncodingTypeId(EncodingTypeId),
 
     Reserved(u8),
     Zero,
@@ -177,11 +303,12 @@ pub enum AnnexA2 {
 
 /// ETSI EN 300 468 V1.15.1
 impl AnnexA2 {
-    fn encoding(self) -> Option<&'static encoding_rs::Encoding> {
+    fn charset(self) -> Option<Charset> {
         match self {
-            AnnexA2::A3(a3) => a3.encoding(),
-            AnnexA2::A4(a4) => a4.encoding(),
-            AnnexA2::Default => Some(encoding_rs::UTF_8),
+            AnnexA2::A3(a3) => a3.charset(),
+            AnnexA2::A4(a4) => a4.charset(),
+            AnnexA2::EncodingTypeId(id) => id.charset(),
+            AnnexA2::Default => Some(Charset::Transcoder(iso_6937::transcode)),
             AnnexA2::Reserved(..) => None,
             AnnexA2::Zero => None,
         }
@@ -189,44 +316,165 @@ impl AnnexA2 {
 
     // TODO: maybe use "encoding" (rust-encoding) crate?
     pub fn decode<'buf>(src_buf: &'buf [u8], dst_str: &'buf mut str) -> Result<AnnexA2, Error> {
+        Self::decode_with(src_buf, |encoding, src_buf| {
+            let mut decoder = encoding.new_decoder();
+
+            let (_, _, _, had_errors) = decoder.decode_to_str(src_buf, dst_str, false);
+
+            had_errors
+        })
+    }
+
+    /// decode into owned string
+    pub fn decode_to_string(src_buf: &[u8]) -> Result<String, Error> {
+        let mut dst = String::new();
+        Self::decode_into(src_buf, &mut dst)?;
+        Ok(dst)
+    }
+
+    /// clear destination and decode into it;
+    /// destination allocation is reused
+    pub fn decode_into(src_buf: &[u8], dst: &mut String) -> Result<AnnexA2, Error> {
+        dst.clear();
+
+        Self::decode_with(src_buf, |encoding, src_buf| {
+            let mut decoder = encoding.new_decoder();
+
+            if let Some(sz) = decoder.max_utf8_buffer_length(src_buf.len()) {
+                dst.reserve(sz);
+            }
+
+            let (_, _, had_errors) = decoder.decode_to_string(src_buf, dst, true);
+
+            had_errors
+        })
+    }
+
+    /// resolve character table and strip control codes;
+    /// decode callback returns had-errors
+    fn decode_with<F>(src_buf: &[u8], decode: F) -> Result<AnnexA2, Error>
+    where
+        F: FnOnce(&'static encoding_rs::Encoding, &[u8]) -> bool,
+    {
         let a2 = AnnexA2::try_from(src_buf)?;
 
         let src_buf = &src_buf[a2.sz()..];
 
-        let encoding = match a2.encoding() {
-            Some(encoding) => encoding,
+        let charset = match a2.charset() {
+            Some(charset) => charset,
             None => return Err(Error::new(ErrorKind::AnnexA2UnsupportedEncoding)),
         };
 
-        let mut decoder = encoding.new_decoder();
-
-        let (result, _, _, had_errors) = decoder.decode_to_str(src_buf, dst_str, false);
+        // intermediate buffers are sized from the input:
+        // stripping never grows it
+        let mut buf = vec![0u8; src_buf.len()];
+        let src_buf = if a2.is_multi_byte() {
+            src_buf
+        } else {
+            control_codes_strip(src_buf, &mut buf, a2.is_utf8())
+        };
 
-        match result {
-            encoding_rs::CoderResult::InputEmpty => {
-                // We have consumed the current input buffer
+        // UTF-8 worst case is 3 bytes per BMP character
+        let mut buf_utf8 = Vec::new();
+        let (encoding, src_buf) = match charset {
+            Charset::Encoding(encoding) => (encoding, src_buf),
+            Charset::Transcoder(transcode) => {
+                buf_utf8.resize(src_buf.len() * 3, 0);
+                let n = transcode(src_buf, &mut buf_utf8)?;
+                (encoding_rs::UTF_8, &buf_utf8[..n])
             }
-            encoding_rs::CoderResult::OutputFull => {}
-        }
+        };
 
-        if had_errors {
+        if decode(encoding, src_buf) {
             Err(Error::new(ErrorKind::AnnexA2Decode))
         } else {
             Ok(a2)
         }
     }
 
+    /// control codes are UTF-8 encoded
+    #[inline(always)]
+    fn is_utf8(self) -> bool {
+        matches!(
+            self,
+            AnnexA2::A3(TableA3::Utf8encodingOfIsoIec10646)
+                | AnnexA2::EncodingTypeId(EncodingTypeId::Utf8encodingOfIsoIec10646)
+        )
+    }
+
+    /// two-byte / multi-byte character tables;
+    /// 0x80...0x9F are valid (trail) bytes there
+    #[inline(always)]
+    fn is_multi_byte(self) -> bool {
+        matches!(
+            self,
+            AnnexA2::A3(TableA3::IsoIec10646)
+                | AnnexA2::A3(TableA3::KSX10012004)
+                | AnnexA2::A3(TableA3::Gb2312_1980)
+                | AnnexA2::A3(TableA3::Big5subsetOfIsoIec10646)
+        )
+    }
+
     // sz to skip in buffer
     fn sz(self) -> usize {
         match self {
             AnnexA2::A3(..) => 1,
             AnnexA2::A4(..) => 3,
+            AnnexA2::EncodingTypeId(..) => 2,
             AnnexA2::Default => 0,
             _ => 0,
         }
     }
 }
 
+/// ETSI EN 300 468 V1.15.1 (Table A.1)
+///
+/// Strip/translate control codes before charset decoding:
+/// 0x86/0x87 - character emphasis on/off - dropped;
+/// 0x8A - CR/LF - translated into newline;
+/// 0x80...0x9F - reserved/user defined - dropped.
+///
+/// For UTF-8 the same codes are encoded as U+E080...U+E09F.
+fn control_codes_strip<'a>(src: &[u8], dst: &'a mut [u8], is_utf8: bool) -> &'a [u8] {
+    let mut i = 0;
+    let mut n = 0;
+
+    while i < src.len() && n < dst.len() {
+        let code = if is_utf8 {
+            match src[i..] {
+                [0xEE, 0x82, b, ..] if (0x80..=0x9F).contains(&b) => {
+                    i += 3;
+                    Some(b)
+                }
+                _ => None,
+            }
+        } else {
+            match src[i] {
+                b @ 0x80..=0x9F => {
+                    i += 1;
+                    Some(b)
+                }
+                _ => None,
+            }
+        };
+
+        match code {
+            Some(0x8A) => {
+                dst[n] = b'\n';
+                n += 1;
+            }
+            Some(..) => {}
+            None => {
+                dst[n] = src[i];
+                n += 1;
+                i += 1;
+            }
+        }
+    }
+
+    &dst[..n]
+}
+
 impl<'buf> TryFrom<&'buf [u8]> for AnnexA2 {
     type Error = Error;
 
@@ -240,9 +488,9 @@ impl<'buf> TryFrom<&'buf [u8]> for AnnexA2 {
 
             0x20..=0xFF => AnnexA2::Default,
 
-            0x01..=0x07 | 0x09..=0x0B | 0x11..=0x15 | 0x1F => {
-                AnnexA2::A3(TableA3::try_from(buf[0])?)
-            }
+            0x01..=0x07 | 0x09..=0x0B | 0x11..=0x15 => AnnexA2::A3(TableA3::try_from(buf[0])?),
+
+            0x1F => AnnexA2::EncodingTypeId(EncodingTypeId::try_from(buf)?),
 
             0x10 => AnnexA2::A4(TableA4::try_from(buf)?),
 
@@ -255,6 +503,132 @@ impl<'buf> TryFrom<&'buf [u8]> for AnnexA2 {
 
 #[cfg(test)]
 mod tests {
+    use super::AnnexA2;
+    use crate::error::{Error, Kind as ErrorKind};
+
+    fn decode(src: &[u8]) -> String {
+        let mut dst_buf = [0u8; 256];
+        let dst_str = core::str::from_utf8_mut(&mut dst_buf).unwrap();
+
+        AnnexA2::decode(src, dst_str).unwrap();
+
+        dst_str.trim_end_matches('\0').to_string()
+    }
+
+    #[test]
+    fn decode_plain() {
+        assert_eq!(decode(b"BBC One"), "BBC One");
+    }
+
+    #[test]
+    fn decode_to_string() {
+        assert_eq!(AnnexA2::decode_to_string(b"BBC One").unwrap(), "BBC One");
+        assert_eq!(
+            AnnexA2::decode_to_string(b"\x05\xDDstanbul").unwrap(),
+            "İstanbul"
+        );
+    }
+
+    #[test]
+    fn decode_into() {
+        let mut dst = String::from("previous");
+
+        AnnexA2::decode_into(b"\x86BBC\x87 One", &mut dst).unwrap();
+        assert_eq!(dst, "BBC One");
+
+        AnnexA2::decode_into(b"Espa\xC4na", &mut dst).unwrap();
+        assert_eq!(dst, "España");
+    }
+
     #[test]
-    fn decode() {}
+    fn decode_long() {
+        // e.g. concatenated extended event items
+        let src = b"\xC2e".repeat(300);
+        assert_eq!(AnnexA2::decode_to_string(&src).unwrap(), "é".repeat(300));
+
+        let mut src = vec![0x15];
+        src.extend_from_slice("é".repeat(300).as_bytes());
+        src.extend_from_slice(b"\xEE\x82\x8A!");
+        assert_eq!(
+            AnnexA2::decode_to_string(&src).unwrap(),
+            "é".repeat(300) + "\n!"
+        );
+    }
+
+    #[test]
+    fn decode_default_iso_6937() {
+        assert_eq!(decode(b"T\xC2el\xC2e \xC1a la carte"), "Télé à la carte");
+        assert_eq!(decode(b"Espa\xC4na"), "España");
+    }
+
+    #[test]
+    fn decode_control_codes() {
+        assert_eq!(decode(b"\x86BBC\x87 One\x8Anews"), "BBC One\nnews");
+    }
+
+    #[test]
+    fn decode_control_codes_iso_8859_5() {
+        // 0x01 - ISO/IEC 8859-5; 0xB1 0xB1 0xC1 - "ББС"
+        assert_eq!(decode(b"\x01\x86\xB1\xB1\xC1\x87\x8A1"), "ББС\n1");
+    }
+
+    #[test]
+    fn decode_iso_8859_1() {
+        // 0x10 0x00 0x01 - ISO/IEC 8859-1
+        assert_eq!(decode(b"\x10\x00\x01Caf\xE9 \xA9"), "Café ©");
+    }
+
+    #[test]
+    fn decode_iso_8859_9() {
+        // 0x05 - ISO/IEC 8859-9
+        assert_eq!(decode(b"\x05\xDDstanbul Ma\xE7 \xDEov"), "İstanbul Maç Şov");
+        // 0x10 0x00 0x09 - ISO/IEC 8859-9
+        assert_eq!(decode(b"\x10\x00\x09\xFEu\xF0"), "şuğ");
+    }
+
+    #[test]
+    fn decode_iso_8859_7() {
+        // 0x03 - ISO/IEC 8859-7
+        assert_eq!(decode(b"\x03\xC5\xD1\xD4"), "ΕΡΤ");
+        // 0x10 0x00 0x07 - ISO/IEC 8859-7
+        assert_eq!(decode(b"\x10\x00\x07\xC5\xD1\xD4"), "ΕΡΤ");
+    }
+
+    #[test]
+    fn decode_iso_8859_11() {
+        // 0x07 - ISO/IEC 8859-11
+        assert_eq!(decode(b"\x07\xA1\xDF"), "\u{0E01}\u{0E3F}");
+    }
+
+    #[test]
+    fn decode_encoding_type_id() {
+        // 0x1F 0x15 - UTF-8
+        assert_eq!(decode(b"\x1F\x15caf\xC3\xA9"), "café");
+        // 0x1F 0x00 - default table
+        assert_eq!(decode(b"\x1F\x00BBC One"), "BBC One");
+    }
+
+    #[test]
+    fn err_decode_encoding_type_id() {
+        let mut dst_buf = [0u8; 256];
+        let dst_str = core::str::from_utf8_mut(&mut dst_buf).unwrap();
+
+        assert_eq!(
+            AnnexA2::decode(b"\x1F", dst_str).err(),
+            Some(Error::new(ErrorKind::AnnexA2EncodingTypeIdBuf(1, 2)))
+        );
+        assert_eq!(
+            AnnexA2::decode(b"\x1F\x01abc", dst_str).err(),
+            Some(Error::new(ErrorKind::AnnexA2UnsupportedEncoding))
+        );
+    }
+
+    #[test]
+    fn decode_control_codes_utf8() {
+        // 0x15 - UTF-8; U+E086/U+E087 - emphasis on/off
+        assert_eq!(
+            decode(b"\x15\xEE\x82\x86news\xEE\x82\x87\xEE\x82\x8A\xC3\xA9"),
+            "news\né"
+        );
+    }
 }
@@ -1,8 +1,9 @@
-use std::time::Duration;
+use core::time::Duration;
 
 use chrono::prelude::*;
 
 use crate::error::{Error, Kind as ErrorKind};
+use crate::reader::be_u16;
 use crate::result::Result;
 
 /// simple binary-coded decimal converter
@@ -38,7 +39,7 @@ pub fn from_bytes_into_date_time_utc(buf: &[u8]) -> Result<DateTime<Utc>> {
         return Err(Error::new(ErrorKind::AnnexCBuf(buf.len(), 5)));
     }
 
-    let mjd = (u16::from(buf[0]) << 8) | u16::from(buf[1]);
+    let mjd = be_u16(buf);
     let (hh, mm, ss) = (
         u32::from(bcd(buf[2])),
         u32::from(bcd(buf[3])),
@@ -47,7 +48,7 @@ pub fn from_bytes_into_date_time_utc(buf: &[u8]) -> Result<DateTime<Utc>> {
 
     let (y, m, d) = mjb_to_ymd(mjd);
 
-    Ok(Utc.ymd(y, m, d).and_hms(hh, mm, ss))
+    Ok(Utc.with_ymd_and_hms(y, m, d, hh, mm, ss).unwrap())
 }
 
 #[allow(dead_code)]
@@ -81,8 +82,8 @@ mod tests {
 
         assert!(dt.is_ok());
         assert_eq!(
-            dt.unwrap_or(Utc::now()),
-            Utc.ymd(2016, 11, 21).and_hms(15, 00, 00)
+            dt.unwrap(),
+            Utc.with_ymd_and_hms(2016, 11, 21, 15, 00, 00).unwrap()
         );
     }
 
@@ -0,0 +1,209 @@
+//! AAC sample rate and channel configuration from frame header
+//!
+//! ISO/IEC 13818-7 (ADTS), ISO/IEC 14496-3 (1.6.2.1, 1.7.3 LOAS/LATM)
+use crate::bits::Bits;
+#[cfg(feature = "std")]
+use crate::stream_type::StreamType;
+
+/// sampling-frequency-index
+const SAMPLE_RATES: [u32; 13] = [
+    96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350,
+];
+
+/// AAC audio parameters
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AudioParams {
+    /// MPEG-4 audio-object-type (2 - AAC-LC)
+    pub object_type: u8,
+    /// output sample rate; SBR rate for explicitly signaled HE-AAC
+    pub sample_rate: u32,
+    /// channel-configuration;
+    /// 0 - defined in program config element
+    pub channel_configuration: u8,
+}
+
+impl AudioParams {
+    /// first frame of AAC (ADTS) / LOAS elementary stream chunk
+    #[cfg(feature = "std")]
+    pub(crate) fn from_es(stream_type: &StreamType, es: &[u8]) -> Option<AudioParams> {
+        match stream_type {
+            StreamType::AAC => Self::from_adts(es),
+            StreamType::MPEG4LOAS => Self::from_loas(es),
+            _ => None,
+        }
+    }
+
+    /// output channels count
+    pub fn channels(&self) -> Option<u8> {
+        match self.channel_configuration {
+            1..=6 => Some(self.channel_configuration),
+            7 => Some(8),
+            _ => None,
+        }
+    }
+
+    pub fn object_type_name(&self) -> &'static str {
+        match self.object_type {
+            1 => "AAC-Main",
+            2 => "AAC-LC",
+            3 => "AAC-SSR",
+            4 => "AAC-LTP",
+            5 => "HE-AAC",
+            29 => "HE-AACv2",
+            _ => "AAC",
+        }
+    }
+
+    #[inline(always)]
+    fn sample_rate(b: &mut Bits) -> Option<u32> {
+        match b.u(4)? {
+            0xF => b.u(24),
+            idx => SAMPLE_RATES.get(idx as usize).copied(),
+        }
+    }
+
+    /// ADTS frame header at the first syncword
+    pub fn from_adts(buf: &[u8]) -> Option<AudioParams> {
+        // syncword 0xFFF, layer 00
+        let pos = buf
+            .windows(2)
+            .position(|w| w[0] == 0xFF && (w[1] & 0xF6) == 0xF0)?;
+        let mut b = Bits::new(&buf[pos..]);
+
+        // syncword, id, layer, protection-absent
+        b.skip(16)?;
+        let profile = b.u(2)?;
+        let sample_rate = SAMPLE_RATES.get(b.u(4)? as usize).copied()?;
+        // private-bit
+        b.skip(1)?;
+        let channel_configuration = b.u(3)?;
+
+        Some(AudioParams {
+            object_type: (profile + 1) as u8,
+            sample_rate,
+            channel_configuration: channel_configuration as u8,
+        })
+    }
+
+    /// first LOAS AudioSyncStream frame with StreamMuxConfig
+    pub fn from_loas(buf: &[u8]) -> Option<AudioParams> {
+        // syncword 0x2B7
+        let mut pos = buf
+            .windows(2)
+            .position(|w| w[0] == 0x56 && (w[1] & 0xE0) == 0xE0)?;
+
+        loop {
+            let hdr = buf.get(pos..pos + 3)?;
+            if hdr[0] != 0x56 || (hdr[1] & 0xE0) != 0xE0 {
+                return None;
+            }
+
+            let sz = ((usize::from(hdr[1]) & 0x1F) << 8) | usize::from(hdr[2]);
+            let frame = buf.get(pos + 3..(pos + 3 + sz).min(buf.len()))?;
+
+            let mut b = Bits::new(frame);
+            // use-same-stream-mux
+            if !b.flag()? {
+                return Self::stream_mux_config(&mut b);
+            }
+
+            pos += 3 + sz;
+        }
+    }
+
+    /// LatmGetValue
+    #[inline(always)]
+    fn latm_value(b: &mut Bits) -> Option<u32> {
+        let bytes = b.u(2)? + 1;
+        let mut v = 0;
+        for _ in 0..bytes {
+            v = (v << 8) | b.u(8)?;
+        }
+        Some(v)
+    }
+
+    /// StreamMuxConfig of the first program / layer
+    fn stream_mux_config(b: &mut Bits) -> Option<AudioParams> {
+        let version = b.flag()?;
+        if version {
+            if b.flag()? {
+                // audio-mux-version-a 1 is reserved
+                return None;
+            }
+            // tara-buffer-fullness
+            Self::latm_value(b)?;
+        }
+
+        // all-streams-same-time-framing, num-sub-frames, num-program, num-layer
+        b.skip(1 + 6 + 4 + 3)?;
+
+        if version {
+            // asc-len
+            Self::latm_value(b)?;
+        }
+
+        Self::audio_specific_config(b)
+    }
+
+    /// AudioSpecificConfig
+    fn audio_specific_config(b: &mut Bits) -> Option<AudioParams> {
+        let object_type = match b.u(5)? {
+            31 => 32 + b.u(6)?,
+            object_type => object_type,
+        };
+        let mut sample_rate = Self::sample_rate(b)?;
+        let channel_configuration = b.u(4)?;
+
+        // explicit SBR / PS signaling
+        if object_type == 5 || object_type == 29 {
+            sample_rate = Self::sample_rate(b)?;
+        }
+
+        Some(AudioParams {
+            object_type: object_type as u8,
+            sample_rate,
+            channel_configuration: channel_configuration as u8,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AudioParams;
+
+    #[test]
+    fn adts() {
+        // junk, AAC-LC 48kHz stereo
+        let buf = [0x00, 0xFF, 0xF1, 0x4C, 0x80, 0x2E, 0x7F, 0xFC];
+
+        let p = AudioParams::from_adts(&buf).unwrap();
+
+        assert_eq!(
+            (p.object_type, p.sample_rate, p.channel_configuration),
+            (2, 48000, 2)
+        );
+        assert_eq!(p.object_type_name(), "AAC-LC");
+        assert_eq!(p.channels(), Some(2));
+
+        assert_eq!(AudioParams::from_adts(&[0xFF, 0xF1]), None);
+    }
+
+    #[test]
+    fn loas() {
+        // use-same-stream-mux frame
+        let same_mux = [0x56, 0xE0, 0x01, 0x80];
+        // StreamMuxConfig v0: AAC-LC 48kHz stereo
+        let config = [0x56, 0xE0, 0x04, 0x20, 0x00, 0x11, 0x90];
+        let buf = [&same_mux[..], &config].concat();
+
+        let p = AudioParams::from_loas(&buf).unwrap();
+
+        assert_eq!(
+            (p.object_type, p.sample_rate, p.channel_configuration),
+            (2, 48000, 2)
+        );
+
+        // no StreamMuxConfig
+        assert_eq!(AudioParams::from_loas(&same_mux), None);
+    }
+}
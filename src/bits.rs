@@ -0,0 +1,64 @@
+//! bitstream reader
+
+/// MSB-first bit reader;
+/// exp-golomb codes for H.264/H.265 RBSP
+pub(crate) struct Bits<'buf> {
+    buf: &'buf [u8],
+    /// bit position
+    pos: usize,
+}
+
+impl<'buf> Bits<'buf> {
+    #[inline(always)]
+    pub(crate) fn new(buf: &'buf [u8]) -> Bits<'buf> {
+        Bits { buf, pos: 0 }
+    }
+
+    #[inline(always)]
+    pub(crate) fn flag(&mut self) -> Option<bool> {
+        let b = self.buf.get(self.pos / 8)?;
+        let v = (b >> (7 - self.pos % 8)) & 1 != 0;
+        self.pos += 1;
+        Some(v)
+    }
+
+    /// u(n), n <= 32
+    #[inline(always)]
+    pub(crate) fn u(&mut self, n: usize) -> Option<u32> {
+        let mut v = 0u32;
+        for _ in 0..n {
+            v = (v << 1) | u32::from(self.flag()?);
+        }
+        Some(v)
+    }
+
+    #[inline(always)]
+    pub(crate) fn skip(&mut self, n: usize) -> Option<()> {
+        if self.pos + n > self.buf.len() * 8 {
+            return None;
+        }
+        self.pos += n;
+        Some(())
+    }
+
+    /// ue(v)
+    #[inline(always)]
+    pub(crate) fn ue(&mut self) -> Option<u32> {
+        let mut zeros = 0;
+        while !self.flag()? {
+            zeros += 1;
+            if zeros > 31 {
+                return None;
+            }
+        }
+        Some(((1u64 << zeros) - 1 + u64::from(self.u(zeros)?)) as u32)
+    }
+
+    /// se(v)
+    #[inline(always)]
+    pub(crate) fn se(&mut self) -> Option<i32> {
+        let k = i64::from(self.ue()?);
+        let v = if k % 2 == 1 { (k + 1) / 2 } else { -(k / 2) };
+        Some(v as i32)
+    }
+}
@@ -0,0 +1,238 @@
+use alloc::vec::Vec;
+
+use crate::packet::Packet;
+use crate::result::Result;
+
+/// adaptation-field-length byte offset
+const ADAPTATION_POS: usize = 4;
+
+/// adaptation flags byte offset
+const ADAPTATION_FLAGS_POS: usize = 5;
+
+const DISCONTINUITY_INDICATOR: u8 = 0b1000_0000;
+
+/// adaptation-field-control: adaptation-field only, no payload
+const ADAPTATION_ONLY: u8 = 0b0010_0000;
+
+/// remux / recording continuity-counter repair:
+/// packet after cc gap gets adaptation discontinuity-indicator so
+/// downstream decoders resync instead of reporting cc errors.
+///
+/// packet with adaptation flags is flagged in place;
+/// otherwise adaptation-only (stuffing) packet of the same PID
+/// with discontinuity-indicator and cc right before the packet one is
+/// emitted first - payload is never moved.
+///
+/// on PCR PID discontinuity-indicator also marks time-base discontinuity;
+/// null and transport-error packets pass unchanged.
+///
+/// opt-in stage placed before writer, e.g. after `PidRemapper`
+pub struct CcRepair {
+    /// PID -> last cc
+    last: Vec<(u16, u8)>,
+
+    /// repaired cc gaps
+    repaired: u64,
+}
+
+impl Default for CcRepair {
+    fn default() -> Self {
+        CcRepair::new()
+    }
+}
+
+impl CcRepair {
+    pub fn new() -> CcRepair {
+        CcRepair {
+            last: Vec::new(),
+            repaired: 0,
+        }
+    }
+
+    /// cc gaps flagged so far
+    #[inline(always)]
+    pub fn repaired(&self) -> u64 {
+        self.repaired
+    }
+
+    /// forget per-PID cc, e.g. on output switch
+    pub fn reset(&mut self) {
+        self.last.clear();
+    }
+
+    /// previous cc of pid replaced with cc
+    fn swap(&mut self, pid: u16, cc: u8) -> Option<u8> {
+        match self.last.iter_mut().find(|(p, _)| *p == pid) {
+            Some(last) => Some(core::mem::replace(&mut last.1, cc)),
+            None => {
+                self.last.push((pid, cc));
+                None
+            }
+        }
+    }
+
+    /// adaptation-only packet with discontinuity-indicator
+    /// carrying cc expected right before pkt
+    fn stuffing(raw: &[u8], pkt: &Packet) -> [u8; Packet::SZ] {
+        let cc = if pkt.got_payload() {
+            pkt.cc().wrapping_sub(1) & 0x0F
+        } else {
+            pkt.cc()
+        };
+
+        let mut buf = [0xFFu8; Packet::SZ];
+        buf[0] = Packet::SYNC_BYTE;
+        // keep PID, drop tei, pusi and priority
+        buf[1] = raw[1] & 0x1F;
+        buf[2] = raw[2];
+        buf[3] = ADAPTATION_ONLY | cc;
+        buf[ADAPTATION_POS] = (Packet::SZ - ADAPTATION_POS - 1) as u8;
+        buf[ADAPTATION_FLAGS_POS] = DISCONTINUITY_INDICATOR;
+
+        buf
+    }
+
+    /// check ts-packet cc;
+    /// out is called for every packet to be written (one or two)
+    pub fn repair<F>(&mut self, raw: &[u8], mut out: F) -> Result<()>
+    where
+        F: FnMut(&[u8]),
+    {
+        let pkt = Packet::new(raw)?;
+        let pid = pkt.pid();
+
+        // null packets cc is undefined
+        if pid.is_null() || pkt.tei() {
+            out(raw);
+            return Ok(());
+        }
+
+        let flagged = match pkt.adaptation() {
+            Some(adapt) => adapt?.discontinuity_indicator(),
+            None => false,
+        };
+
+        let last = self.swap(u16::from(pid), pkt.cc());
+
+        let gap = match last {
+            Some(last) => !flagged && pkt.validate_cc(last).is_err(),
+            None => false,
+        };
+
+        if !gap {
+            out(raw);
+            return Ok(());
+        }
+
+        self.repaired += 1;
+
+        // flags byte present
+        if pkt.adaptation().is_some() && raw[ADAPTATION_POS] > 0 {
+            let mut buf = [0u8; Packet::SZ];
+            buf.copy_from_slice(raw);
+            buf[ADAPTATION_FLAGS_POS] |= DISCONTINUITY_INDICATOR;
+
+            out(&buf);
+        } else {
+            out(&Self::stuffing(raw, &pkt));
+            out(raw);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CcRepair;
+    use crate::packet::Packet;
+    use crate::synthetic::PacketBuilder;
+
+    fn repair(r: &mut CcRepair, raw: &[u8]) -> Vec<Vec<u8>> {
+        let mut out = Vec::new();
+        r.repair(raw, |pkt| out.push(pkt.to_vec())).unwrap();
+        out
+    }
+
+    /// every packet continues previous one or is flagged
+    fn assert_continuous(pkts: &[Vec<u8>]) {
+        let mut last: Option<u8> = None;
+
+        for raw in pkts {
+            let pkt = Packet::new(raw).unwrap();
+            let flagged = pkt
+                .adaptation()
+                .is_some_and(|a| a.unwrap().discontinuity_indicator());
+
+            if let (Some(last), false) = (last, flagged) {
+                assert_eq!(pkt.validate_cc(last), Ok(()));
+            }
+            last = Some(pkt.cc());
+        }
+    }
+
+    #[test]
+    fn stuffing_before_payload() {
+        let mut r = CcRepair::new();
+        let mut out = Vec::new();
+
+        // 0 1 _ 3
+        for cc in [0, 1, 3] {
+            let raw = PacketBuilder::new(0x100)
+                .cc(cc)
+                .payload(&[0xAA; 184])
+                .build();
+            out.extend(repair(&mut r, &raw));
+        }
+
+        assert_eq!(out.len(), 4);
+        assert_eq!(r.repaired(), 1);
+
+        let stuffing = Packet::new(&out[2]).unwrap();
+        assert_eq!(u16::from(stuffing.pid()), 0x100);
+        assert!(!stuffing.got_payload());
+        assert_eq!(stuffing.cc(), 2);
+        assert!(stuffing
+            .adaptation()
+            .unwrap()
+            .unwrap()
+            .discontinuity_indicator());
+
+        // payload untouched
+        assert_eq!(out[3][4..], [0xAA; 184]);
+
+        assert_continuous(&out);
+    }
+
+    #[test]
+    fn flag_in_place() {
+        let mut r = CcRepair::new();
+
+        let raw = PacketBuilder::new(0x100).cc(5).payload(&[0x00]).build();
+        assert_eq!(repair(&mut r, &raw).len(), 1);
+
+        // stuffed via adaptation-field with flags byte
+        let raw = PacketBuilder::new(0x100).cc(9).payload(&[0x00]).build();
+        let out = repair(&mut r, &raw);
+        assert_eq!(out.len(), 1);
+
+        let pkt = Packet::new(&out[0]).unwrap();
+        assert_eq!(pkt.cc(), 9);
+        assert!(pkt.adaptation().unwrap().unwrap().discontinuity_indicator());
+        assert_eq!(out[0][6..], raw[6..]);
+
+        // continues flagged packet; duplicate is not a gap
+        for cc in [10, 10, 11] {
+            let raw = PacketBuilder::new(0x100).cc(cc).payload(&[0x00]).build();
+            assert_eq!(repair(&mut r, &raw), [raw.to_vec()]);
+        }
+
+        // other PID and null packets are independent
+        let raw = PacketBuilder::new(0x101).cc(7).payload(&[0x00]).build();
+        assert_eq!(repair(&mut r, &raw), [raw.to_vec()]);
+        let raw = PacketBuilder::new(0x1FFF).cc(3).payload(&[0x00]).build();
+        assert_eq!(repair(&mut r, &raw), [raw.to_vec()]);
+
+        assert_eq!(r.repaired(), 1);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::descriptor::{Descriptor, Tag, TagDVB};
+use crate::stream_type::StreamType;
+
+/// DVB extension descriptor tag-extension
+const TAG_EXTENSION_AC4: u8 = 0x15;
+
+/// elementary stream codec;
+/// stream-type refined by registration / DVB descriptors
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Codec {
+    MPEG1Video,
+    MPEG2Video,
+    MPEG4Video,
+    H264,
+    H265,
+
+    /// MPEG-1/2 layer I, II, III
+    MPEGAudio,
+    /// ADTS
+    AAC,
+    /// LATM/LOAS
+    AACLATM,
+    AC3,
+    EAC3,
+    AC4,
+    DTS,
+    TrueHD,
+    Opus,
+
+    Teletext,
+    DVBSubtitle,
+    SCTE35,
+
+    /// private or not recognized stream-type
+    Other(StreamType),
+}
+
+impl Codec {
+    /// registration descriptor format-identifier
+    pub fn from_format_identifier(fourcc: &[u8; 4]) -> Option<Codec> {
+        Some(match fourcc {
+            b"AC-3" => Codec::AC3,
+            b"EAC3" => Codec::EAC3,
+            b"AC-4" => Codec::AC4,
+            b"DTS1" | b"DTS2" | b"DTS3" => Codec::DTS,
+            b"Opus" => Codec::Opus,
+            b"HEVC" => Codec::H265,
+            _ => return None,
+        })
+    }
+
+    /// DVB component descriptor of private data stream
+    /// (ETSI EN 300 468 annex G)
+    pub fn from_dvb_descriptor(desc: &Descriptor) -> Option<Codec> {
+        Some(match desc.tag() {
+            Tag::DVB(TagDVB::AC3) => Codec::AC3,
+            Tag::DVB(TagDVB::EnhancedAC3) => Codec::EAC3,
+            Tag::DVB(TagDVB::DTSDescriptor) => Codec::DTS,
+            Tag::DVB(TagDVB::Teletext) => Codec::Teletext,
+            Tag::DVB(TagDVB::Subtitling) => Codec::DVBSubtitle,
+            Tag::DVB(TagDVB::Extension) if desc.buf_data().first() == Some(&TAG_EXTENSION_AC4) => {
+                Codec::AC4
+            }
+            _ => return None,
+        })
+    }
+
+    #[inline(always)]
+    pub fn is_other(&self) -> bool {
+        matches!(self, Codec::Other(..))
+    }
+}
+
+impl From<StreamType> for Codec {
+    fn from(st: StreamType) -> Self {
+        match st {
+            StreamType::MPEG1Video => Codec::MPEG1Video,
+            StreamType::H262 => Codec::MPEG2Video,
+            StreamType::MPEG4H263Video => Codec::MPEG4Video,
+            StreamType::H264 => Codec::H264,
+            StreamType::H265 => Codec::H265,
+
+            StreamType::MPEG1Audio | StreamType::MPEG2Audio => Codec::MPEGAudio,
+            StreamType::AAC => Codec::AAC,
+            StreamType::MPEG4LOAS => Codec::AACLATM,
+            StreamType::AC3 => Codec::AC3,
+            StreamType::AC3DolbyDigitalPlus | StreamType::AC3DolbyDigitalPlus16 => Codec::EAC3,
+            StreamType::DTS8 => Codec::DTS,
+            StreamType::DolbyTrueHDAudio => Codec::TrueHD,
+
+            StreamType::SCTE35 => Codec::SCTE35,
+
+            st => Codec::Other(st),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Codec;
+    use crate::descriptor::Descriptor;
+    use crate::section::TryNewer;
+    use crate::stream_type::StreamType;
+
+    #[test]
+    fn from_stream_type() {
+        assert_eq!(Codec::from(StreamType::from(0x1B)), Codec::H264);
+        assert_eq!(Codec::from(StreamType::from(0x87)), Codec::EAC3);
+        assert_eq!(
+            Codec::from(StreamType::from(0x06)),
+            Codec::Other(StreamType::MPEG2PacketizedData)
+        );
+    }
+
+    #[test]
+    fn from_dvb_descriptor() {
+        let ac4 = [0x7F, 0x02, 0x15, 0x00];
+        let ext = [0x7F, 0x02, 0x04, 0x00];
+
+        let codec = |raw: &[u8]| Codec::from_dvb_descriptor(&Descriptor::try_new(raw).unwrap());
+
+        assert_eq!(codec(&ac4), Some(Codec::AC4));
+        assert_eq!(codec(&ext), None);
+        assert_eq!(codec(&[0x6A, 0x01, 0x00]), Some(Codec::AC3));
+    }
+}
@@ -0,0 +1,29 @@
+/// MPEG-2 CRC32 (poly 0x04C11DB7, no reflection)
+pub(crate) fn crc32(buf: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+
+    for b in buf {
+        crc ^= u32::from(*b) << 24;
+
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ 0x04C1_1DB7
+            } else {
+                crc << 1
+            };
+        }
+    }
+
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::crc32;
+
+    #[test]
+    fn crc32_mpeg2() {
+        // "123456789" check value
+        assert_eq!(crc32(b"123456789"), 0x0376_E6E7);
+    }
+}
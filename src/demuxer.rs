@@ -1,20 +1,43 @@
-use std::cell::RefCell;
+use std::cell::{Ref, RefCell};
 use std::collections::HashMap;
 use std::io::{Cursor, Write};
 use std::rc::Rc;
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+
+use crate::audio_params::AudioParams;
+use crate::epg::{EpgEvent, EpgStore};
+use crate::error::{Error, Kind as ErrorKind};
+use crate::h264::{AccessUnit, AccessUnits};
 use crate::packet::Packet as TsPacket;
-use crate::pes::PES;
+use crate::pcr::PCR;
+use crate::pes::{StreamID, PES};
 use crate::pid::PID;
 use crate::result::Result;
-use crate::section::{WithHeader, WithSyntaxSection};
-use crate::subtable_id::{SubtableID, SubtableIDer};
+use crate::rtp::RTP;
+use crate::section::{
+    EITEvent, PATProgram, PMTStream, SectionAssembler, WithCRC32, WithHeader, WithSyntaxSection,
+    HEADER_MAX_PRIVATE_SECTION_LENGTH, HEADER_MAX_SECTION_LENGTH,
+};
+use crate::service::{Service, ServiceCatalog};
+use crate::stream_type::StreamType;
+use crate::subtable_id::{SubtableID, SubtableIDer, SubtableKind};
+use crate::table_id::TableID;
+use crate::video_params::VideoParams;
 use crate::{EIT, PAT, PMT, SDT};
 
 pub struct Buf(pub Cursor<Vec<u8>>);
 
 impl Buf {
+    /// default initial capacity
+    const CAPACITY: usize = 2048;
+
+    #[inline(always)]
+    fn with_capacity(capacity: usize) -> Buf {
+        Buf(Cursor::new(Vec::with_capacity(capacity)))
+    }
+
     #[inline(always)]
     fn reset(&mut self) {
         self.0.set_position(0);
@@ -30,34 +53,88 @@ impl Buf {
     pub fn sz(&self) -> usize {
         self.0.position() as usize
     }
+
+    /// written data
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0.get_ref()[..self.sz()]
+    }
 }
 
 impl Default for Buf {
     fn default() -> Self {
-        Buf(Cursor::new(Vec::with_capacity(2048)))
+        Buf::with_capacity(Buf::CAPACITY)
     }
 }
 
-pub struct Section {
-    /// parent table-id
-    table_id: SubtableID,
+/// section buffers of dropped (outdated) tables
+/// reused by new sections
+struct BufPool {
+    free: Vec<Buf>,
+
+    /// initial capacity of new buffer
+    capacity: usize,
+
+    /// max pooled buffers;
+    /// 0 - pooling disabled
+    max: usize,
+}
+
+impl BufPool {
+    #[inline(always)]
+    fn take(&mut self) -> Buf {
+        self.free
+            .pop()
+            .unwrap_or_else(|| Buf::with_capacity(self.capacity))
+    }
+
+    /// recycle sections not referenced elsewhere
+    fn put(&mut self, table: Table) {
+        for section_ref in table.sections.0 {
+            if self.free.len() >= self.max {
+                return;
+            }
+
+            if let Ok(section) = Rc::try_unwrap(section_ref) {
+                let mut buf = section.into_inner().buf;
+                buf.reset();
+                self.free.push(buf);
+            }
+        }
+    }
+}
+
+impl Default for BufPool {
+    fn default() -> Self {
+        BufPool {
+            free: Default::default(),
+            capacity: Buf::CAPACITY,
+            max: 0,
+        }
+    }
+}
 
+pub struct Section {
     /// number inside table sections
     number: u8,
 
     /// full section size with header, data, CRC
     sz: usize,
 
+    /// EIT segment-last-section-number;
+    /// None for not segmented tables
+    segment_last_section_number: Option<u8>,
+
     pub buf: Buf,
 }
 
 impl Section {
-    fn new(table_id: SubtableID, number: u8, sz: usize) -> Section {
+    fn new(number: u8, sz: usize, buf: Buf) -> Section {
         Section {
-            table_id,
             number,
             sz,
-            buf: Default::default(),
+            segment_last_section_number: None,
+            buf,
         }
     }
 
@@ -77,6 +154,12 @@ impl Section {
     fn sz_need(&self) -> usize {
         self.sz - self.buf.sz()
     }
+
+    /// raw section: header, data, CRC
+    #[inline(always)]
+    pub fn bytes(&self) -> &[u8] {
+        self.buf.as_slice()
+    }
 }
 
 type SectionRef = Rc<RefCell<Box<Section>>>;
@@ -92,8 +175,7 @@ impl Sections {
     #[inline(always)]
     fn push(&mut self, s: SectionRef) {
         self.0.push(s);
-        self.0
-            .sort_unstable_by(|a, b| a.borrow().number.cmp(&b.borrow().number));
+        self.0.sort_unstable_by_key(|s| s.borrow().number);
     }
 }
 
@@ -110,6 +192,9 @@ pub struct Table {
 }
 
 impl Table {
+    /// EIT schedule segment
+    const SEGMENT_SZ: u8 = 8;
+
     fn new(last_section_number: u8) -> Table {
         Table {
             last_section_number,
@@ -117,36 +202,156 @@ impl Table {
         }
     }
 
+    /// raw sections ordered by section-number;
+    /// borrow guard derefs to section bytes
     #[inline(always)]
-    fn done(&self) -> bool {
-        match self.sections.0.len() {
-            0 => false,
-            n => {
-                let last = (&self.sections.0[n - 1]).borrow();
-                let first = (&self.sections.0[0]).borrow();
+    pub fn sections_bytes(&self) -> impl Iterator<Item = Ref<'_, [u8]>> {
+        self.sections
+            .0
+            .iter()
+            .map(|s| Ref::map(s.borrow(), |s| s.bytes()))
+    }
 
-                first.number == 0
-                    && last.number == self.last_section_number
-                    && first.done()
-                    && last.done()
-            }
+    /// borrow all sections at once
+    /// for typed access across sections
+    #[inline(always)]
+    pub fn view(&self) -> TableView<'_> {
+        TableView(self.sections_bytes().collect())
+    }
+
+    /// all sections 0...last-section-number are received
+    #[inline(always)]
+    pub fn is_complete(&self) -> bool {
+        self.received_sections() == self.expected_sections()
+    }
+
+    /// every PMT section CRC_32 matches
+    #[inline(always)]
+    fn is_pmt_crc32_valid(&self) -> bool {
+        self.sections_bytes()
+            .all(|raw| PMT::new(&raw).is_crc32_valid())
+    }
+
+    /// fully received sections
+    #[inline(always)]
+    pub fn received_sections(&self) -> usize {
+        self.sections
+            .0
+            .iter()
+            .filter(|s| {
+                let s = s.borrow();
+                s.number <= self.last_section_number
+                    && s.number <= s.segment_last_section_number.unwrap_or(u8::MAX)
+                    && s.done()
+            })
+            .count()
+    }
+
+    /// last-section-number + 1;
+    /// segmented (EIT) - sum of segments sizes,
+    /// segment without received sections counts as 1
+    #[inline(always)]
+    pub fn expected_sections(&self) -> usize {
+        let segmented = self
+            .sections
+            .0
+            .iter()
+            .any(|s| s.borrow().segment_last_section_number.is_some());
+
+        if !segmented {
+            return usize::from(self.last_section_number) + 1;
         }
+
+        (0..=self.last_section_number / Self::SEGMENT_SZ)
+            .map(|segment| {
+                let lft = segment * Self::SEGMENT_SZ;
+
+                let segment_last = self.sections.0.iter().find_map(|s| {
+                    let s = s.borrow();
+                    s.segment_last_section_number
+                        .filter(|_| s.number / Self::SEGMENT_SZ == segment)
+                });
+
+                match segment_last {
+                    Some(segment_last) => {
+                        let rght = segment_last
+                            .clamp(lft, lft + Self::SEGMENT_SZ - 1)
+                            .min(self.last_section_number);
+
+                        usize::from(rght - lft) + 1
+                    }
+                    None => 1,
+                }
+            })
+            .sum()
+    }
+}
+
+/// borrowed table sections;
+/// per-section headers are skipped, loops are concatenated
+pub struct TableView<'t>(Vec<Ref<'t, [u8]>>);
+
+impl<'t> TableView<'t> {
+    #[inline(always)]
+    pub fn sections(&self) -> impl Iterator<Item = &[u8]> {
+        self.0.iter().map(|raw| &raw[..])
+    }
+
+    /// EIT events
+    pub fn events(&self) -> impl Iterator<Item = Result<EITEvent<'_>>> {
+        self.sections().flat_map(|raw| EIT::new(raw).events())
+    }
+
+    /// PAT programs
+    pub fn programs(&self) -> impl Iterator<Item = Result<PATProgram<'_>>> {
+        self.sections().flat_map(|raw| PAT::new(raw).programs())
+    }
+
+    /// PMT elementary streams
+    pub fn streams(&self) -> impl Iterator<Item = Result<PMTStream<'_>>> {
+        self.sections().flat_map(|raw| PMT::new(raw).streams())
     }
 }
 
+#[derive(Default)]
 struct Tables {
     map: HashMap<SubtableID, Table>,
-    /// current demuxing section
-    current: Option<SectionRef>,
+    /// per PID in-progress section
+    assemblers: HashMap<PID, SectionAssembler>,
+    /// last complete version-number;
+    /// keyed by subtable-id with version-number 0
+    versions: HashMap<SubtableID, u8>,
+    /// section buffers
+    pool: BufPool,
 }
 
-impl Tables {}
+impl Tables {
+    /// drop all tables;
+    /// section buffers go to pool
+    fn reset(&mut self) {
+        self.assemblers.clear();
+        self.versions.clear();
 
-impl Default for Tables {
-    fn default() -> Self {
-        Tables {
-            map: HashMap::new(),
-            current: None,
+        for (_, table) in self.map.drain() {
+            self.pool.put(table);
+        }
+    }
+
+    /// remember version of complete table;
+    /// return previous version if it differs,
+    /// outdated table is dropped
+    #[inline(always)]
+    fn version_update(&mut self, id: SubtableID) -> Option<u8> {
+        let v = id.version_number();
+
+        match self.versions.insert(id.with_version_number(0), v) {
+            Some(old) if old != v => {
+                if let Some(table) = self.map.remove(&id.with_version_number(old)) {
+                    self.pool.put(table);
+                }
+                Some(old)
+            }
+            _ => None,
         }
     }
 }
@@ -154,35 +359,117 @@ impl Default for Tables {
 pub struct Packet {
     pub pid: PID,
 
+    /// PMT program-number the elementary stream belongs to
+    pub program_number: u16,
+
+    /// PMT stream-type
+    pub stream_type: StreamType,
+
+    /// input offset of ts-packet with PES start
     pub offset: usize,
 
+    /// PES stream-id
+    pub stream_id: Option<StreamID>,
+
     /// presentation time stamp
     pub pts: Option<Duration>,
 
     /// decode time stamp
     pub dts: Option<Duration>,
 
+    /// adaptation random-access-indicator of the PUSI packet;
+    /// access unit is a random access point (e.g. key frame)
+    pub random_access: bool,
+
     pub buf: Buf,
 
+    /// H.264/H.265 SPS resolution and frame rate;
+    /// None until SPS is seen
+    pub video_params: Option<VideoParams>,
+
+    /// AAC (ADTS) / LOAS frame header parameters;
+    /// None until frame with config is seen
+    pub audio_params: Option<AudioParams>,
+
     /// got ts PUSI
     started: bool,
+
+    /// PES header is not parsed yet;
+    /// header split across ts-packets is buffered in buf
+    header_pending: bool,
+    /// access unit framing;
+    /// created on first emit if enabled
+    access_units: Option<AccessUnits>,
 }
 
 impl Packet {
-    fn new(pid: PID) -> Packet {
+    fn new(pid: PID, program_number: u16, stream_type: StreamType) -> Packet {
         Packet {
             pid,
+            program_number,
+            stream_type,
             offset: 0,
+            stream_id: None,
             pts: None,
             dts: None,
+            random_access: false,
             buf: Default::default(),
+            video_params: None,
+            audio_params: None,
             started: false,
+            header_pending: false,
+            access_units: None,
+        }
+    }
+
+    /// feed PES bytes after ts-packet header
+    #[inline(always)]
+    fn write(&mut self, buf: &[u8]) -> Result<()> {
+        if !self.header_pending {
+            self.buf.0.write_all(buf)?;
+            return Ok(());
+        }
+
+        if self.buf.is_empty() {
+            return self.write_header(buf);
+        }
+
+        self.buf.0.write_all(buf)?;
+        let raw = self.buf.as_slice().to_vec();
+        self.buf.reset();
+
+        self.write_header(&raw)
+    }
+
+    /// PES start: header fields and payload
+    fn write_header(&mut self, raw: &[u8]) -> Result<()> {
+        match PES::try_new(raw) {
+            Ok(pes) => {
+                self.header_pending = false;
+                self.stream_id = Some(pes.stream_id());
+                self.pts = pes.pts().map(Duration::from);
+                self.dts = pes.dts().map(Duration::from);
+
+                self.buf.0.write_all(pes.buf_seek_payload())?;
+            }
+            // header continues in the next ts-packet
+            Err(err) if matches!(err.kind(), ErrorKind::Buf(..)) => {
+                self.buf.0.write_all(raw)?;
+            }
+            // not a PES start; wait for the next PUSI
+            Err(_) => {
+                self.started = false;
+            }
         }
+
+        Ok(())
     }
 }
 
 #[derive(Default)]
-struct Packets(HashMap<PID, Packet>);
+/// elementary PID may be shared by programs;
+/// one packet per program
+struct Packets(HashMap<PID, Vec<Packet>>);
 
 /// pid, packet-constructed
 #[derive(Debug)]
@@ -191,7 +478,7 @@ struct PMTPids(Vec<(PID, bool)>);
 impl PMTPids {
     #[inline(always)]
     fn has(&self, pid: PID) -> bool {
-        self.0.iter().any(|p| (*p).0 == pid)
+        self.0.iter().any(|p| p.0 == pid)
     }
 
     #[inline(always)]
@@ -216,9 +503,8 @@ impl PMTPids {
 
     /// all pids are parsed?
     #[inline(always)]
-    #[allow(dead_code)]
     fn are_all_packets_builded(&self) -> bool {
-        !self.0.iter().any(|p| !(*p).1)
+        !self.0.iter().any(|p| !p.1)
     }
 }
 
@@ -228,13 +514,401 @@ impl Default for PMTPids {
     }
 }
 
+/// raw section callback;
+/// Send - stored in Demuxer which is Send
+type OnSection = Box<dyn FnMut(&[u8]) + Send>;
+
+/// raw section on custom (private) PID;
+/// no table/version tracking - every section is delivered
+struct PrivateSection {
+    /// table-id filter; None - any table-id
+    table_id: Option<u8>,
+
+    assembler: SectionAssembler,
+
+    on_section: OnSection,
+}
+
+impl PrivateSection {
+    fn new(
+        table_id: Option<u8>,
+        max_section_length: usize,
+        on_section: OnSection,
+    ) -> PrivateSection {
+        let mut assembler = SectionAssembler::new();
+        assembler.set_max_section_length(max_section_length);
+
+        PrivateSection {
+            table_id,
+            assembler,
+            on_section,
+        }
+    }
+
+    #[inline(always)]
+    fn push(&mut self, pkt: &TsPacket) -> Result<()> {
+        let PrivateSection {
+            table_id,
+            assembler,
+            on_section,
+        } = self;
+
+        assembler.push_packet(pkt, |raw| {
+            if table_id.is_none_or(|table_id| table_id == raw[0]) {
+                // emit
+                on_section(raw);
+            }
+        })
+    }
+}
+
+/// PCR-based mux bitrate;
+/// accumulated bytes and PCR ticks between
+/// consecutive PCR samples on single PCR PID
+#[derive(Default)]
+struct Bitrate {
+    /// first pid carrying PCR
+    pid: Option<PID>,
+
+    /// previous (offset, PCR-27MHz)
+    last: Option<(usize, u64)>,
+
+    bytes: u64,
+    ticks: u64,
+}
+
+impl Bitrate {
+    #[inline(always)]
+    fn push(&mut self, pid: PID, offset: usize, pcr: u64, discontinuity: bool) {
+        if *self.pid.get_or_insert(pid) != pid {
+            return;
+        }
+
+        if let (Some((offset_last, pcr_last)), false) = (self.last, discontinuity) {
+            // PCR wraparound
+            let ticks = (pcr + PCR::MAX - pcr_last) % PCR::MAX;
+
+            self.bytes += (offset - offset_last) as u64;
+            self.ticks += ticks;
+        }
+
+        self.last = Some((offset, pcr));
+    }
+
+    /// bits per second
+    #[inline(always)]
+    fn estimate(&self) -> Option<u64> {
+        if self.ticks == 0 {
+            return None;
+        }
+
+        let bps = u128::from(self.bytes) * 8 * u128::from(PCR::HZ) / u128::from(self.ticks);
+
+        Some(bps as u64)
+    }
+}
+
+/// SI repetition monitoring;
+/// clock is fed by caller (wall time or PCR based)
+struct Repetition {
+    /// last fed clock; None - monitoring is not started
+    now: Option<Duration>,
+
+    /// max interval between table sections
+    max_age: HashMap<TableID, Duration>,
+
+    /// keyed by subtable-id with version-number 0;
+    /// (last subtable-id, seen at, timeout reported)
+    seen: HashMap<SubtableID, (SubtableID, Duration, bool)>,
+}
+
+impl Repetition {
+    /// keep configured max-age
+    fn reset(&mut self) {
+        self.now = None;
+        self.seen.clear();
+    }
+}
+
+impl Default for Repetition {
+    /// ETSI TR 101 290 (5.2.1, 5.2.3)
+    fn default() -> Self {
+        let max_age = [
+            (TableID::ProgramAssociationSection, 500),
+            (TableID::ProgramMapSection, 500),
+            (
+                TableID::ServiceDescriptionSectionActualTransportStream,
+                2_000,
+            ),
+            (
+                TableID::ServiceDescriptionSectionOtherTransportStream,
+                10_000,
+            ),
+            (TableID::EISActualTransportStream, 2_000),
+            (TableID::EISOtherTransportStream, 10_000),
+        ]
+        .iter()
+        .map(|&(table_id, ms)| (table_id, Duration::from_millis(ms)))
+        .collect();
+
+        Repetition {
+            now: None,
+            max_age,
+            seen: Default::default(),
+        }
+    }
+}
+
+impl Repetition {
+    #[inline(always)]
+    fn see(&mut self, id: SubtableID) {
+        if let Some(now) = self.now {
+            if self.max_age.contains_key(&id.table_id()) {
+                self.seen
+                    .insert(id.with_version_number(0), (id, now, false));
+            }
+        }
+    }
+
+    /// return: newly timed out (subtable-id, age)
+    fn tick(&mut self, now: Duration) -> Vec<(SubtableID, Duration)> {
+        self.now = Some(now);
+
+        let max_age = &self.max_age;
+
+        self.seen
+            .values_mut()
+            .filter_map(|(id, at, reported)| {
+                let age = now.saturating_sub(*at);
+
+                match max_age.get(&id.table_id()) {
+                    Some(max_age) if !*reported && age > *max_age => {
+                        *reported = true;
+                        Some((*id, age))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+}
+
+/// per-PID counters
+#[derive(Clone, Debug, Default)]
+pub struct PidStats {
+    pub packets: u64,
+    pub bytes: u64,
+
+    /// continuity-counter errors
+    pub cc_errors: u64,
+
+    /// packets with scrambled payload
+    pub scrambled: u64,
+
+    /// last seen PCR (27MHz)
+    pub pcr: Option<u64>,
+
+    /// last cc
+    cc: Option<u8>,
+}
+
+impl PidStats {
+    /// return: last cc on cc error
+    #[inline(always)]
+    fn push_cc(&mut self, pkt: &TsPacket, discontinuity: bool) -> Option<u8> {
+        let mut err = None;
+
+        if let (Some(cc_last), false) = (self.cc, discontinuity) {
+            if pkt.validate_cc(cc_last).is_err() {
+                self.cc_errors += 1;
+                err = Some(cc_last);
+            }
+        }
+
+        self.cc = Some(pkt.cc());
+
+        err
+    }
+}
+
+/// per-PID counters accumulated during demux
+#[derive(Clone, Debug, Default)]
+pub struct Stats(HashMap<PID, PidStats>);
+
+impl Stats {
+    #[inline(always)]
+    pub fn get(&self, pid: PID) -> Option<&PidStats> {
+        self.0.get(&pid)
+    }
+
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = (&PID, &PidStats)> {
+        self.0.iter()
+    }
+
+    /// all PIDs packets
+    #[inline(always)]
+    pub fn packets(&self) -> u64 {
+        self.0.values().map(|s| s.packets).sum()
+    }
+
+    /// return: (PID first seen, last cc on cc error)
+    #[inline(always)]
+    fn push(
+        &mut self,
+        raw: &[u8],
+        pkt: &TsPacket,
+        discontinuity: bool,
+        pcr: Option<u64>,
+    ) -> (bool, Option<u8>) {
+        let pid = pkt.pid();
+        let stats = self.0.entry(pid).or_default();
+        let first_seen = stats.packets == 0;
+
+        stats.packets += 1;
+        stats.bytes += raw.len() as u64;
+
+        if pkt.is_scrambled() {
+            stats.scrambled += 1;
+        }
+
+        if pcr.is_some() {
+            stats.pcr = pcr;
+        }
+
+        // null packets cc is undefined;
+        // corrupted packet header is not trusted
+        let cc_error = if !pid.is_null() && !pkt.tei() {
+            stats.push_cc(pkt, discontinuity)
+        } else {
+            None
+        };
+
+        (first_seen, cc_error)
+    }
+}
+
+/// PID classification by currently parsed PAT/PMT
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PidRole {
+    Pat,
+    /// PMT of program-number
+    Pmt(u16),
+    /// well-known SI/PSI or registered private section PID
+    Section(PID),
+    /// elementary stream with PMT stream-type
+    Pes(StreamType),
+    Null,
+    /// not (yet) referenced by PAT/PMT
+    Unknown,
+}
+
+/// significant parse event for structured logging
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Trace {
+    /// first packet of PID (since stats reset)
+    PidSeen { pid: PID, offset: usize },
+    /// continuity-counter error; (last, got) cc
+    CcError {
+        pid: PID,
+        offset: usize,
+        last: u8,
+        cc: u8,
+    },
+    /// adaptation discontinuity-indicator is set
+    Discontinuity { pid: PID, offset: usize },
+    /// PSI/SI section header parsed, buffering started
+    SectionBegin {
+        pid: PID,
+        id: SubtableID,
+        section_number: u8,
+        sz: usize,
+    },
+    /// PSI/SI section fully buffered
+    SectionComplete {
+        pid: PID,
+        id: SubtableID,
+        section_number: u8,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// PAT/PMT/SDT cross-check finding
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SignalingIssue {
+    /// PAT program without received PMT
+    PmtMissing { program_number: u16, pmt_pid: u16 },
+    /// PMT elementary stream without packets
+    ElementaryPidMissing { program_number: u16, pid: u16 },
+    /// PMT PCR PID without PCR
+    PcrMissing { program_number: u16, pcr_pid: PID },
+    /// SDT service without PAT program
+    ServiceNotInPat { service_id: u16 },
+    /// PAT program without SDT service (SDT received)
+    ProgramNotInSdt { program_number: u16 },
+}
+
+impl SignalingIssue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            SignalingIssue::PmtMissing { .. } | SignalingIssue::PcrMissing { .. } => {
+                Severity::Error
+            }
+            SignalingIssue::ElementaryPidMissing { .. }
+            | SignalingIssue::ServiceNotInPat { .. } => Severity::Warning,
+            SignalingIssue::ProgramNotInSdt { .. } => Severity::Info,
+        }
+    }
+}
+
 pub trait DemuxerEvents {
     fn on_table(&mut self, _: SubtableID, _: &Table) {}
+    /// complete table with version-number changed;
+    /// (old-version-number, new subtable-id, new table)
+    fn on_table_update(&mut self, _: u8, _: SubtableID, _: &Table) {}
     fn on_packet(&mut self, _: &Packet) {}
+    /// transport-error-indicator is set;
+    /// pid may be corrupted as well
+    fn on_corrupt_packet(&mut self, _: PID) {}
+    /// transport-scrambling-control is set
+    fn on_scrambled_packet(&mut self, _: PID) {}
+    /// H.264 access unit; requires Demuxer::set_access_units
+    fn on_access_unit(&mut self, _: PID, _: &AccessUnit) {}
+    /// RTP sequence-number discontinuity (loss or reordering);
+    /// (expected, got)
+    fn on_rtp_gap(&mut self, _: u16, _: u16) {}
+    /// signaling is ready: every table of Demuxer::set_probe_tables
+    /// is fully assembled; fired once (again after reset)
+    fn on_probe_complete(&mut self) {}
+    /// probe tables are not assembled within Demuxer::set_probe_timeout
+    /// packets (e.g. single-PID capture without PAT); fired once
+    /// (again after reset); on_probe_complete may still follow
+    fn on_probe_failed(&mut self) {}
+    /// no section of subtable within max-age;
+    /// reported once until subtable is seen again
+    fn on_table_timeout(&mut self, _: SubtableID, _: Duration) {}
+    /// structured parse trace (e.g. to `log`/`tracing`);
+    /// default no-op is optimized out
+    #[inline(always)]
+    fn on_trace(&mut self, _: Trace) {}
+}
+
+/// ts-packet input offset and PID
+#[inline(always)]
+fn err_context(err: Error, pid: Option<PID>, offset: usize) -> Error {
+    match pid {
+        Some(pid) => err.with_context(format!(":pid {} :offset {}", pid, offset)),
+        None => err.with_context(format!(":offset {}", offset)),
+    }
 }
 
 /// TODO: use tree, redix tree here
-/// TODO: add benches
 pub struct Demuxer<T>
 where
     T: DemuxerEvents,
@@ -259,6 +933,55 @@ where
     //       for multiple PMTs
     pmt_pids: PMTPids,
 
+    /// program-number -> PCR PID by PMT
+    pcr_pids: HashMap<u16, PID>,
+
+    /// PAT (program-number, PMT PID) PMT PIDs are built from
+    pat_programs: Vec<(u16, PID)>,
+
+    /// tables required for on_probe_complete
+    probe_tables: Vec<SubtableKind>,
+    probe_complete: bool,
+
+    /// packets to give up probing after; 0 - wait forever
+    probe_timeout: usize,
+    /// packets seen while probing
+    probe_packets: usize,
+    probe_failed: bool,
+
+    /// PAT/PMT/SDT joined
+    services: ServiceCatalog,
+
+    /// EIT actual events
+    epg: EpgStore,
+
+    /// do not demux packets with transport-error-indicator
+    skip_corrupt: bool,
+
+    /// do not demux packets with scrambled payload
+    skip_scrambled: bool,
+
+    /// demux sections with current-next-indicator unset
+    include_next: bool,
+
+    /// per-section buffer bound
+    max_section_length: usize,
+
+    /// frame elementary streams into access units
+    access_units: bool,
+
+    bitrate: Bitrate,
+
+    repetition: Repetition,
+
+    /// last RTP sequence-number
+    rtp_seq: Option<u16>,
+
+    stats: Stats,
+
+    /// custom section PIDs
+    private: HashMap<PID, PrivateSection>,
+
     events: T,
 }
 
@@ -284,280 +1007,2684 @@ where
 
             packets: Default::default(),
 
+            pcr_pids: Default::default(),
+
+            pat_programs: Default::default(),
+
+            probe_tables: vec![SubtableKind::PAT, SubtableKind::PMT],
+            probe_complete: false,
+
+            // ~18MB
+            probe_timeout: 100_000,
+            probe_packets: 0,
+            probe_failed: false,
+
+            services: Default::default(),
+            epg: Default::default(),
+
+            skip_corrupt: true,
+            skip_scrambled: true,
+            include_next: false,
+
+            max_section_length: HEADER_MAX_PRIVATE_SECTION_LENGTH,
+
+            access_units: false,
+
+            bitrate: Default::default(),
+
+            repetition: Default::default(),
+
+            rtp_seq: None,
+
+            stats: Default::default(),
+
+            private: Default::default(),
+
             events,
         }
     }
 
-    /// cache pmt pids
-    // TODO: also do via iterator
-    // TODO: .iter().collect() for lazy collection
-    #[inline(always)]
-    fn build_pmt_pids(&mut self) {
-        for (_, table) in self.pat.map.iter() {
-            for section_ref in table.sections.0.iter() {
-                let section = (*section_ref).borrow();
-                let raw = section.buf.0.get_ref().as_slice();
-                let pat = PAT::new(raw);
-
-                // TODO: refactor via iter/to-iter
-                for pid in pat
-                    .programs()
-                    .filter_map(Result::ok)
-                    .filter(|p| p.pid().is_program_map())
-                    .map(|p| PID::from(p.pid()))
-                {
-                    self.pmt_pids.push_uniq(pid)
-                }
-            }
+    /// forget stream state to re-probe a new stream
+    /// (e.g. channel change on the same socket):
+    /// tables, PMT PIDs, PES assembly, services, stats and offset;
+    /// events handler, settings and private section PIDs are kept
+    pub fn reset(&mut self) {
+        self.offset = 0;
+
+        for tables in self.tables_mut() {
+            tables.reset();
+        }
+
+        self.packets = Default::default();
+        self.pmt_pids = Default::default();
+        self.pcr_pids.clear();
+        self.pat_programs.clear();
+        self.probe_complete = false;
+        self.probe_packets = 0;
+        self.probe_failed = false;
+        self.services = Default::default();
+        self.epg = Default::default();
+        self.bitrate = Default::default();
+        self.repetition.reset();
+        self.rtp_seq = None;
+        self.stats = Default::default();
+
+        for private in self.private.values_mut() {
+            private.assembler.reset();
         }
     }
 
-    /// build packets cache
-    // TODO: also do via iterator
-    // TODO: .iter().collect() for lazy collection
+    /// skip packets with transport-error-indicator set
+    /// instead of feeding them into section/PES assembly;
+    /// default: true
     #[inline(always)]
-    fn build_packets(&mut self) {
-        for (_, table) in self.pmt.map.iter() {
-            for section_ref in table.sections.0.iter() {
-                let section = (*section_ref).borrow();
-                let raw = section.buf.0.get_ref().as_slice();
-                let pmt = PMT::new(raw);
-
-                // TODO: refactor via iter/to-iter
-                for pid in pmt
-                    .streams()
-                    .filter_map(Result::ok)
-                    .map(|s| PID::from(s.pid()))
-                {
-                    self.packets
-                        .0
-                        .entry(pid)
-                        .or_insert_with(|| Packet::new(pid));
-                }
-            }
+    pub fn set_skip_corrupt(&mut self, skip_corrupt: bool) {
+        self.skip_corrupt = skip_corrupt;
+    }
+
+    /// skip packets with transport-scrambling-control set
+    /// instead of feeding them into section/PES assembly;
+    /// default: true
+    #[inline(always)]
+    pub fn set_skip_scrambled(&mut self, skip_scrambled: bool) {
+        self.skip_scrambled = skip_scrambled;
+    }
+
+    /// demux "next" (not yet applicable) sections,
+    /// current-next-indicator unset, as regular ones;
+    /// default: false - such sections are skipped
+    #[inline(always)]
+    pub fn set_include_next(&mut self, include_next: bool) {
+        self.include_next = include_next;
+    }
+
+    /// hard per-section memory bound for untrusted input;
+    /// section with larger section-length is dropped with error;
+    /// PAT/PMT/CAT are limited to 1021 anyway;
+    /// default: 4093
+    #[inline(always)]
+    pub fn set_max_section_length(&mut self, max_section_length: usize) {
+        self.max_section_length = max_section_length;
+
+        for private in self.private.values_mut() {
+            private.assembler.set_max_section_length(max_section_length);
+        }
+    }
+
+    /// initial capacity of section buffer;
+    /// default: 2048
+    pub fn set_section_capacity(&mut self, capacity: usize) {
+        for tables in self.tables_mut() {
+            tables.pool.capacity = capacity;
+        }
+    }
+
+    /// keep up to max section buffers of outdated tables
+    /// (per PAT/PMT/EIT/SDT/NIT/CAT) for reuse by new versions;
+    /// for long-running EPG collectors;
+    /// default: 0 - no pooling
+    pub fn set_section_pool(&mut self, max: usize) {
+        for tables in self.tables_mut() {
+            tables.pool.max = max;
+            tables.pool.free.truncate(max);
         }
     }
 
-    // TODO: move to macros?
     #[inline(always)]
-    fn demux_section(&mut self, pid_or_pmt: (PID, bool), pkt: &TsPacket) -> Result<()> {
-        let tables = match pid_or_pmt {
-            (PID::PAT, false) => &mut self.pat,
-            (PID::SDT, false) => &mut self.sdt,
-            (PID::EIT, false) => &mut self.eit,
-            (PID::NIT, false) => &mut self.nit,
-            (PID::CAT, false) => &mut self.cat,
-            (_, true) => &mut self.pmt,
-            _ => unreachable!(),
+    fn tables_mut(&mut self) -> [&mut Tables; 7] {
+        [
+            &mut self.pat,
+            &mut self.pmt,
+            &mut self.eit,
+            &mut self.sdt,
+            &mut self.nit,
+            &mut self.cat,
+            &mut self.bat,
+        ]
+    }
+
+    /// tables to be fully assembled before on_probe_complete;
+    /// PMT - all PMTs referenced by PAT;
+    /// NIT/BAT/CAT are not demuxed and never complete;
+    /// default: PAT, PMT
+    pub fn set_probe_tables(&mut self, kinds: &[SubtableKind]) {
+        self.probe_tables = kinds.to_vec();
+    }
+
+    /// on_probe_complete is fired
+    #[inline(always)]
+    pub fn is_probe_complete(&self) -> bool {
+        self.probe_complete
+    }
+
+    /// give up probing after packets without complete probe tables
+    /// and fire on_probe_failed;
+    /// 0 - wait forever;
+    /// default: 100000 (~18MB)
+    pub fn set_probe_timeout(&mut self, packets: usize) {
+        self.probe_timeout = packets;
+    }
+
+    /// on_probe_failed is fired
+    #[inline(always)]
+    pub fn is_probe_failed(&self) -> bool {
+        self.probe_failed
+    }
+
+    /// fire on_probe_complete once all probe tables are assembled
+    fn probe_check(&mut self) {
+        if self.probe_complete {
+            return;
+        }
+
+        let has_complete = |tables: &Tables| tables.map.values().any(Table::is_complete);
+
+        let complete = self.probe_tables.iter().all(|kind| match kind {
+            SubtableKind::PAT => has_complete(&self.pat),
+            SubtableKind::PMT => has_complete(&self.pat) && self.pmt_pids.are_all_packets_builded(),
+            SubtableKind::SDT => has_complete(&self.sdt),
+            SubtableKind::EIT => has_complete(&self.eit),
+            SubtableKind::NIT => has_complete(&self.nit),
+            SubtableKind::BAT => has_complete(&self.bat),
+            SubtableKind::CAT => has_complete(&self.cat),
+        });
+
+        if complete {
+            self.probe_complete = true;
+
+            // emit
+            self.events.on_probe_complete();
+        }
+    }
+
+    /// count input packet towards probe timeout:
+    /// invalid, skipped and failed packets too;
+    /// fire on_probe_failed once timeout packets went by
+    /// without complete probe tables
+    #[inline(always)]
+    fn probe_count(&mut self) {
+        if self.probe_complete || self.probe_failed || self.probe_timeout == 0 {
+            return;
+        }
+
+        if self.probe_packets < self.probe_timeout {
+            self.probe_packets += 1;
+            return;
+        }
+
+        self.probe_failed = true;
+
+        // emit
+        self.events.on_probe_failed();
+    }
+
+    /// split H.264 (Annex B) PES payload into access units
+    /// delivered via on_access_unit with PES PTS/DTS;
+    /// access unit is emitted once the next one starts;
+    /// default: false
+    #[inline(always)]
+    pub fn set_access_units(&mut self, access_units: bool) {
+        self.access_units = access_units;
+    }
+
+    /// max interval between sections of table-id;
+    /// None - not monitored;
+    /// default: PAT, PMT - 0.5s, SDT/EIT p/f actual - 2s,
+    /// SDT/EIT p/f other - 10s
+    #[inline(always)]
+    pub fn set_table_timeout(&mut self, table_id: TableID, max_age: Option<Duration>) {
+        match max_age {
+            Some(max_age) => self.repetition.max_age.insert(table_id, max_age),
+            None => self.repetition.max_age.remove(&table_id),
         };
+    }
 
-        let buf = pkt.buf_payload_section()?;
+    /// feed clock (wall time since start or PCR based)
+    /// and report tables not seen within max-age;
+    /// monitoring starts with the first call,
+    /// sections are stamped with the last fed clock
+    pub fn check_table_timeouts(&mut self, now: Duration) {
+        for (id, age) in self.repetition.tick(now) {
+            // emit
+            self.events.on_table_timeout(id, age);
+        }
+    }
 
-        if pkt.pusi() {
-            let (id, sz, section_number, last_section_number) = match pid_or_pmt {
-                (PID::PAT, false) => {
-                    let s = PAT::try_new(buf)?;
-                    (
-                        s.subtable_id(),
-                        s.sz(),
-                        s.section_number(),
-                        s.last_section_number(),
-                    )
-                }
-                (PID::SDT, false) => {
-                    let s = SDT::try_new(buf)?;
-                    (
-                        s.subtable_id(),
-                        s.sz(),
-                        s.section_number(),
-                        s.last_section_number(),
-                    )
-                }
-                (PID::EIT, false) => {
-                    let s = EIT::try_new(buf)?;
-                    (
-                        s.subtable_id(),
-                        s.sz(),
-                        s.section_number(),
-                        s.last_section_number(),
-                    )
-                }
-                (_, true) => {
-                    let s = PMT::try_new(buf)?;
-                    (
-                        s.subtable_id(),
-                        s.sz(),
-                        s.section_number(),
-                        s.last_section_number(),
-                    )
-                }
-                _ => unreachable!(),
-            };
+    /// mux bitrate in bits per second;
+    /// requires at least two PCR observations
+    #[inline(always)]
+    pub fn estimated_bitrate(&self) -> Option<u64> {
+        self.bitrate.estimate()
+    }
+
+    /// per-PID packets, bytes, cc-errors and last PCR
+    #[inline(always)]
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// start new accumulation window
+    #[inline(always)]
+    pub fn reset_stats(&mut self) {
+        self.stats = Default::default();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[inline(always)]
+    pub(crate) fn events_mut(&mut self) -> &mut T {
+        &mut self.events
+    }
+
+    /// events handler
+    #[inline(always)]
+    pub fn events(&self) -> &T {
+        &self.events
+    }
+
+    /// services joined across PAT, PMT and SDT
+    #[inline(always)]
+    pub fn services(&self) -> impl Iterator<Item = &Service> {
+        self.services.iter()
+    }
+
+    #[inline(always)]
+    pub fn service_catalog(&self) -> &ServiceCatalog {
+        &self.services
+    }
+
+    /// EIT actual events of service ordered by start-time;
+    /// de-duplicated across present/following, schedule and versions
+    #[inline(always)]
+    pub fn epg(&self, service_id: u16) -> impl Iterator<Item = &EpgEvent> {
+        self.epg.events(service_id)
+    }
+
+    #[inline(always)]
+    pub fn epg_store(&self) -> &EpgStore {
+        &self.epg
+    }
+
+    /// drop EPG events ended before t
+    #[inline(always)]
+    pub fn epg_prune(&mut self, t: DateTime<Utc>) {
+        self.epg.prune(t);
+    }
+
+    /// elementary PIDs and codecs of program by PMT, ordered by PID;
+    /// empty until program PMT is received
+    pub fn program_pids(&self, program_number: u16) -> Vec<(PID, StreamType)> {
+        let mut pids = self
+            .packets
+            .0
+            .iter()
+            .flat_map(|(pid, packets)| {
+                packets
+                    .iter()
+                    .filter(|p| p.program_number == program_number)
+                    .map(move |p| (*pid, p.stream_type))
+            })
+            .collect::<Vec<_>>();
+
+        pids.sort_unstable_by_key(|(pid, _)| u16::from(*pid));
+        pids
+    }
 
-            let table = tables
-                .map
-                .entry(id)
-                .or_insert_with(|| Table::new(last_section_number));
+    /// PCR PID of program by PMT;
+    /// None until program PMT is received
+    #[inline(always)]
+    pub fn pcr_pid(&self, program_number: u16) -> Option<PID> {
+        self.pcr_pids.get(&program_number).copied()
+    }
+
+    /// last PCR (27MHz) of program PCR PID
+    pub fn program_pcr(&self, program_number: u16) -> Option<u64> {
+        let pid = self.pcr_pid(program_number)?;
+
+        self.stats.get(pid)?.pcr
+    }
+
+    /// cross-check parsed PAT/PMT/SDT against each other
+    /// and against PIDs seen since stats reset;
+    /// issues are ordered by program-number / service-id
+    pub fn validate_signaling(&self) -> Vec<SignalingIssue> {
+        let mut issues = Vec::new();
 
-            let section_ref = match table.sections.get_mut(section_number) {
-                Some(section_ref) => {
-                    let mut section = (*section_ref).borrow_mut();
-                    section.buf.reset();
-                    section.sz = sz;
+        let seen = |pid: PID| self.stats.get(pid).is_some_and(|s| s.packets != 0);
+        // SDT service loop sets running-status
+        let got_sdt = self.services.iter().any(|s| s.running_status.is_some());
 
-                    section_ref.clone()
+        for service in self.services.iter() {
+            let program_number = service.service_id;
+
+            let pmt_pid = match service.pmt_pid {
+                Some(pmt_pid) => pmt_pid,
+                None => {
+                    if service.running_status.is_some() {
+                        issues.push(SignalingIssue::ServiceNotInPat {
+                            service_id: program_number,
+                        });
+                    }
+                    continue;
                 }
+            };
+
+            if got_sdt && service.running_status.is_none() {
+                issues.push(SignalingIssue::ProgramNotInSdt { program_number });
+            }
+
+            let pcr_pid = match self.pcr_pid(program_number) {
+                Some(pcr_pid) => pcr_pid,
                 None => {
-                    let section_ref = Section::new(id, section_number, sz).into_ref();
-                    table.sections.push(section_ref.clone());
-                    section_ref
+                    issues.push(SignalingIssue::PmtMissing {
+                        program_number,
+                        pmt_pid,
+                    });
+                    continue;
                 }
             };
 
-            tables.current = Some(section_ref);
+            for stream in service.streams.iter() {
+                if !seen(PID::from(stream.pid)) {
+                    issues.push(SignalingIssue::ElementaryPidMissing {
+                        program_number,
+                        pid: stream.pid,
+                    });
+                }
+            }
+
+            if !pcr_pid.is_null() && self.program_pcr(program_number).is_none() {
+                issues.push(SignalingIssue::PcrMissing {
+                    program_number,
+                    pcr_pid,
+                });
+            }
         }
 
-        if let Some(section_ref) = &tables.current {
-            {
-                let mut section = (*section_ref).borrow_mut();
-                let sz_need = section.sz_need();
+        issues
+    }
+
+    /// video elementary stream resolution and frame rate;
+    /// None until SPS is seen
+    pub fn video_params(&self, pid: PID) -> Option<VideoParams> {
+        self.packets
+            .0
+            .get(&pid)?
+            .iter()
+            .find_map(|packet| packet.video_params)
+    }
 
-                // remove null/padding bytes
-                let buf = if buf.len() > sz_need {
-                    &buf[0..sz_need]
-                } else {
-                    buf
-                };
+    /// audio elementary stream sample rate and channels;
+    /// None until frame header is seen
+    pub fn audio_params(&self, pid: PID) -> Option<AudioParams> {
+        self.packets
+            .0
+            .get(&pid)?
+            .iter()
+            .find_map(|packet| packet.audio_params)
+    }
 
-                section.buf.0.write_all(buf)?;
+    /// PID role by currently parsed (complete) PAT/PMT;
+    /// Unknown until PAT/PMT referencing the PID are received
+    pub fn pid_role(&self, pid: PID) -> PidRole {
+        match pid {
+            PID::NULL => return PidRole::Null,
+            PID::PAT => return PidRole::Pat,
+            _ if pid.is_section() || self.private.contains_key(&pid) => {
+                return PidRole::Section(pid)
             }
+            _ => {}
+        }
 
-            {
-                let section = (*section_ref).borrow();
-                if section.done() {
-                    if let Some(table) = tables.map.get(&section.table_id) {
-                        if table.done() {
-                            // emit
-                            self.events.on_table(section.table_id, &table);
-                        }
-                    }
+        for table in self.pat.map.values().filter(|t| t.is_complete()) {
+            for raw in table.sections_bytes() {
+                let pat = PAT::new(&raw);
+
+                let program = pat
+                    .program_map_pids()
+                    .find(|(_, pmt_pid)| PID::from(*pmt_pid) == pid);
+
+                if let Some((number, _)) = program {
+                    return PidRole::Pmt(number);
                 }
             }
         }
 
-        Ok(())
-    }
+        for table in self.pmt.map.values().filter(|t| t.is_complete()) {
+            for raw in table.sections_bytes() {
+                let pmt = PMT::new(&raw);
 
-    pub fn demux(&mut self, raw: &[u8]) -> Result<()> {
-        if self.demux_tables(raw)? {
-            return Ok(());
+                let stream = pmt
+                    .streams()
+                    .filter_map(Result::ok)
+                    .find(|s| PID::from(s.pid()) == pid);
+
+                if let Some(stream) = stream {
+                    return PidRole::Pes(stream.stream_type());
+                }
+            }
         }
 
-        self.demux_packets(raw)
+        PidRole::Unknown
     }
 
-    /// ffmpeg::avformat_open_input analog
-    /// probe input
-    /// return: is pid handled?
-    pub fn demux_tables(&mut self, raw: &[u8]) -> Result<(bool)> {
-        self.offset += raw.len();
+    /// assemble sections on custom PID
+    /// (e.g. SCTE-35, AIT, private EPG);
+    /// raw section (header, data, CRC) with matching table-id
+    /// is passed to on_section; None - any table-id;
+    /// takes precedence over PAT/PMT/SDT/EIT demuxing of this PID;
+    /// on_section moves with demuxer across threads so it must be Send:
+    ///
+    /// ```compile_fail
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    ///
+    /// use va_ts::{Demuxer, DemuxerEvents};
+    ///
+    /// struct Events;
+    /// impl DemuxerEvents for Events {}
+    ///
+    /// let mut demuxer = Demuxer::new(Events);
+    /// let sections = Rc::new(RefCell::new(0));
+    ///
+    /// demuxer.register_section_pid(0x500, None, move |_| *sections.borrow_mut() += 1);
+    /// ```
+    pub fn register_section_pid<F>(&mut self, pid: u16, table_id: Option<u8>, on_section: F)
+    where
+        F: FnMut(&[u8]) + Send + 'static,
+    {
+        self.private.insert(
+            PID::from(pid),
+            PrivateSection::new(table_id, self.max_section_length, Box::new(on_section)),
+        );
+    }
 
-        let pkt = TsPacket::new(&raw)?;
-        let pid = pkt.pid();
+    /// cache pmt pids
+    // TODO: also do via iterator
+    // TODO: .iter().collect() for lazy collection
+    #[inline(always)]
+    fn build_pmt_pids(&mut self) {
+        let mut programs = Vec::new();
 
-        if pid.is_null() {
-            // null packet PID
-            return Ok(true);
+        for (_, table) in self.pat.map.iter().filter(|(_, t)| t.is_complete()) {
+            for raw in table.sections_bytes() {
+                let pat = PAT::new(&raw);
+
+                programs.extend(
+                    pat.program_map_pids()
+                        .map(|(number, pid)| (number, PID::from(pid))),
+                );
+            }
         }
 
-        match pid {
-            PID::PAT => {
-                self.demux_section((pid, false), &pkt)?;
+        if programs == self.pat_programs {
+            return;
+        }
 
-                // extract pids from PAT
-                if self.pmt_pids.0.is_empty() {
-                    self.pmt_pids.0.clear();
-                    self.packets.0.clear();
-                    self.build_pmt_pids();
-                }
+        // removed programs or PMT PID changed
+        for (number, _) in self.pat_programs.iter().filter(|p| !programs.contains(p)) {
+            self.pcr_pids.remove(number);
+
+            for packets in self.packets.0.values_mut() {
+                packets.retain(|p| p.program_number != *number);
             }
-            PID::SDT | PID::EIT /* | PID::NIT | PID::CAT | PID::BAT */ =>
-                self.demux_section((pid, false), &pkt)?,
 
-            PID::Other(..) => {
-                // PAT not ready yet
-                // wait for PAT
-                if self.pmt_pids.0.is_empty() {
-                    return Ok(true);
-                }
+            let is_stale = |id: &SubtableID| matches!(id, SubtableID::PMT(_, n, _) if n == number);
+            self.pmt.map.retain(|id, _| !is_stale(id));
+            self.pmt.versions.retain(|id, _| !is_stale(id));
+        }
+        self.packets.0.retain(|_, packets| !packets.is_empty());
 
-                match self.pmt_pids.is_packet_builded(pid) {
-                    Some(true) => { // got PMT and already builded
-                        self.demux_section((pid, true), &pkt)?;
-                    },
-                    Some(false) => { // got PMT and not builded
-                        self.demux_section((pid, true), &pkt)?;
+        self.pmt_pids
+            .0
+            .retain(|(pid, _)| programs.iter().any(|(_, p)| p == pid));
+        for (_, pid) in programs.iter() {
+            self.pmt_pids.push_uniq(*pid);
+        }
 
-                        self.build_packets();
+        self.pat_programs = programs;
+    }
 
-                        self.pmt_pids.set_is_packet_builded(pid, true);
-                    },
-                    None => {return Ok(false); }
+    /// build packets cache
+    // TODO: also do via iterator
+    // TODO: .iter().collect() for lazy collection
+    #[inline(always)]
+    fn build_packets(&mut self) {
+        for (_, table) in self.pmt.map.iter().filter(|(_, t)| t.is_complete()) {
+            // corrupt PMT must not register elementary PIDs
+            if !table.is_pmt_crc32_valid() {
+                continue;
+            }
+
+            for raw in table.sections_bytes() {
+                let pmt = PMT::new(&raw);
+
+                let program_number = pmt.program_number();
+
+                self.pcr_pids.insert(program_number, pmt.pcr_pid());
+
+                for stream in pmt.streams().filter_map(Result::ok) {
+                    let pid = PID::from(stream.pid());
+                    let packets = self.packets.0.entry(pid).or_default();
+
+                    if !packets.iter().any(|p| p.program_number == program_number) {
+                        packets.push(Packet::new(pid, program_number, stream.stream_type()));
+                    }
                 }
             }
-            _ => {}
         }
+    }
 
-        Ok(true)
+    #[inline(always)]
+    fn demux_private_section(&mut self, pid: PID, pkt: &TsPacket) -> Result<()> {
+        match self.private.get_mut(&pid) {
+            Some(private) => private.push(pkt),
+            None => Ok(()),
+        }
     }
 
-    /// ffmpeg::av_read_frame analog
-    pub fn demux_packets(&mut self, raw: &[u8]) -> Result<()> {
-        self.offset += raw.len();
+    /// return: table completed by this packet?
+    #[inline(always)]
+    fn demux_section(&mut self, pid_or_pmt: (PID, bool), pkt: &TsPacket) -> Result<bool> {
+        self.demux_section_pkt(pid_or_pmt, pkt).map_err(|err| {
+            err.with_context(match pid_or_pmt {
+                (_, true) => ":section PMT",
+                (PID::PAT, false) => ":section PAT",
+                (PID::SDT, false) => ":section SDT",
+                (PID::EIT, false) => ":section EIT",
+                (PID::NIT, false) => ":section NIT",
+                (PID::CAT, false) => ":section CAT",
+                _ => ":section",
+            })
+        })
+    }
 
-        let pkt = TsPacket::new(&raw)?;
-        let pid = pkt.pid();
+    /// PSI/SI tables of PID
+    #[inline(always)]
+    fn tables_of(&mut self, pid_or_pmt: (PID, bool)) -> &mut Tables {
+        match pid_or_pmt {
+            (PID::PAT, false) => &mut self.pat,
+            (PID::SDT, false) => &mut self.sdt,
+            (PID::EIT, false) => &mut self.eit,
+            (PID::NIT, false) => &mut self.nit,
+            (PID::CAT, false) => &mut self.cat,
+            (_, true) => &mut self.pmt,
+            _ => unreachable!(),
+        }
+    }
 
-        if pid.is_null() // null packet PID
-        && !pid.is_other() // PID is section/table PID
-        // PAT not ready yet
-        // wait for pat
-        && !self.pmt_pids.0.is_empty()
-        {
-            return Ok(());
+    /// PAT/PMT/CAT are limited to 1021
+    #[inline(always)]
+    fn max_section_length_of(&self, pid_or_pmt: (PID, bool)) -> usize {
+        match pid_or_pmt {
+            (PID::PAT, false) | (PID::CAT, false) | (_, true) => {
+                self.max_section_length.min(HEADER_MAX_SECTION_LENGTH)
+            }
+            _ => self.max_section_length,
         }
+    }
 
-        let mut packet = match self.packets.0.get_mut(&pid) {
-            Some(packet) => packet,
-            None => return Ok(()), // packet is not builder - wait fot PMT
-        };
+    // TODO: move to macros?
+    #[inline(always)]
+    fn demux_section_pkt(&mut self, pid_or_pmt: (PID, bool), pkt: &TsPacket) -> Result<bool> {
+        let pid = pid_or_pmt.0;
+        let max_section_length = self.max_section_length_of(pid_or_pmt);
+        let payload = pkt.buf_payload_pes()?;
+
+        // taken out while complete sections are applied to tables
+        let mut assembler = self
+            .tables_of(pid_or_pmt)
+            .assemblers
+            .remove(&pid)
+            .unwrap_or_default();
+        assembler.set_max_section_length(max_section_length);
+        assembler.set_begin_sz(Self::header_sz_of(pid_or_pmt));
+
+        let mut complete = false;
+        let mut err = None;
+
+        let res = assembler.push_with(payload, pkt.pusi(), |raw, is_complete| {
+            if !is_complete {
+                self.section_begin(pid_or_pmt, raw);
+                return;
+            }
 
-        let mut buf = pkt.buf_payload_pes()?;
+            match self.section_complete(pid_or_pmt, raw) {
+                Ok(c) => complete |= c,
+                Err(e) => {
+                    err.get_or_insert(e);
+                }
+            }
+        });
 
-        if pkt.pusi() {
-            let pes = PES::new(buf);
+        self.tables_of(pid_or_pmt).assemblers.insert(pid, assembler);
 
-            if !packet.buf.is_empty() {
-                // emit
-                self.events.on_packet(packet);
-            }
+        res?;
 
-            packet.buf.reset();
-            packet.started = true;
-            packet.offset += self.offset + raw.len() - buf.len();
-            packet.pts = pes.pts().map(Duration::from);
-            packet.dts = pes.dts().map(Duration::from);
+        match err {
+            Some(err) => Err(err),
+            None => Ok(complete),
+        }
+    }
 
-            buf = pes.buf_seek_payload();
+    /// syntax-section header size of PSI/SI table
+    #[inline(always)]
+    fn header_sz_of(pid_or_pmt: (PID, bool)) -> usize {
+        match pid_or_pmt {
+            (PID::PAT, false) => PAT::HEADER_FULL_SZ,
+            (PID::SDT, false) => SDT::HEADER_FULL_SZ,
+            (PID::EIT, false) => EIT::HEADER_FULL_SZ,
+            (_, true) => PMT::HEADER_FULL_SZ,
+            _ => unreachable!(),
         }
+    }
+
+    /// parse section header;
+    /// return: (subtable-id, sz, section-number, last-section-number, current-next-indicator)
+    #[inline(always)]
+    fn section_header(
+        pid_or_pmt: (PID, bool),
+        raw: &[u8],
+    ) -> Result<(SubtableID, usize, u8, u8, bool)> {
+        Ok(match pid_or_pmt {
+            (PID::PAT, false) => {
+                let s = PAT::try_new(raw)?;
+                (
+                    s.subtable_id(),
+                    s.sz(),
+                    s.section_number(),
+                    s.last_section_number(),
+                    s.current_next_indicator(),
+                )
+            }
+            (PID::SDT, false) => {
+                let s = SDT::try_new(raw)?;
+                (
+                    s.subtable_id(),
+                    s.sz(),
+                    s.section_number(),
+                    s.last_section_number(),
+                    s.current_next_indicator(),
+                )
+            }
+            (PID::EIT, false) => {
+                let s = EIT::try_new(raw)?;
+                (
+                    s.subtable_id(),
+                    s.sz(),
+                    s.section_number(),
+                    s.last_section_number(),
+                    s.current_next_indicator(),
+                )
+            }
+            (_, true) => {
+                let s = PMT::try_new(raw)?;
+                (
+                    s.subtable_id(),
+                    s.sz(),
+                    s.section_number(),
+                    s.last_section_number(),
+                    s.current_next_indicator(),
+                )
+            }
+            _ => unreachable!(),
+        })
+    }
+
+    /// section header is buffered;
+    /// malformed header is reported on completion
+    #[inline(always)]
+    fn section_begin(&mut self, pid_or_pmt: (PID, bool), raw: &[u8]) {
+        let (id, sz, section_number, _, current) = match Self::section_header(pid_or_pmt, raw) {
+            Ok(header) => header,
+            Err(_) => return,
+        };
 
-        if packet.started {
-            packet.buf.0.write_all(buf)?;
+        if !current && !self.include_next {
+            return;
         }
 
-        Ok(())
+        self.events.on_trace(Trace::SectionBegin {
+            pid: pid_or_pmt.0,
+            id,
+            section_number,
+            sz,
+        });
     }
-}
+
+    /// store complete section (header, data, CRC) into its table;
+    /// return: table completed?
+    #[inline(always)]
+    fn section_complete(&mut self, pid_or_pmt: (PID, bool), raw: &[u8]) -> Result<bool> {
+        let (id, _, section_number, last_section_number, current) =
+            Self::section_header(pid_or_pmt, raw)?;
+
+        self.repetition.see(id);
+
+        // not yet applicable table;
+        // section is consumed but not assembled
+        if !current && !self.include_next {
+            return Ok(false);
+        }
+
+        let segment_last_section_number = match pid_or_pmt {
+            (PID::EIT, false) => Some(EIT::new(raw).segment_last_section_number()),
+            _ => None,
+        };
+
+        let sz = raw.len();
+
+        let Demuxer {
+            pat,
+            pmt,
+            eit,
+            sdt,
+            nit,
+            cat,
+            services,
+            epg,
+            events,
+            ..
+        } = self;
+
+        let tables = match pid_or_pmt {
+            (PID::PAT, false) => pat,
+            (PID::SDT, false) => sdt,
+            (PID::EIT, false) => eit,
+            (PID::NIT, false) => nit,
+            (PID::CAT, false) => cat,
+            (_, true) => pmt,
+            _ => unreachable!(),
+        };
+
+        let table = tables
+            .map
+            .entry(id)
+            .or_insert_with(|| Table::new(last_section_number));
+
+        let section_ref = match table.sections.get_mut(section_number) {
+            Some(section_ref) => section_ref.clone(),
+            None => {
+                let section = Section::new(section_number, sz, tables.pool.take());
+
+                let section_ref = section.into_ref();
+                table.sections.push(section_ref.clone());
+                section_ref
+            }
+        };
+
+        {
+            let mut section = (*section_ref).borrow_mut();
+            section.buf.reset();
+            section.sz = sz;
+            section.segment_last_section_number = segment_last_section_number;
+            section.buf.0.write_all(raw)?;
+        }
+
+        events.on_trace(Trace::SectionComplete {
+            pid: pid_or_pmt.0,
+            id,
+            section_number,
+        });
+
+        if !table.is_complete() {
+            return Ok(false);
+        }
+
+        // corrupt PMT is kept incomplete-like:
+        // next repetition overwrites its sections
+        if pid_or_pmt.1 && !table.is_pmt_crc32_valid() {
+            return Ok(false);
+        }
+
+        let old_version = tables.version_update(id);
+        let table = &tables.map[&id];
+
+        services.update(id, table);
+        epg.update(id, table);
+
+        // emit
+        if let Some(old_version) = old_version {
+            events.on_table_update(old_version, id, table);
+        }
+        events.on_table(id, table);
+
+        Ok(true)
+    }
+
+    /// per ts-packet bookkeeping: offset, PCR, transport-error-indicator;
+    /// return: skip packet?
+    #[inline(always)]
+    fn begin(&mut self, raw: &[u8], pkt: &TsPacket) -> bool {
+        let offset = self.offset;
+        self.offset += raw.len();
+
+        let adapt = pkt.adaptation().and_then(Result::ok);
+        let discontinuity = adapt.as_ref().is_some_and(|a| a.discontinuity_indicator());
+        let pcr = adapt.as_ref().and_then(|a| a.pcr()).map(|pcr| pcr.value());
+
+        let pid = pkt.pid();
+        let (first_seen, cc_error) = self.stats.push(raw, pkt, discontinuity, pcr);
+
+        if first_seen {
+            self.events.on_trace(Trace::PidSeen { pid, offset });
+        }
+        if let Some(last) = cc_error {
+            self.events.on_trace(Trace::CcError {
+                pid,
+                offset,
+                last,
+                cc: pkt.cc(),
+            });
+        }
+        if discontinuity {
+            self.events.on_trace(Trace::Discontinuity { pid, offset });
+        }
+
+        if pkt.tei() {
+            // emit
+            self.events.on_corrupt_packet(pid);
+
+            if self.skip_corrupt {
+                return true;
+            }
+        }
+
+        if let Some(pcr) = pcr {
+            self.bitrate.push(pid, offset, pcr, discontinuity);
+        }
+
+        if pkt.is_scrambled() {
+            self.services.mark_scrambled(u16::from(pid));
+
+            // emit
+            self.events.on_scrambled_packet(pid);
+
+            if self.skip_scrambled {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn demux(&mut self, raw: &[u8]) -> Result<()> {
+        self.probe_count();
+
+        let offset = self.offset;
+        let pkt = TsPacket::new(raw).map_err(|err| err_context(err, None, offset))?;
+
+        if self.begin(raw, &pkt) {
+            return Ok(());
+        }
+
+        let is_table = self
+            .demux_tables_pkt(&pkt)
+            .map_err(|err| err_context(err, Some(pkt.pid()), offset))?;
+        self.probe_check();
+
+        if is_table {
+            return Ok(());
+        }
+
+        self.demux_packets_pkt(raw, &pkt)
+            .map_err(|err| err_context(err, Some(pkt.pid()), offset))
+    }
+
+    /// demux UDP/RTP payload of whole ts-packets
+    /// (typically 7 x 188 = 1316 bytes) in place;
+    /// partial trailing packet fails the datagram before demuxing;
+    /// packet errors do not stop the rest of datagram,
+    /// the first one is returned
+    pub fn demux_datagram(&mut self, datagram: &[u8]) -> Result<()> {
+        let tail = datagram.len() % TsPacket::SZ;
+        if tail != 0 {
+            return Err(Error::new(ErrorKind::Buf(
+                datagram.len(),
+                datagram.len() - tail + TsPacket::SZ,
+            )));
+        }
+
+        let mut res = Ok(());
+
+        for raw in datagram.chunks_exact(TsPacket::SZ) {
+            let r = self.demux(raw);
+            if res.is_ok() {
+                res = r;
+            }
+        }
+
+        res
+    }
+
+    /// demux RTP (RFC 2250) datagram with whole ts-packets;
+    /// sequence-number gaps are reported via on_rtp_gap
+    pub fn demux_rtp(&mut self, datagram: &[u8]) -> Result<()> {
+        let rtp = RTP::try_new(datagram)?;
+
+        if rtp.payload_type() != RTP::PAYLOAD_TYPE_MP2T {
+            return Err(Error::new(ErrorKind::RTPPayloadType(rtp.payload_type())));
+        }
+
+        let seq = rtp.sequence_number();
+        if let Some(expected) = self.rtp_seq.map(|last| last.wrapping_add(1)) {
+            if seq != expected {
+                // emit
+                self.events.on_rtp_gap(expected, seq);
+            }
+        }
+        self.rtp_seq = Some(seq);
+
+        self.demux_datagram(rtp.buf_payload())
+    }
+
+    /// ffmpeg::avformat_open_input analog
+    /// probe input
+    /// return: is pid handled?
+    pub fn demux_tables(&mut self, raw: &[u8]) -> Result<bool> {
+        self.probe_count();
+
+        let offset = self.offset;
+        let pkt = TsPacket::new(raw).map_err(|err| err_context(err, None, offset))?;
+
+        if self.begin(raw, &pkt) {
+            return Ok(true);
+        }
+
+        let is_table = self
+            .demux_tables_pkt(&pkt)
+            .map_err(|err| err_context(err, Some(pkt.pid()), offset))?;
+        self.probe_check();
+
+        Ok(is_table)
+    }
+
+    /// ffmpeg::av_read_frame analog
+    pub fn demux_packets(&mut self, raw: &[u8]) -> Result<()> {
+        self.probe_count();
+
+        let offset = self.offset;
+        let pkt = TsPacket::new(raw).map_err(|err| err_context(err, None, offset))?;
+
+        if self.begin(raw, &pkt) {
+            return Ok(());
+        }
+
+        self.demux_packets_pkt(raw, &pkt)
+            .map_err(|err| err_context(err, Some(pkt.pid()), offset))
+    }
+
+    #[inline(always)]
+    fn demux_tables_pkt(&mut self, pkt: &TsPacket) -> Result<bool> {
+        let pid = pkt.pid();
+
+        if pid.is_null() {
+            // null packet PID
+            return Ok(true);
+        }
+
+        if self.private.contains_key(&pid) {
+            self.demux_private_section(pid, pkt)
+                .map_err(|err| err.with_context(":section private"))?;
+            return Ok(true);
+        }
+
+        match pid {
+            PID::PAT => {
+                let complete = self.demux_section((pid, false), pkt)?;
+
+                // extract pids from PAT;
+                // rebuilt on programs change
+                if complete {
+                    self.build_pmt_pids();
+                }
+            }
+            PID::SDT | PID::EIT /* | PID::NIT | PID::CAT | PID::BAT */ => {
+                self.demux_section((pid, false), pkt)?;
+            }
+
+            PID::Other(..) => {
+                // PAT not ready yet
+                // wait for PAT
+                if self.pmt_pids.0.is_empty() {
+                    return Ok(true);
+                }
+
+                match self.pmt_pids.is_packet_builded(pid) {
+                    Some(true) => { // got PMT and already builded
+                        self.demux_section((pid, true), pkt)?;
+                    },
+                    Some(false) => { // got PMT and not builded
+                        // wait for all PMT sections
+                        if self.demux_section((pid, true), pkt)? {
+                            self.build_packets();
+
+                            self.pmt_pids.set_is_packet_builded(pid, true);
+                        }
+                    },
+                    None => {return Ok(false); }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(true)
+    }
+
+    #[inline(always)]
+    fn demux_packets_pkt(&mut self, raw: &[u8], pkt: &TsPacket) -> Result<()> {
+        let pid = pkt.pid();
+
+        // null packet, section/table or reserved PID
+        if !pid.is_other() {
+            return Ok(());
+        }
+
+        // PAT not ready yet
+        // wait for pat
+        if self.pmt_pids.0.is_empty() {
+            return Ok(());
+        }
+
+        let packets = match self.packets.0.get_mut(&pid) {
+            Some(packets) => packets,
+            None => return Ok(()), // packet is not builder - wait fot PMT
+        };
+
+        let buf = pkt.buf_payload_pes()?;
+
+        if pkt.pusi() {
+            let random_access = pkt
+                .adaptation()
+                .and_then(Result::ok)
+                .is_some_and(|a| a.random_access_indicator());
+
+            for packet in packets.iter_mut() {
+                if !packet.header_pending && !packet.buf.is_empty() {
+                    if packet.video_params.is_none() {
+                        packet.video_params =
+                            VideoParams::from_es(&packet.stream_type, packet.buf.as_slice());
+                    }
+                    if packet.audio_params.is_none() {
+                        packet.audio_params =
+                            AudioParams::from_es(&packet.stream_type, packet.buf.as_slice());
+                    }
+
+                    // emit
+                    self.events.on_packet(packet);
+
+                    if self.access_units && matches!(packet.stream_type, StreamType::H264) {
+                        let events = &mut self.events;
+                        let pid = packet.pid;
+
+                        packet
+                            .access_units
+                            .get_or_insert_with(AccessUnits::new)
+                            .push(packet.buf.as_slice(), packet.pts, packet.dts, |au| {
+                                // emit
+                                events.on_access_unit(pid, au)
+                            });
+                    }
+                }
+
+                packet.buf.reset();
+                packet.started = true;
+                packet.offset = self.offset - raw.len();
+                packet.stream_id = None;
+                packet.pts = None;
+                packet.dts = None;
+                packet.random_access = random_access;
+                packet.header_pending = true;
+            }
+        }
+
+        for packet in packets.iter_mut().filter(|p| p.started) {
+            packet.write(buf)?;
+        }
+
+        Ok(())
+    }
+}
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::{
+        Bitrate, Demuxer, DemuxerEvents, Packet, PidRole, Section, Severity, SignalingIssue, Table,
+        Trace,
+    };
+    use crate::error::{Error, Kind as ErrorKind};
+    use crate::h264::AccessUnit;
+    use crate::packet::Packet as TsPacket;
+    use crate::pcr::PCR;
+    use crate::pes::StreamID;
+    use crate::pid::PID;
+    use crate::section::SDT;
+    use crate::stream_type::StreamType;
+    use crate::subtable_id::{SubtableID, SubtableKind};
+    use crate::synthetic::{self, PacketBuilder, Packetizer};
+    use crate::table_id::TableID;
+
+    #[derive(Default)]
+    struct Events {
+        tables: usize,
+        updates: Vec<(u8, SubtableID)>,
+        random_access: Vec<bool>,
+        stream_ids: Vec<Option<StreamID>>,
+        programs: Vec<(u16, PID)>,
+        corrupt: Vec<PID>,
+        scrambled: Vec<PID>,
+        pat_programs: Vec<(u16, u16)>,
+        event_ids: Vec<u16>,
+        timeouts: Vec<(SubtableID, Duration)>,
+        probe_complete: usize,
+        probe_failed: usize,
+        sections: Vec<Vec<u8>>,
+        payloads: Vec<(usize, Option<Duration>)>,
+        access_units: Vec<(PID, Option<Duration>, bool)>,
+        traces: Vec<Trace>,
+        rtp_gaps: Vec<(u16, u16)>,
+    }
+
+    impl DemuxerEvents for Events {
+        fn on_table(&mut self, id: SubtableID, table: &Table) {
+            self.tables += 1;
+            self.sections
+                .extend(table.sections_bytes().map(|raw| raw.to_vec()));
+
+            match id {
+                SubtableID::PAT(..) => self.pat_programs.extend(
+                    table
+                        .view()
+                        .programs()
+                        .filter_map(Result::ok)
+                        .filter(|p| p.pid().is_program_map())
+                        .map(|p| (p.number(), p.pid_raw())),
+                ),
+                SubtableID::EIT(..) => self.event_ids.extend(
+                    table
+                        .view()
+                        .events()
+                        .filter_map(Result::ok)
+                        .map(|e| e.event_id()),
+                ),
+                _ => {}
+            }
+        }
+
+        fn on_table_update(&mut self, old_version: u8, id: SubtableID, _: &Table) {
+            self.updates.push((old_version, id));
+        }
+
+        fn on_packet(&mut self, pkt: &Packet) {
+            self.random_access.push(pkt.random_access);
+            self.stream_ids.push(pkt.stream_id);
+            self.programs.push((pkt.program_number, pkt.pid));
+            self.payloads.push((pkt.buf.sz(), pkt.pts));
+        }
+
+        fn on_access_unit(&mut self, pid: PID, au: &AccessUnit) {
+            self.access_units.push((pid, au.pts, au.random_access));
+        }
+
+        fn on_corrupt_packet(&mut self, pid: PID) {
+            self.corrupt.push(pid);
+        }
+
+        fn on_scrambled_packet(&mut self, pid: PID) {
+            self.scrambled.push(pid);
+        }
+
+        fn on_probe_complete(&mut self) {
+            self.probe_complete += 1;
+        }
+
+        fn on_probe_failed(&mut self) {
+            self.probe_failed += 1;
+        }
+
+        fn on_table_timeout(&mut self, id: SubtableID, age: Duration) {
+            self.timeouts.push((id, age));
+        }
+
+        fn on_rtp_gap(&mut self, expected: u16, got: u16) {
+            self.rtp_gaps.push((expected, got));
+        }
+
+        fn on_trace(&mut self, trace: Trace) {
+            self.traces.push(trace);
+        }
+    }
+
+    /// PES start packet on pid 0x101 with adaptation field
+    fn pes_packet(random_access: bool) -> [u8; TsPacket::SZ] {
+        let flags = if random_access { 0x40 } else { 0x00 };
+        // PES header, no PTS/DTS
+        let pes = synthetic::pes(0xE0, None, None, &[0xAA; 8]);
+
+        PacketBuilder::new(0x101)
+            .pusi(true)
+            .adaptation(&[flags])
+            .payload(&pes)
+            .build()
+    }
+
+    /// single-section PSI packet
+    fn psi_packet(
+        pid: u16,
+        table_id: u8,
+        ext: u16,
+        version_number: u8,
+        body: &[u8],
+    ) -> [u8; TsPacket::SZ] {
+        let section = synthetic::section(table_id, ext, version_number, (0, 0), body);
+
+        Packetizer::new(pid).section(&section)[0]
+    }
+
+    /// PAT (program 1 -> pmt pid 0x100)
+    fn pat_packet(version_number: u8) -> [u8; TsPacket::SZ] {
+        psi_packet(0x0000, 0x00, 1, version_number, &[0x00, 0x01, 0xE1, 0x00])
+    }
+
+    /// PAT with (program-number, pmt-pid)
+    fn pat_programs_packet(programs: &[(u16, u16)]) -> [u8; TsPacket::SZ] {
+        Packetizer::new(0x0000).section(&synthetic::pat(1, 0, programs))[0]
+    }
+
+    /// PMT with h264 streams
+    fn pmt_program_packet(pmt_pid: u16, program_number: u16, pids: &[u16]) -> [u8; TsPacket::SZ] {
+        let streams = pids.iter().map(|pid| (0x1B, *pid)).collect::<Vec<_>>();
+
+        Packetizer::new(pmt_pid).section(&synthetic::pmt(program_number, 0x101, &streams))[0]
+    }
+
+    /// PMT (program 1, h264 @ 0x101)
+    fn pmt_packet() -> [u8; TsPacket::SZ] {
+        pmt_program_packet(0x0100, 1, &[0x0101])
+    }
+
+    #[test]
+    fn pmt_crc32_corrupt() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        let mut pmt = pmt_program_packet(0x100, 1, &[0x101, 0x102]);
+        // last CRC byte; section follows 4-byte header and pointer-field
+        let crc_pos = 5 + 12 + 2 * 5 + 3;
+        pmt[crc_pos] ^= 0xFF;
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.demux(&pmt).unwrap();
+
+        assert!(demuxer.program_pids(1).is_empty());
+        assert!(demuxer.pcr_pid(1).is_none());
+        assert_eq!(demuxer.events.tables, 1);
+
+        // valid repetition
+        demuxer
+            .demux(&pmt_program_packet(0x100, 1, &[0x101, 0x102]))
+            .unwrap();
+
+        assert_eq!(demuxer.program_pids(1).len(), 2);
+        assert_eq!(demuxer.events.tables, 2);
+    }
+
+    #[test]
+    fn table_progress() {
+        let mut table = Table::new(2);
+
+        // 1-byte sections; section 1 is not received yet
+        let sections =
+            [0, 1, 2].map(|number| Section::new(number, 1, Default::default()).into_ref());
+        for (section_ref, data) in sections.iter().zip([&[0x00][..], &[], &[0x00]]) {
+            section_ref.borrow_mut().buf.0.write_all(data).unwrap();
+            table.sections.push(section_ref.clone());
+        }
+
+        assert_eq!(table.expected_sections(), 3);
+        assert_eq!(table.received_sections(), 2);
+        assert!(!table.is_complete());
+
+        sections[1].borrow_mut().buf.0.write_all(&[0x00]).unwrap();
+
+        assert_eq!(table.received_sections(), 3);
+        assert!(table.is_complete());
+    }
+
+    #[test]
+    fn table_update_on_version_change() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.demux(&pat_packet(0)).unwrap();
+        assert_eq!(demuxer.events.tables, 2);
+        assert!(demuxer.events.updates.is_empty());
+
+        demuxer.demux(&pat_packet(1)).unwrap();
+        assert_eq!(demuxer.events.tables, 3);
+        assert_eq!(demuxer.events.updates.len(), 1);
+
+        let (old_version, id) = demuxer.events.updates[0];
+        assert_eq!(old_version, 0);
+        assert_eq!(id.version_number(), 1);
+
+        // outdated version dropped
+        assert_eq!(demuxer.pat.map.len(), 1);
+    }
+
+    #[test]
+    fn section_pool() {
+        let mut demuxer = Demuxer::new(Events::default());
+        demuxer.set_section_capacity(256);
+        demuxer.set_section_pool(4);
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        assert!(demuxer.pat.pool.free.is_empty());
+
+        // outdated version 0 buffer is recycled
+        demuxer.demux(&pat_packet(1)).unwrap();
+        assert_eq!(demuxer.pat.pool.free.len(), 1);
+        assert_eq!(demuxer.pat.pool.free[0].0.get_ref().capacity(), 256);
+
+        // and reused by version 2
+        demuxer.demux(&pat_packet(2)).unwrap();
+        assert_eq!(demuxer.pat.pool.free.len(), 1);
+        assert_eq!(demuxer.events.tables, 3);
+
+        demuxer.set_section_pool(0);
+        assert!(demuxer.pat.pool.free.is_empty());
+    }
+
+    #[test]
+    fn packet_random_access() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.demux(&pmt_packet()).unwrap();
+
+        demuxer.demux(&pes_packet(true)).unwrap();
+        demuxer.demux(&pes_packet(false)).unwrap();
+        demuxer.demux(&pes_packet(true)).unwrap();
+
+        assert_eq!(demuxer.events.random_access, vec![true, false]);
+        assert_eq!(
+            demuxer.events.stream_ids,
+            vec![Some(StreamID::VideoStreamNumber(0xE0)); 2]
+        );
+    }
+
+    #[test]
+    fn pes_header_split() {
+        let mut demuxer = Demuxer::new(Events::default());
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.demux(&pmt_packet()).unwrap();
+
+        #[rustfmt::skip]
+        let pes = [
+            0x00, 0x00, 0x01, 0xE0, 0x00, 0x00, 0x80, 0x80, 0x05,
+            // PTS 90000
+            0x21, 0x00, 0x05, 0xBF, 0x21,
+        ];
+
+        // adaptation stuffing; only 4 bytes of PES header fit
+        let mut raw = [0xFFu8; TsPacket::SZ];
+        raw[..6].copy_from_slice(&[0x47, 0x41, 0x01, 0x30, 179, 0x00]);
+        raw[184..].copy_from_slice(&pes[..4]);
+        demuxer.demux(&raw).unwrap();
+
+        let mut raw = [0xFFu8; TsPacket::SZ];
+        raw[..4].copy_from_slice(&[0x47, 0x01, 0x01, 0x11]);
+        raw[4..4 + pes.len() - 4].copy_from_slice(&pes[4..]);
+        demuxer.demux(&raw).unwrap();
+
+        let packet = &demuxer.packets.0[&PID::Other(0x101)][0];
+        assert_eq!(packet.stream_id, Some(StreamID::VideoStreamNumber(0xE0)));
+        assert_eq!(packet.pts, Some(Duration::from_secs(1)));
+        assert_eq!(packet.buf.sz(), TsPacket::SZ - 4 - (pes.len() - 4));
+
+        // flush
+        demuxer.demux(&pes_packet(true)).unwrap();
+        assert_eq!(
+            demuxer.events.stream_ids,
+            [Some(StreamID::VideoStreamNumber(0xE0))]
+        );
+    }
+
+    #[test]
+    fn demux_packets_pid_filter() {
+        let pes = synthetic::pes(0xE0, None, None, &[0xAA; 8]);
+        let null = PacketBuilder::new(0x1FFF).pusi(true).payload(&pes).build();
+
+        let mut demuxer = Demuxer::new(Events::default());
+
+        // PAT not ready yet
+        demuxer.demux_packets(&pes_packet(true)).unwrap();
+
+        demuxer.demux_tables(&pat_packet(0)).unwrap();
+        demuxer.demux_tables(&pmt_packet()).unwrap();
+
+        // null, section and PES PIDs
+        for raw in [
+            null,
+            pat_packet(0),
+            pes_packet(true),
+            null,
+            pes_packet(true),
+        ] {
+            demuxer.demux_packets(&raw).unwrap();
+        }
+
+        assert_eq!(demuxer.events.tables, 2);
+        assert_eq!(
+            demuxer.events.stream_ids,
+            vec![Some(StreamID::VideoStreamNumber(0xE0))]
+        );
+    }
+
+    #[test]
+    fn reset() {
+        let mut demuxer = Demuxer::new(Events::default());
+        demuxer.set_skip_scrambled(false);
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.demux(&pmt_packet()).unwrap();
+        demuxer.demux(&pes_packet(true)).unwrap();
+
+        demuxer.reset();
+
+        assert!(demuxer.pat.map.is_empty());
+        assert!(demuxer.pmt_pids.0.is_empty());
+        assert!(demuxer.packets.0.is_empty());
+        assert_eq!(demuxer.services().count(), 0);
+        assert!(demuxer.stats().get(PID::PAT).is_none());
+        assert!(!demuxer.skip_scrambled);
+
+        // pending PES of previous stream is not emitted
+        demuxer.demux(&pes_packet(true)).unwrap();
+        assert!(demuxer.events.stream_ids.is_empty());
+
+        // events state is kept, same PAT version is reported again
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.demux(&pmt_packet()).unwrap();
+        assert_eq!(demuxer.events.tables, 4);
+        assert!(demuxer.events.updates.is_empty());
+    }
+
+    #[test]
+    fn pat_programs_change() {
+        let pes = synthetic::pes(0xE0, None, None, &[0xAA; 8]);
+        let pes_201 = PacketBuilder::new(0x201).pusi(true).payload(&pes).build();
+
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.demux(&pmt_packet()).unwrap();
+        demuxer.demux(&pes_packet(true)).unwrap();
+        demuxer.demux(&pes_packet(true)).unwrap();
+        assert_eq!(demuxer.events.stream_ids.len(), 1);
+
+        // program 1 -> program 2 (pmt pid 0x200, h264 @ 0x201)
+        let pat = synthetic::pat(1, 1, &[(2, 0x200)]);
+        demuxer
+            .demux(&Packetizer::new(0x0000).section(&pat)[0])
+            .unwrap();
+
+        assert_eq!(demuxer.pmt_pids.0, vec![(PID::Other(0x200), false)]);
+        assert!(demuxer.packets.0.is_empty());
+        assert!(demuxer.pmt.map.is_empty());
+        assert_eq!(demuxer.program_pcr(1), None);
+
+        // old PMT PID and elementary stream are not demuxed anymore
+        demuxer.demux(&pmt_packet()).unwrap();
+        demuxer.demux(&pes_packet(true)).unwrap();
+        demuxer.demux(&pes_packet(true)).unwrap();
+        assert_eq!(demuxer.events.stream_ids.len(), 1);
+
+        demuxer
+            .demux(&pmt_program_packet(0x200, 2, &[0x201]))
+            .unwrap();
+        demuxer.demux(&pes_201).unwrap();
+        demuxer.demux(&pes_201).unwrap();
+        assert_eq!(demuxer.events.stream_ids.len(), 2);
+        assert_eq!(demuxer.pmt_pids.0, vec![(PID::Other(0x200), true)]);
+    }
+
+    #[test]
+    fn program_pids() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        assert!(demuxer.program_pids(1).is_empty());
+
+        demuxer
+            .demux(&pmt_program_packet(0x100, 1, &[0x102, 0x101]))
+            .unwrap();
+
+        let pids = demuxer.program_pids(1);
+        assert_eq!(
+            pids.iter().map(|(pid, _)| *pid).collect::<Vec<_>>(),
+            vec![PID::Other(0x101), PID::Other(0x102)]
+        );
+        assert!(pids.iter().all(|(_, st)| *st == StreamType::H264));
+        assert!(demuxer.program_pids(2).is_empty());
+    }
+
+    #[test]
+    fn probe_complete() {
+        let sdt = psi_packet(0x0011, 0x42, 1, 0, &[0x00, 0x01, 0xFF]);
+
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        assert_eq!(demuxer.events.probe_complete, 0);
+
+        demuxer.demux(&pmt_packet()).unwrap();
+        assert_eq!(demuxer.events.probe_complete, 1);
+        assert!(demuxer.is_probe_complete());
+
+        // fired once
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.demux(&pmt_packet()).unwrap();
+        assert_eq!(demuxer.events.probe_complete, 1);
+
+        demuxer.reset();
+        demuxer.set_probe_tables(&[SubtableKind::PAT, SubtableKind::PMT, SubtableKind::SDT]);
+
+        demuxer.demux_tables(&pat_packet(0)).unwrap();
+        demuxer.demux_tables(&pmt_packet()).unwrap();
+        assert!(!demuxer.is_probe_complete());
+
+        demuxer.demux_tables(&sdt).unwrap();
+        assert_eq!(demuxer.events.probe_complete, 2);
+    }
+
+    #[test]
+    fn probe_timeout() {
+        let es = PacketBuilder::new(0x101).payload(&[0xAA; 16]).build();
+
+        let mut demuxer = Demuxer::new(Events::default());
+        demuxer.set_probe_timeout(3);
+
+        for _ in 0..2 {
+            demuxer.demux(&es).unwrap();
+        }
+        assert!(!demuxer.is_probe_failed());
+
+        // fired once
+        for _ in 0..3 {
+            demuxer.demux(&es).unwrap();
+        }
+        assert_eq!(demuxer.events.probe_failed, 1);
+        assert!(demuxer.is_probe_failed());
+
+        // late signaling still completes probe
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.demux(&pmt_packet()).unwrap();
+        assert_eq!(demuxer.events.probe_complete, 1);
+
+        // completed in time
+        demuxer.reset();
+        assert!(!demuxer.is_probe_failed());
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.demux(&pmt_packet()).unwrap();
+        for _ in 0..3 {
+            demuxer.demux(&es).unwrap();
+        }
+        assert_eq!(demuxer.events.probe_failed, 1);
+
+        // disabled
+        demuxer.reset();
+        demuxer.set_probe_timeout(0);
+        for _ in 0..8 {
+            demuxer.demux(&es).unwrap();
+        }
+        assert!(!demuxer.is_probe_failed());
+    }
+
+    #[test]
+    fn probe_timeout_skipped_and_failed() {
+        let corrupt = PacketBuilder::new(0x101)
+            .tei(true)
+            .payload(&[0xAA; 16])
+            .build();
+        let scrambled = PacketBuilder::new(0x101)
+            .tsc(0b10)
+            .payload(&[0xAA; 16])
+            .build();
+        let mut pat = synthetic::pat(1, 0, &[(1, 0x100)]);
+        pat[1] &= 0x7F;
+        let invalid = Packetizer::new(0x0000).section(&pat)[0];
+        let mut no_sync = invalid;
+        no_sync[0] = 0x00;
+
+        let mut demuxer = Demuxer::new(Events::default());
+        demuxer.set_skip_corrupt(true);
+        demuxer.set_skip_scrambled(true);
+        demuxer.set_probe_timeout(5);
+
+        demuxer.demux(&corrupt).unwrap();
+        demuxer.demux(&scrambled).unwrap();
+        assert!(demuxer.demux(&invalid).is_err());
+        assert!(demuxer.demux_tables(&no_sync).is_err());
+        demuxer.demux_packets(&corrupt).unwrap();
+        assert!(!demuxer.is_probe_failed());
+
+        demuxer.demux(&corrupt).unwrap();
+        assert_eq!(demuxer.events.probe_failed, 1);
+        assert!(demuxer.is_probe_failed());
+    }
+
+    #[test]
+    fn scrambled_packet() {
+        let pes = synthetic::pes(0xE0, None, None, &[0xAA; 8]);
+        let scrambled = PacketBuilder::new(0x101)
+            .pusi(true)
+            .tsc(0b10)
+            .adaptation(&[0x00])
+            .payload(&pes)
+            .build();
+
+        for skip in [true, false] {
+            let mut demuxer = Demuxer::new(Events::default());
+            demuxer.set_skip_scrambled(skip);
+
+            demuxer.demux(&pat_packet(0)).unwrap();
+            demuxer.demux(&pmt_packet()).unwrap();
+            demuxer.demux(&pes_packet(true)).unwrap();
+            demuxer.demux(&scrambled).unwrap();
+            demuxer.demux(&pes_packet(true)).unwrap();
+
+            assert_eq!(demuxer.events.scrambled, vec![PID::Other(0x101)]);
+            assert_eq!(demuxer.stats().get(PID::Other(0x101)).unwrap().scrambled, 1);
+            // scrambled PES is not assembled
+            let packets = if skip { 1 } else { 2 };
+            assert_eq!(demuxer.events.random_access.len(), packets);
+        }
+    }
+
+    #[test]
+    fn corrupt_packet() {
+        let mut payload = vec![0x00];
+        payload.extend_from_slice(&synthetic::pat(1, 0, &[(1, 0x100)]));
+        let corrupt = PacketBuilder::new(0x0000)
+            .pusi(true)
+            .tei(true)
+            .payload(&payload)
+            .build();
+
+        let mut demuxer = Demuxer::new(Events::default());
+        demuxer.demux(&corrupt).unwrap();
+        assert_eq!(demuxer.events.corrupt, vec![PID::PAT]);
+        assert_eq!(demuxer.events.tables, 0);
+
+        let mut demuxer = Demuxer::new(Events::default());
+        demuxer.set_skip_corrupt(false);
+        demuxer.demux(&corrupt).unwrap();
+        assert_eq!(demuxer.events.corrupt, vec![PID::PAT]);
+        assert_eq!(demuxer.events.tables, 1);
+    }
+
+    #[test]
+    fn bitrate() {
+        let mut bitrate = Bitrate::default();
+
+        bitrate.push(PID::Other(0x100), 0, 0, false);
+        assert_eq!(bitrate.estimate(), None);
+
+        // 100 packets in 100ms
+        bitrate.push(PID::Other(0x100), 188 * 100, PCR::HZ / 10, false);
+        assert_eq!(bitrate.estimate(), Some(1_504_000));
+
+        // other PCR pid ignored
+        bitrate.push(PID::Other(0x200), 188 * 150, PCR::HZ, false);
+        assert_eq!(bitrate.estimate(), Some(1_504_000));
+    }
+
+    #[test]
+    fn bitrate_pcr_wraparound() {
+        let mut bitrate = Bitrate::default();
+
+        bitrate.push(PID::Other(0x100), 0, PCR::MAX - PCR::HZ / 20, false);
+        bitrate.push(PID::Other(0x100), 188 * 100, PCR::HZ / 20, false);
+
+        assert_eq!(bitrate.estimate(), Some(1_504_000));
+    }
+
+    #[test]
+    fn bitrate_discontinuity() {
+        let mut bitrate = Bitrate::default();
+
+        bitrate.push(PID::Other(0x100), 0, 0, false);
+        bitrate.push(PID::Other(0x100), 188 * 100, PCR::HZ / 10, false);
+        // PCR jump is not accounted
+        bitrate.push(PID::Other(0x100), 188 * 200, PCR::HZ * 100, true);
+
+        assert_eq!(bitrate.estimate(), Some(1_504_000));
+    }
+
+    #[test]
+    fn packet_program_number() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer
+            .demux(&pat_programs_packet(&[(1, 0x100), (2, 0x200)]))
+            .unwrap();
+        demuxer
+            .demux(&pmt_program_packet(0x100, 1, &[0x101]))
+            .unwrap();
+        // 0x101 shared by both programs
+        demuxer
+            .demux(&pmt_program_packet(0x200, 2, &[0x101, 0x102]))
+            .unwrap();
+
+        demuxer.demux(&pes_packet(false)).unwrap();
+        demuxer.demux(&pes_packet(false)).unwrap();
+
+        assert_eq!(
+            demuxer.events.programs,
+            vec![(1, PID::Other(0x101)), (2, PID::Other(0x101))]
+        );
+    }
+
+    #[test]
+    fn pat_round_trip() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        // network + 2 programs
+        let section = synthetic::pat(1, 0, &[(0, 0x10), (1, 0x100), (2, 0x200)]);
+        for raw in Packetizer::new(0x0000).section(&section) {
+            demuxer.demux(&raw).unwrap();
+        }
+
+        assert_eq!(demuxer.events.tables, 1);
+        assert_eq!(demuxer.events.pat_programs, vec![(1, 0x100), (2, 0x200)]);
+        assert!(demuxer.pmt_pids.has(PID::Other(0x100)));
+        assert!(demuxer.pmt_pids.has(PID::Other(0x200)));
+    }
+
+    #[test]
+    fn section_across_packets() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+
+        let streams = (0..60).map(|i| (0x1B, 0x101 + i)).collect::<Vec<_>>();
+        let pkts = Packetizer::new(0x100).section(&synthetic::pmt(1, 0x101, &streams));
+        assert_eq!(pkts.len(), 2);
+
+        demuxer.demux(&pkts[0]).unwrap();
+        assert_eq!(demuxer.events.tables, 1);
+
+        demuxer.demux(&pkts[1]).unwrap();
+        assert_eq!(demuxer.events.tables, 2);
+        assert_eq!(demuxer.services.get(1).unwrap().streams.len(), 60);
+        assert_eq!(demuxer.packets.0.len(), 60);
+    }
+
+    #[test]
+    fn pes_across_packets() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.demux(&pmt_packet()).unwrap();
+
+        let mut pid = Packetizer::new(0x101);
+        for pts in [90_000, 93_600] {
+            let pes = synthetic::pes(0xE0, Some(pts), None, &[0xAA; 500]);
+            let af = synthetic::adaptation_pcr(pts * 300, true);
+
+            let pkts = pid.pes(Some(&af), &pes);
+            assert_eq!(pkts.len(), 3);
+
+            for raw in pkts {
+                demuxer.demux(&raw).unwrap();
+            }
+        }
+
+        assert_eq!(
+            demuxer.events.payloads,
+            vec![(500, Some(Duration::from_secs(1)))]
+        );
+        assert_eq!(demuxer.events.random_access, vec![true]);
+    }
+
+    #[test]
+    fn access_units() {
+        let mut demuxer = Demuxer::new(Events::default());
+        demuxer.set_access_units(true);
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.demux(&pmt_packet()).unwrap();
+
+        // AUD + IDR slice, AUD + P slice, AUD + P slice
+        let mut pid = Packetizer::new(0x101);
+        for (pts, slice) in [(90_000, 0x65), (93_600, 0x41), (97_200, 0x41)] {
+            let es = [
+                0x00, 0x00, 0x00, 0x01, 0x09, 0xF0, 0x00, 0x00, 0x01, slice, 0x88,
+            ];
+            let pes = synthetic::pes(0xE0, Some(pts), None, &es);
+
+            for raw in pid.pes(None, &pes) {
+                demuxer.demux(&raw).unwrap();
+            }
+        }
+
+        // last PES is not complete, previous access unit waits for the next start
+        assert_eq!(
+            demuxer.events.access_units,
+            vec![(PID::Other(0x101), Some(Duration::from_secs(1)), true)]
+        );
+    }
+
+    #[test]
+    fn video_params() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.demux(&pmt_packet()).unwrap();
+
+        // baseline SPS: 176x144, no VUI
+        let sps = [0x67, 0x42, 0x00, 0x0A, 0xF8, 0x58, 0x98];
+        let mut pid = Packetizer::new(0x101);
+        for es in [
+            &[0x00, 0x00, 0x01, 0x41, 0x9A][..],
+            &[0x00, 0x00, 0x01, 0x41, 0x9A],
+        ] {
+            let pes = synthetic::pes(0xE0, Some(90_000), None, es);
+            for raw in pid.pes(None, &pes) {
+                demuxer.demux(&raw).unwrap();
+            }
+        }
+        assert_eq!(demuxer.video_params(PID::Other(0x101)), None);
+
+        for es in [
+            [&[0x00, 0x00, 0x01][..], &sps].concat(),
+            vec![0x00, 0x00, 0x01, 0x41],
+        ] {
+            let pes = synthetic::pes(0xE0, Some(90_000), None, &es);
+            for raw in pid.pes(None, &pes) {
+                demuxer.demux(&raw).unwrap();
+            }
+        }
+
+        let params = demuxer.video_params(PID::Other(0x101)).unwrap();
+        assert_eq!((params.width, params.height, params.fps), (176, 144, None));
+    }
+
+    #[test]
+    fn audio_params() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer
+            .demux(&Packetizer::new(0x0100).section(&synthetic::pmt(1, 0x101, &[(0x0F, 0x102)]))[0])
+            .unwrap();
+
+        // ADTS AAC-LC 48kHz stereo
+        let mut pid = Packetizer::new(0x102);
+        for _ in 0..2 {
+            let es = [0xFF, 0xF1, 0x4C, 0x80, 0x01, 0x3F, 0xFC, 0x21];
+            let pes = synthetic::pes(0xC0, Some(90_000), None, &es);
+            for raw in pid.pes(None, &pes) {
+                demuxer.demux(&raw).unwrap();
+            }
+        }
+
+        let params = demuxer.audio_params(PID::Other(0x102)).unwrap();
+        assert_eq!((params.sample_rate, params.channels()), (48000, Some(2)));
+        assert_eq!(demuxer.video_params(PID::Other(0x102)), None);
+    }
+
+    #[test]
+    fn sections_packed() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+
+        // PMT table of 2 sections in a single packet
+        let body = [0xE1, 0x01, 0xF0, 0x00, 0x1B, 0xE1, 0x01, 0xF0, 0x00];
+        let mut payload = vec![0x00];
+        payload.extend_from_slice(&synthetic::section(0x02, 1, 0, (0, 1), &body));
+        payload.extend_from_slice(&synthetic::section(0x02, 1, 0, (1, 1), &body));
+
+        let raw = PacketBuilder::new(0x100)
+            .pusi(true)
+            .payload(&payload)
+            .build();
+        demuxer.demux(&raw).unwrap();
+
+        assert_eq!(demuxer.events.tables, 2);
+        assert_eq!(demuxer.services.get(1).unwrap().streams.len(), 2);
+    }
+
+    #[test]
+    fn section_tail_before_pointer_field() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+
+        let streams = (0..60).map(|i| (0x1B, 0x101 + i)).collect::<Vec<_>>();
+        let section = synthetic::pmt(1, 0x101, &streams);
+        let (head, tail) = section.split_at(183);
+
+        let mut payload = vec![0x00];
+        payload.extend_from_slice(head);
+        let raw = PacketBuilder::new(0x100)
+            .pusi(true)
+            .payload(&payload)
+            .build();
+        demuxer.demux(&raw).unwrap();
+        assert_eq!(demuxer.events.tables, 1);
+
+        // tail of version 0, then version 1 starts in the same packet
+        let mut payload = vec![tail.len() as u8];
+        payload.extend_from_slice(tail);
+        payload.extend_from_slice(&synthetic::section(
+            0x02,
+            1,
+            1,
+            (0, 0),
+            &[0xE1, 0x01, 0xF0, 0x00, 0x1B, 0xE1, 0x01, 0xF0, 0x00],
+        ));
+        let raw = PacketBuilder::new(0x100)
+            .pusi(true)
+            .cc(1)
+            .payload(&payload)
+            .build();
+        demuxer.demux(&raw).unwrap();
+
+        assert_eq!(demuxer.events.tables, 3);
+        assert_eq!(demuxer.events.updates.len(), 1);
+        assert_eq!(demuxer.services.get(1).unwrap().streams.len(), 1);
+    }
+    /// collect sections delivered for custom PID
+    fn register_private(
+        demuxer: &mut Demuxer<Events>,
+        pid: u16,
+        table_id: Option<u8>,
+    ) -> Arc<Mutex<Vec<Vec<u8>>>> {
+        let sections = Arc::new(Mutex::new(Vec::new()));
+        let sink = sections.clone();
+
+        demuxer.register_section_pid(pid, table_id, move |raw| {
+            sink.lock().unwrap().push(raw.to_vec())
+        });
+
+        sections
+    }
+
+    #[test]
+    fn private_section_across_packets() {
+        let mut demuxer = Demuxer::new(Events::default());
+        let sections = register_private(&mut demuxer, 0x500, None);
+
+        let section = synthetic::section(0xC0, 1, 0, (0, 0), &[0xAA; 300]);
+        let pkts = Packetizer::new(0x500).section(&section);
+        assert_eq!(pkts.len(), 2);
+
+        // PAT is not required
+        demuxer.demux(&pkts[0]).unwrap();
+        assert!(sections.lock().unwrap().is_empty());
+
+        demuxer.demux(&pkts[1]).unwrap();
+        assert_eq!(*sections.lock().unwrap(), vec![section]);
+        assert_eq!(demuxer.events.tables, 0);
+    }
+
+    #[test]
+    fn private_section_table_id_filter() {
+        let mut demuxer = Demuxer::new(Events::default());
+        let sections = register_private(&mut demuxer, 0x500, Some(0xFC));
+
+        let other = synthetic::section(0xC0, 1, 0, (0, 0), &[0x01; 4]);
+        let scte35 = synthetic::section(0xFC, 1, 0, (0, 0), &[0x02; 4]);
+
+        // both sections packed into a single packet
+        let mut payload = vec![0x00];
+        payload.extend_from_slice(&other);
+        payload.extend_from_slice(&scte35);
+
+        let raw = PacketBuilder::new(0x500)
+            .pusi(true)
+            .payload(&payload)
+            .build();
+        demuxer.demux(&raw).unwrap();
+
+        assert_eq!(*sections.lock().unwrap(), vec![scte35]);
+    }
+
+    #[test]
+    fn section_header_split() {
+        let mut demuxer = Demuxer::new(Events::default());
+        let pat = synthetic::pat(1, 0, &[(1, 0x100)]);
+
+        // section starts at payload offset 180:
+        // only 4 bytes of 8-byte header fit
+        let mut payload = [0xFF; 184];
+        payload[0] = 179;
+        payload[180..].copy_from_slice(&pat[..4]);
+
+        let raw = PacketBuilder::new(0x0000)
+            .pusi(true)
+            .payload(&payload)
+            .build();
+        demuxer.demux(&raw).unwrap();
+        assert_eq!(demuxer.events.tables, 0);
+
+        let raw = PacketBuilder::new(0x0000).cc(1).payload(&pat[4..]).build();
+        demuxer.demux(&raw).unwrap();
+        assert_eq!(demuxer.events.tables, 1);
+        assert!(demuxer.pmt_pids.has(PID::Other(0x100)));
+    }
+
+    #[test]
+    fn section_syntax_indicator_not_set() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        let mut pat = synthetic::pat(1, 0, &[(1, 0x100)]);
+        pat[1] &= 0x7F;
+        let raw = Packetizer::new(0x0000).section(&pat)[0];
+
+        assert_eq!(
+            demuxer.demux(&raw).err(),
+            Some(Error::new(ErrorKind::SectionSyntaxIndicatorNotSet))
+        );
+        assert_eq!(demuxer.events.tables, 0);
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        assert_eq!(demuxer.events.tables, 1);
+    }
+
+    #[test]
+    fn section_length_max() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        // PAT section-length 1022
+        let raw = PacketBuilder::new(0x0000)
+            .pusi(true)
+            .payload(&[0x00, 0x00, 0xB3, 0xFE, 0x00, 0x01, 0xC1, 0x00, 0x00])
+            .build();
+        assert_eq!(
+            demuxer.demux(&raw).err(),
+            Some(Error::new(ErrorKind::SectionLength(0x3FE, 0x3FD)))
+        );
+
+        // continuation is not buffered
+        let raw = PacketBuilder::new(0x0000).payload(&[0xAA; 16]).build();
+        demuxer.demux(&raw).unwrap();
+        assert_eq!(demuxer.events.tables, 0);
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        assert_eq!(demuxer.events.tables, 1);
+    }
+
+    #[test]
+    fn pmt_section_length_max() {
+        let mut demuxer = Demuxer::new(Events::default());
+        demuxer.demux(&pat_packet(0)).unwrap();
+        assert_eq!(demuxer.events.tables, 1);
+
+        // PMT section-length 0xFFF (bit error): never completes if trusted
+        let raw = PacketBuilder::new(0x0100)
+            .pusi(true)
+            .payload(&[0x00, 0x02, 0xBF, 0xFF, 0x00, 0x01, 0xC1, 0x00, 0x00])
+            .build();
+        assert_eq!(
+            demuxer.demux(&raw).err(),
+            Some(Error::new(ErrorKind::SectionLength(0xFFF, 0x3FD)))
+        );
+
+        let raw = PacketBuilder::new(0x0100)
+            .cc(1)
+            .payload(&[0xAA; 184])
+            .build();
+        demuxer.demux(&raw).unwrap();
+        assert_eq!(demuxer.events.tables, 1);
+
+        // resync on the next PUSI
+        demuxer.demux(&pmt_packet()).unwrap();
+        assert_eq!(demuxer.events.tables, 2);
+        assert_eq!(
+            demuxer.pid_role(PID::Other(0x101)),
+            PidRole::Pes(StreamType::H264)
+        );
+    }
+
+    #[test]
+    fn private_section_length_max() {
+        let mut demuxer = Demuxer::new(Events::default());
+        let sections = register_private(&mut demuxer, 0x500, None);
+        demuxer.set_max_section_length(8);
+
+        let section = synthetic::section(0xFC, 1, 0, (0, 0), &[0x02; 4]);
+        let mut payload = vec![0x00];
+        payload.extend_from_slice(&section);
+
+        let raw = PacketBuilder::new(0x500)
+            .pusi(true)
+            .payload(&payload)
+            .build();
+        assert_eq!(
+            demuxer.demux(&raw).err(),
+            Some(Error::new(ErrorKind::SectionLength(13, 8)))
+        );
+        assert!(sections.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn table_timeout() {
+        let mut demuxer = Demuxer::new(Events::default());
+        let ms = Duration::from_millis;
+        let pat = SubtableID::PAT(TableID::ProgramAssociationSection, 1, 0);
+
+        // not stamped before monitoring starts
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.check_table_timeouts(ms(1_000));
+        assert!(demuxer.events.timeouts.is_empty());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.check_table_timeouts(ms(1_400));
+        assert!(demuxer.events.timeouts.is_empty());
+
+        // reported once
+        demuxer.check_table_timeouts(ms(1_600));
+        demuxer.check_table_timeouts(ms(1_700));
+        assert_eq!(demuxer.events.timeouts, vec![(pat, ms(600))]);
+
+        // seen again
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.check_table_timeouts(ms(2_000));
+        assert_eq!(demuxer.events.timeouts.len(), 1);
+
+        demuxer.set_table_timeout(TableID::ProgramAssociationSection, None);
+        demuxer.check_table_timeouts(ms(5_000));
+        assert_eq!(demuxer.events.timeouts.len(), 1);
+    }
+
+    #[test]
+    fn sections_bytes() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+
+        assert_eq!(
+            demuxer.events.sections,
+            vec![synthetic::section(
+                0x00,
+                1,
+                0,
+                (0, 0),
+                &[0x00, 0x01, 0xE1, 0x00]
+            )]
+        );
+    }
+
+    #[test]
+    fn pid_role() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        assert!(matches!(demuxer.pid_role(PID::PAT), PidRole::Pat));
+        assert!(matches!(demuxer.pid_role(PID::NULL), PidRole::Null));
+        assert!(matches!(
+            demuxer.pid_role(PID::SDT),
+            PidRole::Section(PID::SDT)
+        ));
+        assert!(matches!(
+            demuxer.pid_role(PID::Other(0x100)),
+            PidRole::Unknown
+        ));
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        assert!(matches!(
+            demuxer.pid_role(PID::Other(0x100)),
+            PidRole::Pmt(1)
+        ));
+        assert!(matches!(
+            demuxer.pid_role(PID::Other(0x101)),
+            PidRole::Unknown
+        ));
+
+        demuxer.demux(&pmt_packet()).unwrap();
+        assert!(matches!(
+            demuxer.pid_role(PID::Other(0x101)),
+            PidRole::Pes(StreamType::H264)
+        ));
+
+        demuxer.register_section_pid(0x500, None, |_| {});
+        assert!(matches!(
+            demuxer.pid_role(PID::Other(0x500)),
+            PidRole::Section(PID::Other(0x500))
+        ));
+    }
+    /// PAT (program 1 -> pmt pid 0x100) with current-next-indicator unset
+    fn pat_next_packet(version_number: u8) -> [u8; TsPacket::SZ] {
+        let mut section = synthetic::pat(1, version_number, &[(1, 0x100)]);
+        section[5] &= 0xFE;
+
+        let crc_pos = section.len() - 4;
+        let crc = synthetic::crc32(&section[..crc_pos]);
+        section[crc_pos..].copy_from_slice(&crc.to_be_bytes());
+
+        Packetizer::new(0x0000).section(&section)[0]
+    }
+
+    #[test]
+    fn next_section_skipped() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.demux(&pat_next_packet(1)).unwrap();
+
+        assert_eq!(demuxer.events.tables, 1);
+        assert!(demuxer.events.updates.is_empty());
+
+        // became current
+        demuxer.demux(&pat_packet(1)).unwrap();
+        assert_eq!(demuxer.events.tables, 2);
+        assert_eq!(demuxer.events.updates.len(), 1);
+    }
+
+    #[test]
+    fn next_section_included() {
+        let mut demuxer = Demuxer::new(Events::default());
+        demuxer.set_include_next(true);
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        demuxer.demux(&pat_next_packet(1)).unwrap();
+
+        assert_eq!(demuxer.events.tables, 2);
+        assert_eq!(demuxer.events.updates.len(), 1);
+    }
+
+    #[test]
+    fn table_progress_segmented() {
+        // 4 segments; sections 0...1, 8, 16, 24
+        let mut table = Table::new(24);
+
+        for (number, segment_last) in [(0, 1), (8, 8), (16, 16)] {
+            let mut section = Section::new(number, 1, Default::default());
+            section.segment_last_section_number = Some(segment_last);
+            section.buf.0.write_all(&[0x00]).unwrap();
+            table.sections.push(section.into_ref());
+        }
+
+        // section 1 and segment 3 are missing
+        assert_eq!(table.expected_sections(), 5);
+        assert_eq!(table.received_sections(), 3);
+        assert!(!table.is_complete());
+
+        for (number, segment_last) in [(1, 1), (24, 24)] {
+            let mut section = Section::new(number, 1, Default::default());
+            section.segment_last_section_number = Some(segment_last);
+            section.buf.0.write_all(&[0x00]).unwrap();
+            table.sections.push(section.into_ref());
+        }
+
+        assert_eq!(table.received_sections(), 5);
+        assert!(table.is_complete());
+    }
+
+    #[test]
+    fn eit_schedule_complete() {
+        let mut demuxer = Demuxer::new(Events::default());
+        let mut pid = Packetizer::new(0x0012);
+
+        // EIT schedule actual: segment 0 - section 0, segment 1 - section 8
+        for (number, segment_last) in [(0, 0), (8, 8)] {
+            let body = [0x00, 0x01, 0x00, 0x01, segment_last, 0x50];
+            let section = synthetic::section(0x50, 1, 0, (number, 8), &body);
+
+            for raw in pid.section(&section) {
+                demuxer.demux(&raw).unwrap();
+            }
+        }
+
+        assert_eq!(demuxer.events.tables, 1);
+    }
+
+    #[test]
+    fn sdt_actual_other() {
+        let mut demuxer = Demuxer::new(Events::default());
+        let mut pid = Packetizer::new(0x0011);
+
+        // same transport-stream-id and version-number
+        for table_id in [0x42, 0x46] {
+            let section = synthetic::section(table_id, 1, 0, (0, 0), &[0x00, 0x01, 0xFF]);
+
+            for raw in pid.section(&section) {
+                demuxer.demux(&raw).unwrap();
+            }
+        }
+
+        assert_eq!(demuxer.events.tables, 2);
+        assert!(SDT::new(&demuxer.events.sections[0]).is_actual());
+        assert!(!SDT::new(&demuxer.events.sections[1]).is_actual());
+    }
+
+    #[test]
+    fn table_view_events() {
+        let mut demuxer = Demuxer::new(Events::default());
+        let mut pid = Packetizer::new(0x0012);
+
+        // EIT p/f actual: one event per section
+        for (number, event_id) in [(0u8, 0x0101u16), (1, 0x0102)] {
+            let mut body = vec![0x00, 0x01, 0x00, 0x01, 0x01, 0x4E];
+            body.extend_from_slice(&event_id.to_be_bytes());
+            // start-time, duration, running-status + descriptors-loop-length
+            body.extend_from_slice(&[0xE4, 0x3A, 0x12, 0x00, 0x00, 0x01, 0x30, 0x00, 0x80, 0x00]);
+
+            let section = synthetic::section(0x4E, 1, 0, (number, 1), &body);
+
+            for raw in pid.section(&section) {
+                demuxer.demux(&raw).unwrap();
+            }
+        }
+
+        assert_eq!(demuxer.events.tables, 1);
+        assert_eq!(demuxer.events.event_ids, vec![0x0101, 0x0102]);
+
+        // same start-time - ordered by event-id
+        assert_eq!(
+            demuxer.epg(1).map(|e| e.event_id).collect::<Vec<_>>(),
+            vec![0x0101, 0x0102]
+        );
+        assert_eq!(demuxer.epg(2).count(), 0);
+
+        demuxer.reset();
+        assert!(demuxer.epg_store().is_empty());
+    }
+
+    #[test]
+    fn validate_signaling() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer
+            .demux(&pat_programs_packet(&[(1, 0x100), (2, 0x200)]))
+            .unwrap();
+        demuxer
+            .demux(&pmt_program_packet(0x0100, 1, &[0x101, 0x102]))
+            .unwrap();
+
+        // video with PCR, audio not sent
+        let af = synthetic::adaptation_pcr(27_000_000, false);
+        let pes = synthetic::pes(0xE0, None, None, &[0xAA; 8]);
+        demuxer
+            .demux(
+                &PacketBuilder::new(0x101)
+                    .pusi(true)
+                    .adaptation(&af)
+                    .payload(&pes)
+                    .build(),
+            )
+            .unwrap();
+
+        // services 1 and 3
+        #[rustfmt::skip]
+        let body = [
+            0x00, 0x01, 0xFF,
+            0x00, 0x01, 0xFC, 0x80, 0x00,
+            0x00, 0x03, 0xFC, 0x80, 0x00,
+        ];
+        let section = synthetic::section(0x42, 1, 0, (0, 0), &body);
+        demuxer
+            .demux(&Packetizer::new(0x0011).section(&section)[0])
+            .unwrap();
+
+        let issues = demuxer.validate_signaling();
+        assert_eq!(
+            issues,
+            vec![
+                SignalingIssue::ElementaryPidMissing {
+                    program_number: 1,
+                    pid: 0x102
+                },
+                SignalingIssue::ProgramNotInSdt { program_number: 2 },
+                SignalingIssue::PmtMissing {
+                    program_number: 2,
+                    pmt_pid: 0x200
+                },
+                SignalingIssue::ServiceNotInPat { service_id: 3 },
+            ]
+        );
+        assert_eq!(
+            issues.iter().map(|i| i.severity()).max(),
+            Some(Severity::Error)
+        );
+    }
+
+    #[test]
+    fn program_pcr() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+        assert_eq!(demuxer.pcr_pid(1), None);
+
+        // PCR on dedicated PID, video @ 0x101
+        let pmt = synthetic::pmt(1, 0x1FF, &[(0x1B, 0x101)]);
+        demuxer
+            .demux(&Packetizer::new(0x0100).section(&pmt)[0])
+            .unwrap();
+        assert_eq!(demuxer.pcr_pid(1), Some(PID::Other(0x1FF)));
+        assert_eq!(demuxer.program_pcr(1), None);
+
+        let af = synthetic::adaptation_pcr(27_000_000, false);
+        demuxer
+            .demux(&PacketBuilder::new(0x1FF).adaptation(&af).build())
+            .unwrap();
+
+        assert_eq!(demuxer.program_pcr(1), Some(27_000_000));
+        assert_eq!(demuxer.program_pcr(2), None);
+    }
+
+    #[test]
+    fn trace() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+
+        // cc 0 -> 2
+        for cc in [0, 2] {
+            let raw = PacketBuilder::new(0x101).cc(cc).payload(&[0xAA]).build();
+            demuxer.demux(&raw).unwrap();
+        }
+
+        let traces = &demuxer.events.traces;
+        assert_eq!(traces.len(), 5);
+        assert!(matches!(
+            traces[0],
+            Trace::PidSeen {
+                pid: PID::PAT,
+                offset: 0
+            }
+        ));
+        assert!(matches!(
+            traces[1],
+            Trace::SectionBegin {
+                pid: PID::PAT,
+                section_number: 0,
+                ..
+            }
+        ));
+        assert!(matches!(
+            traces[2],
+            Trace::SectionComplete {
+                pid: PID::PAT,
+                id: SubtableID::PAT(..),
+                section_number: 0
+            }
+        ));
+        assert!(matches!(
+            traces[3],
+            Trace::PidSeen {
+                pid: PID::Other(0x101),
+                offset: 188
+            }
+        ));
+        assert!(matches!(
+            traces[4],
+            Trace::CcError {
+                pid: PID::Other(0x101),
+                offset: 376,
+                last: 0,
+                cc: 2
+            }
+        ));
+    }
+
+    #[test]
+    fn trace_section_begin() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        let programs: Vec<(u16, u16)> = (1..=60).map(|n| (n, 0x100 + n)).collect();
+        let pat = synthetic::pat(1, 0, &programs);
+        let pkts = Packetizer::new(0x0000).section(&pat);
+        assert_eq!(pkts.len(), 2);
+
+        // header parsed, section is not complete yet
+        demuxer.demux(&pkts[0]).unwrap();
+        let traces = &demuxer.events.traces;
+        assert_eq!(traces.len(), 2);
+        assert!(matches!(
+            traces[1],
+            Trace::SectionBegin {
+                pid: PID::PAT,
+                id: SubtableID::PAT(..),
+                section_number: 0,
+                sz
+            } if sz == pat.len()
+        ));
+
+        demuxer.demux(&pkts[1]).unwrap();
+        let traces = &demuxer.events.traces;
+        assert_eq!(traces.len(), 3);
+        assert!(matches!(traces[2], Trace::SectionComplete { .. }));
+        assert_eq!(demuxer.events.tables, 1);
+    }
+
+    #[test]
+    fn demux_datagram() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        let mut datagram = pat_packet(0).to_vec();
+        datagram.extend_from_slice(&pmt_packet());
+        for _ in 0..5 {
+            datagram.extend_from_slice(&PacketBuilder::new(0x1FFF).payload(&[0xFF]).build());
+        }
+        assert_eq!(datagram.len(), 1316);
+
+        demuxer.demux_datagram(&datagram).unwrap();
+        assert_eq!(demuxer.events.tables, 2);
+        assert_eq!(demuxer.stats().packets(), 7);
+
+        // partial trailing packet
+        assert_eq!(
+            demuxer.demux_datagram(&datagram[..1000]),
+            Err(Error::new(ErrorKind::Buf(1000, 1128)))
+        );
+        assert_eq!(demuxer.stats().packets(), 7);
+
+        // bad sync byte does not stop the rest
+        let mut datagram = datagram[..2 * 188].to_vec();
+        datagram[0] = 0x00;
+        assert!(demuxer.demux_datagram(&datagram).is_err());
+        assert_eq!(demuxer.stats().packets(), 8);
+    }
+
+    #[test]
+    fn demux_rtp() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        let rtp = |seq: u16, pt: u8, pkt: &[u8]| {
+            let mut raw = vec![0x80, pt];
+            raw.extend_from_slice(&seq.to_be_bytes());
+            raw.extend_from_slice(&[0x00; 8]);
+            raw.extend_from_slice(pkt);
+            raw
+        };
+
+        demuxer.demux_rtp(&rtp(0xFFFF, 33, &pat_packet(0))).unwrap();
+        demuxer.demux_rtp(&rtp(0x0000, 33, &pmt_packet())).unwrap();
+        assert_eq!(demuxer.events.tables, 2);
+        assert!(demuxer.events.rtp_gaps.is_empty());
+
+        // 2 datagrams lost
+        let null = PacketBuilder::new(0x1FFF).payload(&[0xFF]).build();
+        demuxer.demux_rtp(&rtp(0x0003, 33, &null)).unwrap();
+        assert_eq!(demuxer.events.rtp_gaps, vec![(0x0001, 0x0003)]);
+
+        assert_eq!(
+            demuxer.demux_rtp(&rtp(0x0004, 96, &null)),
+            Err(Error::new(ErrorKind::RTPPayloadType(96)))
+        );
+    }
+
+    #[test]
+    fn stats() {
+        let mut demuxer = Demuxer::new(Events::default());
+        let af = synthetic::adaptation_pcr(27_000_000, false);
+
+        // repeated 1 - duplicate, 3 - cc error (2 expected),
+        // adaptation-only 3 does not advance cc
+        for (cc, payload) in [
+            (0, true),
+            (1, true),
+            (1, true),
+            (3, true),
+            (3, false),
+            (4, true),
+        ] {
+            let payload: &[u8] = if payload { &[0xAA; 8] } else { &[] };
+            let raw = PacketBuilder::new(0x101)
+                .cc(cc)
+                .adaptation(&af)
+                .payload(payload)
+                .build();
+
+            demuxer.demux(&raw).unwrap();
+        }
+
+        // null packets cc is not checked
+        for cc in [5, 9] {
+            let raw = PacketBuilder::new(0x1FFF).cc(cc).payload(&[0xFF]).build();
+            demuxer.demux(&raw).unwrap();
+        }
+
+        let stats = demuxer.stats().get(PID::Other(0x101)).unwrap();
+        assert_eq!(stats.packets, 6);
+        assert_eq!(stats.bytes, 6 * 188);
+        assert_eq!(stats.cc_errors, 1);
+        assert_eq!(stats.pcr, Some(27_000_000));
+
+        assert_eq!(demuxer.stats().get(PID::NULL).unwrap().cc_errors, 0);
+        assert_eq!(demuxer.stats().packets(), 8);
+
+        demuxer.reset_stats();
+        assert_eq!(demuxer.stats().packets(), 0);
+    }
+
+    #[test]
+    fn error_context() {
+        let mut demuxer = Demuxer::new(Events::default());
+
+        let err = demuxer.demux(&[0x00; TsPacket::SZ]).unwrap_err();
+        assert_eq!(err, Error::new(ErrorKind::SyncByte(0x00)));
+        assert_eq!(err.details(), Some(":offset 0"));
+
+        demuxer.demux(&pat_packet(0)).unwrap();
+
+        // pointer-field is out of payload
+        let raw = PacketBuilder::new(0x0000)
+            .pusi(true)
+            .payload(&[0xFF; 184])
+            .build();
+        let err = demuxer.demux(&raw).unwrap_err();
+
+        assert!(matches!(err.kind(), ErrorKind::Buf(..)));
+        assert_eq!(
+            err.details(),
+            Some(":pid PAT (0x0000) :offset 188 :section PAT")
+        );
+    }
+}
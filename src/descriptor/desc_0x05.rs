@@ -0,0 +1,101 @@
+use core::fmt;
+use core::str;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::result::Result;
+
+/// ISO/IEC 13818-1
+///
+/// Registration descriptor
+#[derive(Clone)]
+pub struct Desc0x05<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> Desc0x05<'buf> {
+    const HEADER_SZ: usize = 4;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> Desc0x05<'buf> {
+        Desc0x05 { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<Desc0x05<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// SMPTE-RA registered (e.g. "AC-3", "HEVC", "Opus")
+    #[inline(always)]
+    pub fn format_identifier(&self) -> [u8; 4] {
+        [self.buf[0], self.buf[1], self.buf[2], self.buf[3]]
+    }
+
+    /// format-identifier FourCC;
+    /// "----" in case it is not valid UTF-8
+    #[inline(always)]
+    pub fn fourcc(&self) -> &'buf str {
+        str::from_utf8(&self.buf[..Self::HEADER_SZ]).unwrap_or("----")
+    }
+
+    #[inline(always)]
+    pub fn is(&self, fourcc: &[u8; 4]) -> bool {
+        &self.format_identifier() == fourcc
+    }
+
+    #[inline(always)]
+    pub fn additional_identification_info(&self) -> &'buf [u8] {
+        &self.buf[Self::HEADER_SZ..]
+    }
+}
+
+impl<'buf> fmt::Debug for Desc0x05<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            r#":0x05 (:format-identifier "{}" :additional-identification-info {})"#,
+            self.fourcc(),
+            self.additional_identification_info().len(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Desc0x05;
+    use crate::error::{Error, Kind as ErrorKind};
+
+    #[test]
+    fn format_identifier() {
+        let d = Desc0x05::try_new(b"Opus").unwrap();
+
+        assert_eq!(&d.format_identifier(), b"Opus");
+        assert_eq!(d.fourcc(), "Opus");
+        assert!(d.is(b"Opus"));
+        assert!(!d.is(b"AC-4"));
+        assert!(d.additional_identification_info().is_empty());
+
+        let d = Desc0x05::new(&[0xFF, 0xFE, b'x', b'y', 0x01]);
+        assert_eq!(d.fourcc(), "----");
+        assert_eq!(d.additional_identification_info(), [0x01]);
+    }
+
+    #[test]
+    fn truncated() {
+        assert_eq!(
+            Desc0x05::try_new(b"AC-").err(),
+            Some(Error::new(ErrorKind::Buf(3, 4)))
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use core::fmt;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::result::Result;
+
+/// ISO/IEC 13818-1
+///
+/// Data stream alignment descriptor
+#[derive(Clone)]
+pub struct Desc0x06<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> Desc0x06<'buf> {
+    const SZ: usize = 1;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> Desc0x06<'buf> {
+        Desc0x06 { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<Desc0x06<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::SZ)))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// video (table 2-53): 1 - slice or video access unit, 2 - video access unit,
+    /// 3 - GOP or SEQ, 4 - SEQ;
+    /// audio (table 2-54): 1 - syncword
+    #[inline(always)]
+    pub fn alignment_type(&self) -> u8 {
+        self.buf[0]
+    }
+}
+
+impl<'buf> fmt::Debug for Desc0x06<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, ":0x06 (:alignment-type {})", self.alignment_type())
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt;
+use core::fmt;
 
 use crate::error::{Error, Kind as ErrorKind};
 use crate::iso_639::ISO639;
@@ -14,13 +14,34 @@ pub struct Desc0x0A<'buf> {
 }
 
 impl<'buf> Desc0x0A<'buf> {
+    const LANGUAGE_SZ: usize = 4;
+
     #[inline(always)]
     pub fn new(buf: &'buf [u8]) -> Desc0x0A<'buf> {
         Desc0x0A { buf }
     }
 
     #[inline(always)]
-    pub fn languages(&self) -> Cursor<'buf, Language> {
+    pub fn try_new(buf: &'buf [u8]) -> Result<Desc0x0A<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    /// loop of 4-byte languages
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        let rem = self.buf.len() % Self::LANGUAGE_SZ;
+        if rem != 0 {
+            let sz = self.buf.len() - rem + Self::LANGUAGE_SZ;
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), sz)))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    pub fn languages(&self) -> Cursor<'buf, Language<'buf>> {
         Cursor::new(self.buf)
     }
 }
@@ -0,0 +1,151 @@
+use core::fmt;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::reader::bits;
+use crate::result::Result;
+
+use super::bcd;
+
+/// ETSI EN 300 468 V1.15.1
+///
+/// Satellite delivery system descriptor
+#[derive(Clone)]
+pub struct DescDVB0x43<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> DescDVB0x43<'buf> {
+    const SZ: usize = 11;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> DescDVB0x43<'buf> {
+        DescDVB0x43 { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<DescDVB0x43<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::SZ)))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 8 BCD digits, GHz with 5 decimals - 10kHz units
+    #[inline(always)]
+    pub fn frequency(&self) -> u32 {
+        bcd(&self.buf[0..4], 8)
+    }
+
+    /// 4 BCD digits - 0.1 degree units
+    #[inline(always)]
+    pub fn orbital_position(&self) -> u16 {
+        bcd(&self.buf[4..6], 4) as u16
+    }
+
+    /// true - eastern, false - western position
+    #[inline(always)]
+    pub fn west_east_flag(&self) -> bool {
+        (self.buf[6] & 0b1000_0000) != 0
+    }
+
+    /// 0 - linear horizontal, 1 - linear vertical,
+    /// 2 - circular left, 3 - circular right
+    #[inline(always)]
+    pub fn polarization(&self) -> u8 {
+        bits(self.buf[6], 5, 2)
+    }
+
+    /// DVB-S2 only;
+    /// 0 - 0.35, 1 - 0.25, 2 - 0.20
+    #[inline(always)]
+    pub fn roll_off(&self) -> u8 {
+        bits(self.buf[6], 3, 2)
+    }
+
+    /// false - DVB-S, true - DVB-S2
+    #[inline(always)]
+    pub fn modulation_system(&self) -> bool {
+        (self.buf[6] & 0b0000_0100) != 0
+    }
+
+    /// 0 - auto, 1 - QPSK, 2 - 8PSK, 3 - 16-QAM
+    #[inline(always)]
+    pub fn modulation_type(&self) -> u8 {
+        self.buf[6] & 0b0000_0011
+    }
+
+    /// 7 BCD digits, Msymbol/s with 4 decimals - 100symbol/s units
+    #[inline(always)]
+    pub fn symbol_rate(&self) -> u32 {
+        bcd(&self.buf[7..11], 7)
+    }
+
+    /// 1 - 1/2, 2 - 2/3, 3 - 3/4, 4 - 5/6, 5 - 7/8,
+    /// 6 - 8/9, 7 - 3/5, 8 - 4/5, 9 - 9/10, 15 - no conv. coding
+    #[inline(always)]
+    pub fn fec_inner(&self) -> u8 {
+        self.buf[10] & 0b0000_1111
+    }
+}
+
+impl<'buf> fmt::Debug for DescDVB0x43<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Err(err) = self.validate() {
+            return write!(f, ":dvb-0x43 (:error {:?})", err);
+        }
+
+        write!(
+            f,
+            ":dvb-0x43 (:frequency {} :orbital-position {} :east {} :polarization {} :s2 {} :modulation-type {} :symbol-rate {} :fec-inner {})",
+            self.frequency(),
+            self.orbital_position(),
+            self.west_east_flag(),
+            self.polarization(),
+            self.modulation_system(),
+            self.modulation_type(),
+            self.symbol_rate(),
+            self.fec_inner(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DescDVB0x43;
+    use crate::error::{Error, Kind as ErrorKind};
+
+    #[test]
+    fn satellite() {
+        // 11.72748 GHz, 19.2E, vertical, DVB-S2 8PSK, 27.5 Msym/s, 3/4
+        let raw = [
+            0x01, 0x17, 0x27, 0x48, 0x01, 0x92, 0xA6, 0x02, 0x75, 0x00, 0x03,
+        ];
+        let d = DescDVB0x43::try_new(&raw).unwrap();
+
+        assert_eq!(d.frequency(), 1_172_748);
+        assert_eq!(d.orbital_position(), 192);
+        assert!(d.west_east_flag());
+        assert_eq!(d.polarization(), 1);
+        assert_eq!(d.roll_off(), 0);
+        assert!(d.modulation_system());
+        assert_eq!(d.modulation_type(), 2);
+        assert_eq!(d.symbol_rate(), 275_000);
+        assert_eq!(d.fec_inner(), 3);
+    }
+
+    #[test]
+    fn truncated() {
+        assert_eq!(
+            DescDVB0x43::new(&[0x01; 10]).validate(),
+            Err(Error::new(ErrorKind::Buf(10, 11)))
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use core::fmt;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::result::Result;
+
+use super::bcd;
+
+/// ETSI EN 300 468 V1.15.1
+///
+/// Cable delivery system descriptor
+#[derive(Clone)]
+pub struct DescDVB0x44<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> DescDVB0x44<'buf> {
+    const SZ: usize = 11;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> DescDVB0x44<'buf> {
+        DescDVB0x44 { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<DescDVB0x44<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::SZ)))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 8 BCD digits, MHz with 4 decimals - 100Hz units
+    #[inline(always)]
+    pub fn frequency(&self) -> u32 {
+        bcd(&self.buf[0..4], 8)
+    }
+
+    /// 1 - no outer FEC, 2 - RS(204/188)
+    #[inline(always)]
+    pub fn fec_outer(&self) -> u8 {
+        self.buf[5] & 0b0000_1111
+    }
+
+    /// 1 - 16-QAM, 2 - 32-QAM, 3 - 64-QAM, 4 - 128-QAM, 5 - 256-QAM
+    #[inline(always)]
+    pub fn modulation(&self) -> u8 {
+        self.buf[6]
+    }
+
+    /// 7 BCD digits, Msymbol/s with 4 decimals - 100symbol/s units
+    #[inline(always)]
+    pub fn symbol_rate(&self) -> u32 {
+        bcd(&self.buf[7..11], 7)
+    }
+
+    /// same coding as satellite delivery system
+    #[inline(always)]
+    pub fn fec_inner(&self) -> u8 {
+        self.buf[10] & 0b0000_1111
+    }
+}
+
+impl<'buf> fmt::Debug for DescDVB0x44<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Err(err) = self.validate() {
+            return write!(f, ":dvb-0x44 (:error {:?})", err);
+        }
+
+        write!(
+            f,
+            ":dvb-0x44 (:frequency {} :fec-outer {} :modulation {} :symbol-rate {} :fec-inner {})",
+            self.frequency(),
+            self.fec_outer(),
+            self.modulation(),
+            self.symbol_rate(),
+            self.fec_inner(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DescDVB0x44;
+    use crate::error::{Error, Kind as ErrorKind};
+
+    #[test]
+    fn cable() {
+        // 346 MHz, RS(204/188), 256-QAM, 6.9 Msym/s, no conv. coding
+        let raw = [
+            0x03, 0x46, 0x00, 0x00, 0xFF, 0xF2, 0x05, 0x00, 0x69, 0x00, 0x0F,
+        ];
+        let d = DescDVB0x44::try_new(&raw).unwrap();
+
+        assert_eq!(d.frequency(), 3_460_000);
+        assert_eq!(d.fec_outer(), 2);
+        assert_eq!(d.modulation(), 5);
+        assert_eq!(d.symbol_rate(), 69_000);
+        assert_eq!(d.fec_inner(), 15);
+    }
+
+    #[test]
+    fn truncated() {
+        assert_eq!(
+            DescDVB0x44::new(&[0x00; 4]).validate(),
+            Err(Error::new(ErrorKind::Buf(4, 11)))
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use core::fmt;
+
+use crate::result::Result;
+
+use crate::annex_a2::AnnexA2;
+
+/// ETSI EN 300 468 V1.15.1
+///
+/// Bouquet name descriptor
+#[derive(Clone)]
+pub struct DescDVB0x47<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> DescDVB0x47<'buf> {
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> DescDVB0x47<'buf> {
+        DescDVB0x47 { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<DescDVB0x47<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    /// any body is a valid (maybe empty) name
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Annex A2 encoded; whole descriptor body
+    #[inline(always)]
+    pub fn name(&self) -> &'buf [u8] {
+        self.buf
+    }
+}
+
+impl<'buf> fmt::Debug for DescDVB0x47<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, ":dvb-0x47 (:bouquet")?;
+
+        match AnnexA2::decode_to_string(self.name()) {
+            Ok(name) => write!(f, r#" "{}""#, name),
+            Err(err) => write!(f, " (error: {:?})", err),
+        }?;
+
+        write!(f, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DescDVB0x47;
+
+    #[test]
+    fn name() {
+        let d = DescDVB0x47::new(b"Sky UK");
+
+        assert_eq!(d.name(), b"Sky UK");
+        assert_eq!(format!("{:?}", d), r#":dvb-0x47 (:bouquet "Sky UK")"#);
+    }
+}
@@ -1,8 +1,10 @@
-use std::fmt;
+use core::fmt;
 
+use super::buf_slice;
 use crate::annex_a2::AnnexA2;
-
-// TODO: implement
+use crate::error::{Error, Kind as ErrorKind};
+use crate::result::Result;
+use crate::service_type::ServiceType;
 
 /// ETSI EN 300 468 V1.15.1
 ///
@@ -20,9 +22,42 @@ impl<'buf> DescDVB0x48<'buf> {
         DescDVB0x48 { buf }
     }
 
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<DescDVB0x48<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    /// length fields fit into buffer
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            return Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)));
+        }
+
+        // service-name-length
+        let sz = self.buf_pos_service_name();
+        if self.buf.len() < sz {
+            return Err(Error::new(ErrorKind::Buf(self.buf.len(), sz)));
+        }
+
+        let sz = sz + (self.service_name_length() as usize);
+        if self.buf.len() < sz {
+            return Err(Error::new(ErrorKind::Buf(self.buf.len(), sz)));
+        }
+
+        Ok(())
+    }
+
     #[inline(always)]
     pub fn service_type(&self) -> u8 {
-        self.buf[0]
+        self.buf.first().copied().unwrap_or(0)
+    }
+
+    #[inline(always)]
+    pub fn service_type_kind(&self) -> ServiceType {
+        ServiceType::from(self.service_type())
     }
 
     #[inline(always)]
@@ -42,24 +77,33 @@ impl<'buf> DescDVB0x48<'buf> {
 
     #[inline(always)]
     pub fn service_provider_name_length(&self) -> u8 {
-        self.buf[1]
+        self.buf.get(1).copied().unwrap_or(0)
     }
 
+    /// clamped to buffer
     #[inline(always)]
     pub fn service_provider_name(&self) -> &'buf [u8] {
-        &self.buf[self.buf_pos_service_provider_name()..self.buf_pos_service_name_length()]
+        buf_slice(
+            self.buf,
+            self.buf_pos_service_provider_name(),
+            self.buf_pos_service_name_length(),
+        )
     }
 
     #[inline(always)]
     pub fn service_name_length(&self) -> u8 {
-        self.buf[self.buf_pos_service_name_length()]
+        self.buf
+            .get(self.buf_pos_service_name_length())
+            .copied()
+            .unwrap_or(0)
     }
 
+    /// clamped to buffer
     #[inline(always)]
     pub fn service_name(&self) -> &'buf [u8] {
         let lft = self.buf_pos_service_name();
-        let rgh = lft + (self.service_name_length() as usize);
-        &self.buf[lft..rgh]
+        let rght = lft + (self.service_name_length() as usize);
+        buf_slice(self.buf, lft, rght)
     }
 }
 
@@ -67,29 +111,60 @@ impl<'buf> fmt::Debug for DescDVB0x48<'buf> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            ":dvb-0x48 (:service-type 0x{:02}/{}",
+            ":dvb-0x48 (:service-type 0x{:02X}/{}",
             self.service_type(),
             self.service_type()
         )?;
 
-        let mut dst_buf = [0u8; 256];
-        let mut dst_str = std::str::from_utf8_mut(&mut dst_buf).unwrap();
-
         write!(f, " :provider")?;
-        match AnnexA2::decode(self.service_provider_name(), &mut dst_str) {
-            Ok(..) => write!(f, r#" "{}""#, dst_str),
+        match AnnexA2::decode_to_string(self.service_provider_name()) {
+            Ok(name) => write!(f, r#" "{}""#, name),
             Err(err) => write!(f, " (error: {:?})", err),
         }?;
 
-        dst_buf = [0u8; 256];
-        dst_str = std::str::from_utf8_mut(&mut dst_buf).unwrap();
-
         write!(f, " :service")?;
-        match AnnexA2::decode(self.service_name(), &mut dst_str) {
-            Ok(..) => write!(f, r#" "{}""#, dst_str),
+        match AnnexA2::decode_to_string(self.service_name()) {
+            Ok(name) => write!(f, r#" "{}""#, name),
             Err(err) => write!(f, " (error: {:?})", err),
         }?;
 
         write!(f, ")")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DescDVB0x48;
+    use crate::error::{Error, Kind as ErrorKind};
+
+    const RAW: [u8; 9] = [0x01, 0x03, b'A', b'B', b'C', 0x03, b'O', b'n', b'e'];
+
+    #[test]
+    fn names() {
+        let d = DescDVB0x48::try_new(&RAW).unwrap();
+
+        assert_eq!(d.service_type(), 0x01);
+        assert_eq!(d.service_provider_name(), b"ABC");
+        assert_eq!(d.service_name(), b"One");
+    }
+
+    #[test]
+    fn truncated() {
+        for sz in 0..RAW.len() {
+            let buf = &RAW[..sz];
+            let d = DescDVB0x48::new(buf);
+
+            let expected = match sz {
+                0..=1 => 2,
+                2..=5 => 6,
+                _ => 9,
+            };
+            assert_eq!(d.validate(), Err(Error::new(ErrorKind::Buf(sz, expected))));
+
+            // clamped accessors do not panic
+            assert!(d.service_provider_name().len() <= 3);
+            assert!(d.service_name().len() <= 3);
+            let _ = format!("{:?}", d);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use alloc::string::String;
+use core::fmt;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::result::Result;
+
+/// ETSI EN 300 468 V1.15.1
+///
+/// Country availability descriptor
+#[derive(Clone)]
+pub struct DescDVB0x49<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> DescDVB0x49<'buf> {
+    const HEADER_SZ: usize = 1;
+    const COUNTRY_CODE_SZ: usize = 3;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> DescDVB0x49<'buf> {
+        DescDVB0x49 { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<DescDVB0x49<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    pub fn country_availability_flag(&self) -> bool {
+        (self.buf[0] & 0b1000_0000) != 0
+    }
+
+    /// service is available in listed countries (true)
+    /// or in all countries except listed (false)
+    #[inline(always)]
+    pub fn is_available(&self) -> bool {
+        self.country_availability_flag()
+    }
+
+    /// ISO 3166 alpha-3 country codes
+    #[inline(always)]
+    pub fn countries(&self) -> impl Iterator<Item = [u8; 3]> + 'buf {
+        self.buf[Self::HEADER_SZ..]
+            .chunks_exact(Self::COUNTRY_CODE_SZ)
+            .map(|c| [c[0], c[1], c[2]])
+    }
+}
+
+impl<'buf> fmt::Debug for DescDVB0x49<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ":dvb-0x49 (:available {} :countries",
+            self.is_available()
+        )?;
+
+        for c in self.countries() {
+            write!(f, " {}", String::from_utf8_lossy(&c))?;
+        }
+
+        write!(f, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DescDVB0x49;
+    use crate::error::{Error, Kind as ErrorKind};
+
+    #[test]
+    fn countries() {
+        let d = DescDVB0x49::try_new(b"\xFFGBRIRL").unwrap();
+
+        assert!(d.is_available());
+        assert_eq!(d.countries().collect::<Vec<_>>(), vec![*b"GBR", *b"IRL"]);
+        assert_eq!(
+            format!("{:?}", d),
+            ":dvb-0x49 (:available true :countries GBR IRL)"
+        );
+
+        let d = DescDVB0x49::try_new(b"\x7FFRA").unwrap();
+
+        assert!(!d.is_available());
+        assert_eq!(d.countries().collect::<Vec<_>>(), vec![*b"FRA"]);
+    }
+
+    #[test]
+    fn err_try_new() {
+        assert_eq!(
+            DescDVB0x49::try_new(&[]).err(),
+            Some(Error::new(ErrorKind::Buf(0, 1)))
+        );
+    }
+}
@@ -1,6 +1,10 @@
-use std::fmt;
+use core::fmt;
 
+use super::buf_slice;
 use crate::annex_a2::AnnexA2;
+use crate::error::{Error, Kind as ErrorKind};
+use crate::iso_639::ISO639;
+use crate::result::Result;
 
 /// ETSI EN 300 468 V1.15.1
 ///
@@ -18,6 +22,39 @@ impl<'buf> DescDVB0x4D<'buf> {
         DescDVB0x4D { buf }
     }
 
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<DescDVB0x4D<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    /// length fields fit into buffer
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            return Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)));
+        }
+
+        // text-length
+        let sz = self.buf_pos_text();
+        if self.buf.len() < sz {
+            return Err(Error::new(ErrorKind::Buf(self.buf.len(), sz)));
+        }
+
+        let sz = sz + (self.text_length() as usize);
+        if self.buf.len() < sz {
+            return Err(Error::new(ErrorKind::Buf(self.buf.len(), sz)));
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn iso_639_language_code(&self) -> ISO639 {
+        ISO639::must_from_bytes_3(self.buf)
+    }
+
     #[inline(always)]
     fn buf_pos_event_name(&self) -> usize {
         Self::HEADER_SZ
@@ -35,24 +72,33 @@ impl<'buf> DescDVB0x4D<'buf> {
 
     #[inline(always)]
     fn event_name_length(&self) -> u8 {
-        self.buf[3]
+        self.buf.get(3).copied().unwrap_or(0)
     }
 
+    /// clamped to buffer
     #[inline(always)]
     pub fn event_name(&self) -> &'buf [u8] {
-        &self.buf[self.buf_pos_event_name()..self.buf_pos_text_length()]
+        buf_slice(
+            self.buf,
+            self.buf_pos_event_name(),
+            self.buf_pos_text_length(),
+        )
     }
 
     #[inline(always)]
     fn text_length(&self) -> u8 {
-        self.buf[self.buf_pos_text_length()]
+        self.buf
+            .get(self.buf_pos_text_length())
+            .copied()
+            .unwrap_or(0)
     }
 
+    /// clamped to buffer
     #[inline(always)]
     pub fn text(&self) -> &'buf [u8] {
         let lft = self.buf_pos_text();
         let rght = lft + (self.text_length() as usize);
-        &self.buf[lft..rght]
+        buf_slice(self.buf, lft, rght)
     }
 }
 
@@ -60,24 +106,57 @@ impl<'buf> fmt::Debug for DescDVB0x4D<'buf> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, ":dvb-0x4d (")?;
 
-        let mut dst_buf = [0u8; 256];
-        let mut dst_str = std::str::from_utf8_mut(&mut dst_buf).unwrap();
-
         write!(f, ":event-name")?;
-        match AnnexA2::decode(self.event_name(), &mut dst_str) {
-            Ok(..) => write!(f, r#" "{}""#, dst_str),
+        match AnnexA2::decode_to_string(self.event_name()) {
+            Ok(name) => write!(f, r#" "{}""#, name),
             Err(err) => write!(f, " (error: {:?})", err),
         }?;
 
-        dst_buf = [0u8; 256];
-        dst_str = std::str::from_utf8_mut(&mut dst_buf).unwrap();
-
         write!(f, " :text")?;
-        match AnnexA2::decode(self.text(), &mut dst_str) {
-            Ok(..) => write!(f, r#" "{}""#, dst_str),
+        match AnnexA2::decode_to_string(self.text()) {
+            Ok(text) => write!(f, r#" "{}""#, text),
             Err(err) => write!(f, " (error: {})", err),
         }?;
 
         write!(f, ")")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DescDVB0x4D;
+    use crate::error::{Error, Kind as ErrorKind};
+
+    const RAW: [u8; 12] = [
+        b'e', b'n', b'g', 0x04, b'N', b'e', b'w', b's', 0x03, b'1', b'2', b'h',
+    ];
+
+    #[test]
+    fn event_name_text() {
+        let d = DescDVB0x4D::try_new(&RAW).unwrap();
+
+        assert_eq!(d.iso_639_language_code().as_str(), "eng");
+        assert_eq!(d.event_name(), b"News");
+        assert_eq!(d.text(), b"12h");
+    }
+
+    #[test]
+    fn truncated() {
+        for sz in 0..RAW.len() {
+            let buf = &RAW[..sz];
+            let d = DescDVB0x4D::new(buf);
+
+            let expected = match sz {
+                0..=3 => 4,
+                4..=8 => 9,
+                _ => 12,
+            };
+            assert_eq!(d.validate(), Err(Error::new(ErrorKind::Buf(sz, expected))));
+
+            // clamped accessors do not panic
+            assert!(d.event_name().len() <= 4);
+            assert!(d.text().len() <= 3);
+            let _ = format!("{:?}", d);
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use std::fmt;
+use core::fmt;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::result::Result;
 
 // TODO: implement
 
@@ -11,13 +14,49 @@ pub struct DescDVB0x4E<'buf> {
 }
 
 impl<'buf> DescDVB0x4E<'buf> {
-    #[allow(dead_code)]
-    const HEADER_SZ: usize = 4;
+    /// descriptor-number, last-descriptor-number,
+    /// ISO 639 language code, length-of-items
+    const HEADER_SZ: usize = 5;
 
     #[inline(always)]
     pub fn new(buf: &'buf [u8]) -> DescDVB0x4E<'buf> {
         DescDVB0x4E { buf }
     }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<DescDVB0x4E<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    /// length fields fit into buffer
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            return Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)));
+        }
+
+        // text-length
+        let sz = Self::HEADER_SZ + (self.buf[4] as usize) + 1;
+        if self.buf.len() < sz {
+            return Err(Error::new(ErrorKind::Buf(self.buf.len(), sz)));
+        }
+
+        let sz = sz + (self.buf[sz - 1] as usize);
+        if self.buf.len() < sz {
+            return Err(Error::new(ErrorKind::Buf(self.buf.len(), sz)));
+        }
+
+        Ok(())
+    }
+
+    /// body fields are not parsed yet
+    #[inline(always)]
+    pub fn try_parse(&self) -> Result<()> {
+        self.validate()?;
+        Err(Error::new(ErrorKind::DescriptorUnimplemented(0x4E)))
+    }
 }
 
 impl<'buf> fmt::Debug for DescDVB0x4E<'buf> {
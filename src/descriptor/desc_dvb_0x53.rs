@@ -1,4 +1,7 @@
-use std::fmt;
+use core::fmt;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::result::Result;
 
 // TODO: implement
 
@@ -11,10 +14,39 @@ pub struct DescDVB0x53<'buf> {
 }
 
 impl<'buf> DescDVB0x53<'buf> {
+    const CA_SYSTEM_ID_SZ: usize = 2;
+
     #[inline(always)]
     pub fn new(buf: &'buf [u8]) -> DescDVB0x53<'buf> {
         DescDVB0x53 { buf }
     }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<DescDVB0x53<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    /// loop of 2-byte CA-system-ids
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if !self.buf.len().is_multiple_of(Self::CA_SYSTEM_ID_SZ) {
+            Err(Error::new(ErrorKind::Buf(
+                self.buf.len(),
+                self.buf.len() + 1,
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// body fields are not parsed yet
+    #[inline(always)]
+    pub fn try_parse(&self) -> Result<()> {
+        self.validate()?;
+        Err(Error::new(ErrorKind::DescriptorUnimplemented(0x53)))
+    }
 }
 
 impl<'buf> fmt::Debug for DescDVB0x53<'buf> {
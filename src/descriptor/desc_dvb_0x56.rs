@@ -1,4 +1,7 @@
-use std::fmt;
+use core::fmt;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::result::Result;
 
 // TODO: implement
 
@@ -11,10 +14,38 @@ pub struct DescDVB0x56<'buf> {
 }
 
 impl<'buf> DescDVB0x56<'buf> {
+    const ITEM_SZ: usize = 5;
+
     #[inline(always)]
     pub fn new(buf: &'buf [u8]) -> DescDVB0x56<'buf> {
         DescDVB0x56 { buf }
     }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<DescDVB0x56<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    /// loop of 5-byte teletext pages
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        let rem = self.buf.len() % Self::ITEM_SZ;
+        if rem != 0 {
+            let sz = self.buf.len() - rem + Self::ITEM_SZ;
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), sz)))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// body fields are not parsed yet
+    #[inline(always)]
+    pub fn try_parse(&self) -> Result<()> {
+        self.validate()?;
+        Err(Error::new(ErrorKind::DescriptorUnimplemented(0x56)))
+    }
 }
 
 impl<'buf> fmt::Debug for DescDVB0x56<'buf> {
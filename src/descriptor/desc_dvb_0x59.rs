@@ -0,0 +1,141 @@
+use core::fmt;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::iso_639::ISO639;
+use crate::reader::be_u16;
+use crate::result::Result;
+use crate::section::{Cursor, Szer, TryNewer};
+
+/// ETSI EN 300 468 V1.15.1
+///
+/// Subtitling descriptor
+#[derive(Clone)]
+pub struct DescDVB0x59<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> DescDVB0x59<'buf> {
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> DescDVB0x59<'buf> {
+        DescDVB0x59 { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<DescDVB0x59<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    /// loop of 8-byte subtitles
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        let rem = self.buf.len() % Subtitle::SZ;
+        if rem != 0 {
+            let sz = self.buf.len() - rem + Subtitle::SZ;
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), sz)))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    pub fn subtitles(&self) -> Cursor<'buf, Subtitle<'buf>> {
+        Cursor::new(self.buf)
+    }
+}
+
+impl<'buf> fmt::Debug for DescDVB0x59<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, ":dvb-0x59 :subtitles")?;
+
+        for resl in self.subtitles() {
+            write!(f, "\n    ")?;
+            match resl {
+                Ok(s) => {
+                    s.fmt(f)?;
+                }
+                Err(err) => {
+                    write!(f, "error parse 0x59 subtitle: {}", err)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Subtitle<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> Subtitle<'buf> {
+    const SZ: usize = 8;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> Subtitle<'buf> {
+        Subtitle { buf }
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::SZ)))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    pub fn iso_639_language_code(&self) -> ISO639 {
+        ISO639::must_from_bytes_3(self.buf)
+    }
+
+    /// component_type of stream_content 0x03 (table 26);
+    /// 0x10..=0x15 - DVB subtitles
+    #[inline(always)]
+    pub fn subtitling_type(&self) -> u8 {
+        self.buf[3]
+    }
+
+    /// page-id of subtitle segments
+    #[inline(always)]
+    pub fn composition_page_id(&self) -> u16 {
+        be_u16(&self.buf[4..])
+    }
+
+    /// page-id of shared segments (e.g. logos)
+    #[inline(always)]
+    pub fn ancillary_page_id(&self) -> u16 {
+        be_u16(&self.buf[6..])
+    }
+}
+
+impl<'buf> Szer for Subtitle<'buf> {
+    #[inline(always)]
+    fn sz(&self) -> usize {
+        Self::SZ
+    }
+}
+
+impl<'buf> TryNewer<'buf> for Subtitle<'buf> {
+    #[inline(always)]
+    fn try_new(buf: &'buf [u8]) -> Result<Subtitle<'buf>> {
+        let s = Subtitle::new(buf);
+        s.validate()?;
+        Ok(s)
+    }
+}
+
+impl<'buf> fmt::Debug for Subtitle<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            r#"        :subtitle (:iso-639 "{}" :type 0x{:02X} :composition-page-id {} :ancillary-page-id {})"#,
+            self.iso_639_language_code(),
+            self.subtitling_type(),
+            self.composition_page_id(),
+            self.ancillary_page_id(),
+        )
+    }
+}
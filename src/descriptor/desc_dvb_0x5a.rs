@@ -0,0 +1,150 @@
+use core::fmt;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::reader::{be_u32, bits};
+use crate::result::Result;
+
+/// ETSI EN 300 468 V1.15.1
+///
+/// Terrestrial delivery system descriptor
+#[derive(Clone)]
+pub struct DescDVB0x5A<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> DescDVB0x5A<'buf> {
+    const SZ: usize = 11;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> DescDVB0x5A<'buf> {
+        DescDVB0x5A { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<DescDVB0x5A<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::SZ)))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// binary - 10Hz units
+    #[inline(always)]
+    pub fn centre_frequency(&self) -> u32 {
+        be_u32(self.buf)
+    }
+
+    /// 0 - 8MHz, 1 - 7MHz, 2 - 6MHz, 3 - 5MHz
+    #[inline(always)]
+    pub fn bandwidth(&self) -> u8 {
+        bits(self.buf[4], 5, 3)
+    }
+
+    /// true - HP (high priority) stream or non-hierarchical
+    #[inline(always)]
+    pub fn priority(&self) -> bool {
+        (self.buf[4] & 0b0001_0000) != 0
+    }
+
+    /// 0 - QPSK, 1 - 16-QAM, 2 - 64-QAM
+    #[inline(always)]
+    pub fn constellation(&self) -> u8 {
+        bits(self.buf[5], 6, 2)
+    }
+
+    /// alpha and native/in-depth interleaver
+    #[inline(always)]
+    pub fn hierarchy_information(&self) -> u8 {
+        bits(self.buf[5], 3, 3)
+    }
+
+    /// 0 - 1/2, 1 - 2/3, 2 - 3/4, 3 - 5/6, 4 - 7/8
+    #[inline(always)]
+    pub fn code_rate_hp_stream(&self) -> u8 {
+        self.buf[5] & 0b0000_0111
+    }
+
+    #[inline(always)]
+    pub fn code_rate_lp_stream(&self) -> u8 {
+        bits(self.buf[6], 5, 3)
+    }
+
+    /// 0 - 1/32, 1 - 1/16, 2 - 1/8, 3 - 1/4
+    #[inline(always)]
+    pub fn guard_interval(&self) -> u8 {
+        bits(self.buf[6], 3, 2)
+    }
+
+    /// 0 - 2k, 1 - 8k, 2 - 4k
+    #[inline(always)]
+    pub fn transmission_mode(&self) -> u8 {
+        bits(self.buf[6], 1, 2)
+    }
+
+    /// other frequencies in use (frequency list descriptor)
+    #[inline(always)]
+    pub fn other_frequency_flag(&self) -> bool {
+        (self.buf[6] & 0b0000_0001) != 0
+    }
+}
+
+impl<'buf> fmt::Debug for DescDVB0x5A<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Err(err) = self.validate() {
+            return write!(f, ":dvb-0x5a (:error {:?})", err);
+        }
+
+        write!(
+            f,
+            ":dvb-0x5a (:centre-frequency {} :bandwidth {} :constellation {} :code-rate-hp {} :guard-interval {} :transmission-mode {})",
+            self.centre_frequency(),
+            self.bandwidth(),
+            self.constellation(),
+            self.code_rate_hp_stream(),
+            self.guard_interval(),
+            self.transmission_mode(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DescDVB0x5A;
+    use crate::error::{Error, Kind as ErrorKind};
+
+    #[test]
+    fn terrestrial() {
+        // 538 MHz, 8MHz, 64-QAM, 2/3, 1/4, 8k
+        let raw = [
+            0x03, 0x34, 0xEC, 0x40, 0x1F, 0x81, 0x1A, 0xFF, 0xFF, 0xFF, 0xFF,
+        ];
+        let d = DescDVB0x5A::try_new(&raw).unwrap();
+
+        assert_eq!(d.centre_frequency(), 53_800_000);
+        assert_eq!(d.bandwidth(), 0);
+        assert!(d.priority());
+        assert_eq!(d.constellation(), 2);
+        assert_eq!(d.hierarchy_information(), 0);
+        assert_eq!(d.code_rate_hp_stream(), 1);
+        assert_eq!(d.code_rate_lp_stream(), 0);
+        assert_eq!(d.guard_interval(), 3);
+        assert_eq!(d.transmission_mode(), 1);
+        assert!(!d.other_frequency_flag());
+    }
+
+    #[test]
+    fn truncated() {
+        assert_eq!(
+            DescDVB0x5A::new(&[0x00; 7]).validate(),
+            Err(Error::new(ErrorKind::Buf(7, 11)))
+        );
+    }
+}
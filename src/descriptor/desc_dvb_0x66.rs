@@ -0,0 +1,92 @@
+use core::fmt;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::reader::be_u16;
+use crate::result::Result;
+
+/// ETSI EN 300 468 V1.15.1
+///
+/// Data broadcast id descriptor
+#[derive(Clone)]
+pub struct DescDVB0x66<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> DescDVB0x66<'buf> {
+    const HEADER_SZ: usize = 2;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> DescDVB0x66<'buf> {
+        DescDVB0x66 { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<DescDVB0x66<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// ETSI TS 101 162;
+    /// e.g. 0x0005 - multiprotocol encapsulation, 0x00F0 - MHP object carousel
+    #[inline(always)]
+    pub fn data_broadcast_id(&self) -> u16 {
+        be_u16(self.buf)
+    }
+
+    /// data-broadcast-id specific
+    #[inline(always)]
+    pub fn id_selector(&self) -> &'buf [u8] {
+        &self.buf[Self::HEADER_SZ..]
+    }
+}
+
+impl<'buf> fmt::Debug for DescDVB0x66<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Err(err) = self.validate() {
+            return write!(f, ":dvb-0x66 (:error {:?})", err);
+        }
+
+        write!(
+            f,
+            ":dvb-0x66 (:data-broadcast-id 0x{:04X} :id-selector-length {})",
+            self.data_broadcast_id(),
+            self.id_selector().len(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DescDVB0x66;
+    use crate::error::{Error, Kind as ErrorKind};
+
+    #[test]
+    fn data_broadcast_id() {
+        let d = DescDVB0x66::try_new(&[0x00, 0xF0, 0x01, 0x02]).unwrap();
+
+        assert_eq!(d.data_broadcast_id(), 0x00F0);
+        assert_eq!(d.id_selector(), &[0x01, 0x02]);
+        assert_eq!(
+            format!("{:?}", d),
+            ":dvb-0x66 (:data-broadcast-id 0x00F0 :id-selector-length 2)"
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let d = DescDVB0x66::new(&[0x00]);
+
+        assert_eq!(d.validate(), Err(Error::new(ErrorKind::Buf(1, 2))));
+        assert!(DescDVB0x66::try_new(&[0x00]).is_err());
+    }
+}
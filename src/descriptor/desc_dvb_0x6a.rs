@@ -1,4 +1,7 @@
-use std::fmt;
+use core::fmt;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::result::Result;
 
 // TODO: implement
 
@@ -11,10 +14,43 @@ pub struct DescDVB0x6A<'buf> {
 }
 
 impl<'buf> DescDVB0x6A<'buf> {
+    /// flags
+    const HEADER_SZ: usize = 1;
+
     #[inline(always)]
     pub fn new(buf: &'buf [u8]) -> DescDVB0x6A<'buf> {
         DescDVB0x6A { buf }
     }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<DescDVB0x6A<'buf>> {
+        let d = Self::new(buf);
+        d.validate()?;
+        Ok(d)
+    }
+
+    /// flags and flagged fields
+    /// (component-type, bsid, mainid, asvc) fit into buffer
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            return Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)));
+        }
+
+        let sz = Self::HEADER_SZ + (self.buf[0] & 0b1111_0000).count_ones() as usize;
+        if self.buf.len() < sz {
+            return Err(Error::new(ErrorKind::Buf(self.buf.len(), sz)));
+        }
+
+        Ok(())
+    }
+
+    /// body fields are not parsed yet
+    #[inline(always)]
+    pub fn try_parse(&self) -> Result<()> {
+        self.validate()?;
+        Err(Error::new(ErrorKind::DescriptorUnimplemented(0x6A)))
+    }
 }
 
 impl<'buf> fmt::Debug for DescDVB0x6A<'buf> {
@@ -1,31 +1,70 @@
 mod tag;
 
+mod desc_0x05;
+mod desc_0x06;
 mod desc_0x0a;
+mod desc_dvb_0x43;
+mod desc_dvb_0x44;
+mod desc_dvb_0x47;
 mod desc_dvb_0x48;
+mod desc_dvb_0x49;
 mod desc_dvb_0x4d;
 mod desc_dvb_0x4e;
 mod desc_dvb_0x53;
 mod desc_dvb_0x54;
 mod desc_dvb_0x56;
+mod desc_dvb_0x59;
+mod desc_dvb_0x5a;
+mod desc_dvb_0x66;
 mod desc_dvb_0x6a;
 
-use std::fmt;
-use std::str;
+use core::fmt;
 
 use crate::error::{Error, Kind as ErrorKind};
 use crate::result::Result;
 use crate::section::{Szer, TryNewer};
 
+pub use self::desc_0x05::Desc0x05;
+pub use self::desc_0x06::Desc0x06;
 pub use self::desc_0x0a::Desc0x0A;
+pub use self::desc_dvb_0x43::DescDVB0x43;
+pub use self::desc_dvb_0x44::DescDVB0x44;
+pub use self::desc_dvb_0x47::DescDVB0x47;
 pub use self::desc_dvb_0x48::DescDVB0x48;
+pub use self::desc_dvb_0x49::DescDVB0x49;
 pub use self::desc_dvb_0x4d::DescDVB0x4D;
 pub use self::desc_dvb_0x4e::DescDVB0x4E;
 pub use self::desc_dvb_0x53::DescDVB0x53;
 pub use self::desc_dvb_0x54::DescDVB0x54;
 pub use self::desc_dvb_0x56::DescDVB0x56;
+pub use self::desc_dvb_0x59::DescDVB0x59;
+pub use self::desc_dvb_0x5a::DescDVB0x5A;
+pub use self::desc_dvb_0x66::DescDVB0x66;
 pub use self::desc_dvb_0x6a::DescDVB0x6A;
 pub use self::tag::{Tag, TagDVB};
 
+/// slice clamped to buffer bounds;
+/// malformed length fields must not panic
+#[inline(always)]
+fn buf_slice(buf: &[u8], lft: usize, rght: usize) -> &[u8] {
+    let rght = rght.min(buf.len());
+    let lft = lft.min(rght);
+    &buf[lft..rght]
+}
+
+/// packed BCD, most significant digit first
+#[inline(always)]
+fn bcd(buf: &[u8], digits: usize) -> u32 {
+    (0..digits).fold(0, |v, i| {
+        let nibble = if i % 2 == 0 {
+            buf[i / 2] >> 4
+        } else {
+            buf[i / 2] & 0x0F
+        };
+        v * 10 + u32::from(nibble)
+    })
+}
+
 #[derive(Clone)]
 pub struct Descriptor<'buf> {
     buf: &'buf [u8],
@@ -65,6 +104,96 @@ impl<'buf> Descriptor<'buf> {
         self.tag().is_dvb_short_event()
     }
 
+    /// typed descriptor by tag;
+    /// Raw for not implemented tags
+    pub fn parsed(&self) -> ParsedDescriptor<'buf> {
+        let data = self.buf_data();
+
+        match self.tag() {
+            Tag::RegistrationPrivate => ParsedDescriptor::Registration(Desc0x05::new(data)),
+            Tag::DataStreamAlignment => ParsedDescriptor::DataStreamAlignment(Desc0x06::new(data)),
+            Tag::ISO639 => ParsedDescriptor::ISO639(Desc0x0A::new(data)),
+            Tag::DVB(TagDVB::SatelliteDeliverySystem) => {
+                ParsedDescriptor::SatelliteDeliverySystem(DescDVB0x43::new(data))
+            }
+            Tag::DVB(TagDVB::CableDeliverySystem) => {
+                ParsedDescriptor::CableDeliverySystem(DescDVB0x44::new(data))
+            }
+            Tag::DVB(TagDVB::BouquetName) => ParsedDescriptor::BouquetName(DescDVB0x47::new(data)),
+            Tag::DVB(TagDVB::Service) => ParsedDescriptor::Service(DescDVB0x48::new(data)),
+            Tag::DVB(TagDVB::CountryAvailability) => {
+                ParsedDescriptor::CountryAvailability(DescDVB0x49::new(data))
+            }
+            Tag::DVB(TagDVB::ShortEvent) => ParsedDescriptor::ShortEvent(DescDVB0x4D::new(data)),
+            Tag::DVB(TagDVB::ExtendedEvent) => {
+                ParsedDescriptor::ExtendedEvent(DescDVB0x4E::new(data))
+            }
+            Tag::DVB(TagDVB::CAIdentifier) => {
+                ParsedDescriptor::CAIdentifier(DescDVB0x53::new(data))
+            }
+            Tag::DVB(TagDVB::Content) => ParsedDescriptor::Content(DescDVB0x54::new(data)),
+            Tag::DVB(TagDVB::Teletext) => ParsedDescriptor::Teletext(DescDVB0x56::new(data)),
+            Tag::DVB(TagDVB::Subtitling) => ParsedDescriptor::Subtitling(DescDVB0x59::new(data)),
+            Tag::DVB(TagDVB::TerrestrialDeliverySystem) => {
+                ParsedDescriptor::TerrestrialDeliverySystem(DescDVB0x5A::new(data))
+            }
+            Tag::DVB(TagDVB::DataBroadcastId) => {
+                ParsedDescriptor::DataBroadcastId(DescDVB0x66::new(data))
+            }
+            Tag::DVB(TagDVB::AC3) => ParsedDescriptor::AC3(DescDVB0x6A::new(data)),
+            _ => ParsedDescriptor::Raw(self.clone()),
+        }
+    }
+
+    /// typed descriptor by tag;
+    /// body is validated against typed descriptor layout
+    pub fn try_parsed(&self) -> Result<ParsedDescriptor<'buf>> {
+        let data = self.buf_data();
+
+        Ok(match self.tag() {
+            Tag::RegistrationPrivate => ParsedDescriptor::Registration(Desc0x05::try_new(data)?),
+            Tag::DataStreamAlignment => {
+                ParsedDescriptor::DataStreamAlignment(Desc0x06::try_new(data)?)
+            }
+            Tag::ISO639 => ParsedDescriptor::ISO639(Desc0x0A::try_new(data)?),
+            Tag::DVB(TagDVB::SatelliteDeliverySystem) => {
+                ParsedDescriptor::SatelliteDeliverySystem(DescDVB0x43::try_new(data)?)
+            }
+            Tag::DVB(TagDVB::CableDeliverySystem) => {
+                ParsedDescriptor::CableDeliverySystem(DescDVB0x44::try_new(data)?)
+            }
+            Tag::DVB(TagDVB::BouquetName) => {
+                ParsedDescriptor::BouquetName(DescDVB0x47::try_new(data)?)
+            }
+            Tag::DVB(TagDVB::Service) => ParsedDescriptor::Service(DescDVB0x48::try_new(data)?),
+            Tag::DVB(TagDVB::CountryAvailability) => {
+                ParsedDescriptor::CountryAvailability(DescDVB0x49::try_new(data)?)
+            }
+            Tag::DVB(TagDVB::ShortEvent) => {
+                ParsedDescriptor::ShortEvent(DescDVB0x4D::try_new(data)?)
+            }
+            Tag::DVB(TagDVB::ExtendedEvent) => {
+                ParsedDescriptor::ExtendedEvent(DescDVB0x4E::try_new(data)?)
+            }
+            Tag::DVB(TagDVB::CAIdentifier) => {
+                ParsedDescriptor::CAIdentifier(DescDVB0x53::try_new(data)?)
+            }
+            Tag::DVB(TagDVB::Content) => ParsedDescriptor::Content(DescDVB0x54::try_new(data)?),
+            Tag::DVB(TagDVB::Teletext) => ParsedDescriptor::Teletext(DescDVB0x56::try_new(data)?),
+            Tag::DVB(TagDVB::Subtitling) => {
+                ParsedDescriptor::Subtitling(DescDVB0x59::try_new(data)?)
+            }
+            Tag::DVB(TagDVB::TerrestrialDeliverySystem) => {
+                ParsedDescriptor::TerrestrialDeliverySystem(DescDVB0x5A::try_new(data)?)
+            }
+            Tag::DVB(TagDVB::DataBroadcastId) => {
+                ParsedDescriptor::DataBroadcastId(DescDVB0x66::try_new(data)?)
+            }
+            Tag::DVB(TagDVB::AC3) => ParsedDescriptor::AC3(DescDVB0x6A::try_new(data)?),
+            _ => ParsedDescriptor::Raw(self.clone()),
+        })
+    }
+
     #[inline(always)]
     fn len(&self) -> u8 {
         self.buf[1]
@@ -76,9 +205,14 @@ impl<'buf> Descriptor<'buf> {
         &self.buf[Self::HEADER_SZ..]
     }
 
-    #[inline(always)]
-    fn data_as_unicode(&'buf self) -> &'buf str {
-        str::from_utf8(&self.buf_data()).unwrap_or("---")
+    /// not implemented descriptor body;
+    /// hex dump - body may be binary
+    fn fmt_data(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, ":data")?;
+        for b in self.buf_data() {
+            write!(f, " {:02X}", b)?;
+        }
+        Ok(())
     }
 }
 
@@ -104,36 +238,241 @@ impl<'buf> fmt::Debug for Descriptor<'buf> {
         write!(f, ":desc (:tag {:?} :length {})", self.tag(), self.len())?;
         write!(f, "\n          ")?;
 
-        match self.tag() {
-            Tag::ISO639 => {
-                Desc0x0A::new(self.buf_data()).fmt(f)?;
-            }
-            Tag::DVB(TagDVB::Service) => {
-                DescDVB0x48::new(self.buf_data()).fmt(f)?;
-            }
-            Tag::DVB(TagDVB::ShortEvent) => {
-                DescDVB0x4D::new(self.buf_data()).fmt(f)?;
-            }
-            Tag::DVB(TagDVB::ExtendedEvent) => {
-                DescDVB0x4E::new(self.buf_data()).fmt(f)?;
-            }
-            Tag::DVB(TagDVB::CAIdentifier) => {
-                DescDVB0x53::new(self.buf_data()).fmt(f)?;
-            }
-            Tag::DVB(TagDVB::Content) => {
-                DescDVB0x54::new(self.buf_data()).fmt(f)?;
-            }
-            Tag::DVB(TagDVB::Teletext) => {
-                DescDVB0x56::new(self.buf_data()).fmt(f)?;
-            }
-            Tag::DVB(TagDVB::AC3) => {
-                DescDVB0x6A::new(self.buf_data()).fmt(f)?;
+        match self.parsed() {
+            ParsedDescriptor::Raw(_) => self.fmt_data(f),
+            parsed => parsed.fmt(f),
+        }
+    }
+}
+
+/// descriptor dispatched by tag
+#[derive(Clone)]
+pub enum ParsedDescriptor<'buf> {
+    Registration(Desc0x05<'buf>),
+    DataStreamAlignment(Desc0x06<'buf>),
+    ISO639(Desc0x0A<'buf>),
+    SatelliteDeliverySystem(DescDVB0x43<'buf>),
+    CableDeliverySystem(DescDVB0x44<'buf>),
+    BouquetName(DescDVB0x47<'buf>),
+    Service(DescDVB0x48<'buf>),
+    CountryAvailability(DescDVB0x49<'buf>),
+    ShortEvent(DescDVB0x4D<'buf>),
+    ExtendedEvent(DescDVB0x4E<'buf>),
+    CAIdentifier(DescDVB0x53<'buf>),
+    Content(DescDVB0x54<'buf>),
+    Teletext(DescDVB0x56<'buf>),
+    Subtitling(DescDVB0x59<'buf>),
+    TerrestrialDeliverySystem(DescDVB0x5A<'buf>),
+    DataBroadcastId(DescDVB0x66<'buf>),
+    AC3(DescDVB0x6A<'buf>),
+
+    /// not implemented or custom tag
+    Raw(Descriptor<'buf>),
+}
+
+impl<'buf> ParsedDescriptor<'buf> {
+    /// body fields are parsed;
+    /// DescriptorUnimplemented for stubs and Raw
+    pub fn try_parse(&self) -> Result<()> {
+        match self {
+            ParsedDescriptor::ExtendedEvent(d) => d.try_parse(),
+            ParsedDescriptor::CAIdentifier(d) => d.try_parse(),
+            ParsedDescriptor::Content(d) => d.try_parse(),
+            ParsedDescriptor::Teletext(d) => d.try_parse(),
+            ParsedDescriptor::AC3(d) => d.try_parse(),
+            ParsedDescriptor::Raw(d) => Err(Error::new(ErrorKind::DescriptorUnimplemented(
+                u8::from(d.tag()),
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<'buf> fmt::Debug for ParsedDescriptor<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsedDescriptor::Registration(d) => d.fmt(f),
+            ParsedDescriptor::DataStreamAlignment(d) => d.fmt(f),
+            ParsedDescriptor::ISO639(d) => d.fmt(f),
+            ParsedDescriptor::SatelliteDeliverySystem(d) => d.fmt(f),
+            ParsedDescriptor::CableDeliverySystem(d) => d.fmt(f),
+            ParsedDescriptor::BouquetName(d) => d.fmt(f),
+            ParsedDescriptor::Service(d) => d.fmt(f),
+            ParsedDescriptor::CountryAvailability(d) => d.fmt(f),
+            ParsedDescriptor::ShortEvent(d) => d.fmt(f),
+            ParsedDescriptor::ExtendedEvent(d) => d.fmt(f),
+            ParsedDescriptor::CAIdentifier(d) => d.fmt(f),
+            ParsedDescriptor::Content(d) => d.fmt(f),
+            ParsedDescriptor::Teletext(d) => d.fmt(f),
+            ParsedDescriptor::Subtitling(d) => d.fmt(f),
+            ParsedDescriptor::TerrestrialDeliverySystem(d) => d.fmt(f),
+            ParsedDescriptor::DataBroadcastId(d) => d.fmt(f),
+            ParsedDescriptor::AC3(d) => d.fmt(f),
+            ParsedDescriptor::Raw(d) => d.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Descriptor, ParsedDescriptor};
+    use crate::error::{Error, Kind as ErrorKind};
+    use crate::section::TryNewer;
+
+    #[test]
+    fn parsed() {
+        let raw = [0x48, 0x05, 0x01, 0x00, 0x02, b'T', b'V'];
+        let d = Descriptor::try_new(&raw).unwrap();
+
+        match d.parsed() {
+            ParsedDescriptor::Service(s) => {
+                assert_eq!(s.service_type(), 0x01);
+                assert_eq!(s.service_name(), b"TV");
             }
-            _ => {
-                write!(f, ":data {}", self.data_as_unicode())?;
+            _ => panic!("expected service descriptor"),
+        }
+    }
+
+    #[test]
+    fn parsed_registration() {
+        let raw = [0x05, 0x04, b'A', b'C', b'-', b'3', 0x06, 0x01, 0x02];
+        let d = Descriptor::try_new(&raw).unwrap();
+
+        match d.try_parsed().unwrap() {
+            ParsedDescriptor::Registration(r) => assert_eq!(r.fourcc(), "AC-3"),
+            _ => panic!("expected registration descriptor"),
+        }
+
+        let d = Descriptor::try_new(&raw[6..]).unwrap();
+        assert!(matches!(
+            d.parsed(),
+            ParsedDescriptor::DataStreamAlignment(a) if a.alignment_type() == 2
+        ));
+    }
+
+    #[test]
+    fn parsed_raw() {
+        // private data specifier is not implemented
+        let raw = [0x5F, 0x04, 0x00, 0x00, 0x00, 0x02];
+        let d = Descriptor::try_new(&raw).unwrap();
+
+        assert!(matches!(d.parsed(), ParsedDescriptor::Raw(r) if r.buf_data() == &raw[2..]));
+        assert!(format!("{:?}", d).ends_with(":data 00 00 00 02"));
+    }
+
+    #[test]
+    fn fmt_raw_hex() {
+        // CA descriptor: ca-system-id 0x0B00, ca-pid 0x100
+        let raw = [0x09, 0x04, 0x0B, 0x00, 0xE1, 0x00];
+        let d = Descriptor::try_new(&raw).unwrap();
+
+        assert!(format!("{:?}", d).ends_with("\n          :data 0B 00 E1 00"));
+    }
+
+    #[test]
+    fn parsed_data_broadcast_id() {
+        let raw = [0x66, 0x02, 0x00, 0x05];
+        let d = Descriptor::try_new(&raw).unwrap();
+
+        assert!(
+            matches!(d.parsed(), ParsedDescriptor::DataBroadcastId(d) if d.data_broadcast_id() == 5)
+        );
+    }
+
+    #[test]
+    fn parsed_subtitling() {
+        // "eng" DVB subtitles (normal), composition page 1, ancillary page 2
+        let raw = [0x59, 0x08, b'e', b'n', b'g', 0x10, 0x00, 0x01, 0x00, 0x02];
+        let d = Descriptor::try_new(&raw).unwrap();
+
+        match d.try_parsed().unwrap() {
+            ParsedDescriptor::Subtitling(s) => {
+                let sub = s.subtitles().next().unwrap().unwrap();
+                assert_eq!(sub.iso_639_language_code().to_string(), "eng");
+                assert_eq!(sub.subtitling_type(), 0x10);
+                assert_eq!(sub.composition_page_id(), 1);
+                assert_eq!(sub.ancillary_page_id(), 2);
             }
+            _ => panic!("expected subtitling descriptor"),
         }
+    }
 
-        Ok(())
+    #[test]
+    fn try_parse_unimplemented() {
+        let cases: [(&[u8], Option<u8>); 3] = [
+            (&[0x66, 0x02, 0x00, 0x05], None),
+            (&[0x56, 0x05, b'e', b'n', b'g', 0x09, 0x00], Some(0x56)),
+            // private data specifier
+            (&[0x5F, 0x04, 0x00, 0x00, 0x00, 0x02], Some(0x5F)),
+        ];
+
+        for (raw, tag) in cases {
+            let d = Descriptor::try_new(raw).unwrap();
+
+            assert_eq!(
+                d.parsed().try_parse().err(),
+                tag.map(|tag| Error::new(ErrorKind::DescriptorUnimplemented(tag)))
+            );
+        }
+    }
+
+    #[test]
+    fn bcd() {
+        assert_eq!(super::bcd(&[0x01, 0x17, 0x27, 0x48], 8), 1_172_748);
+        assert_eq!(super::bcd(&[0x02, 0x75, 0x00, 0x03], 7), 275_000);
+    }
+
+    #[test]
+    fn try_parsed() {
+        #[rustfmt::skip]
+        let ok: [&[u8]; 10] = [
+            &[0x0A, 0x04, b'e', b'n', b'g', 0x00],
+            &[0x47, 0x00],
+            &[0x48, 0x03, 0x01, 0x00, 0x00],
+            &[0x49, 0x01, 0x80],
+            &[0x4D, 0x05, b'e', b'n', b'g', 0x00, 0x00],
+            &[0x4E, 0x06, 0x00, b'e', b'n', b'g', 0x00, 0x00],
+            &[0x53, 0x02, 0x0B, 0x00],
+            &[0x54, 0x02, 0x10, 0x00],
+            &[0x56, 0x05, b'e', b'n', b'g', 0x09, 0x00],
+            &[0x6A, 0x02, 0x80, 0x00],
+        ];
+        for raw in ok {
+            let d = Descriptor::try_new(raw).unwrap();
+
+            assert!(!matches!(
+                d.try_parsed(),
+                Ok(ParsedDescriptor::Raw(_)) | Err(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn try_parsed_truncated() {
+        #[rustfmt::skip]
+        let truncated: [(&[u8], usize, usize); 12] = [
+            (&[0x0A, 0x03, b'e', b'n', b'g'], 3, 4),
+            (&[0x43, 0x01, 0x01], 1, 11),
+            (&[0x44, 0x00], 0, 11),
+            (&[0x48, 0x02, 0x01, 0x05], 2, 8),
+            (&[0x49, 0x00], 0, 1),
+            (&[0x4D, 0x04, b'e', b'n', b'g', 0x02], 4, 7),
+            (&[0x4E, 0x05, 0x00, b'e', b'n', b'g', 0x00], 5, 6),
+            (&[0x53, 0x03, 0x0B, 0x00, 0x01], 3, 4),
+            (&[0x56, 0x04, b'e', b'n', b'g', 0x09], 4, 5),
+            (&[0x5A, 0x02, 0x03, 0x34], 2, 11),
+            (&[0x66, 0x01, 0x00], 1, 2),
+            (&[0x6A, 0x01, 0x80], 1, 2),
+        ];
+        for (raw, actual, expected) in truncated {
+            let d = Descriptor::try_new(raw).unwrap();
+
+            assert_eq!(
+                d.try_parsed().err(),
+                Some(Error::new(ErrorKind::Buf(actual, expected))),
+                "{:02X?}",
+                raw
+            );
+        }
     }
 }
@@ -1,4 +1,4 @@
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum TagDVB {
     NetworkName,
     ServiceList,
@@ -66,7 +66,7 @@ pub enum TagDVB {
     Extension,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Tag {
     VH2621381811172,
     A1381811172,
@@ -148,20 +148,104 @@ pub enum Tag {
 }
 
 impl Tag {
+    #[inline(always)]
+    pub fn is_dvb(&self) -> bool {
+        matches!(self, Tag::DVB(..))
+    }
+
+    #[inline(always)]
+    pub fn is_registration(&self) -> bool {
+        matches!(self, Tag::RegistrationPrivate)
+    }
+
+    #[inline(always)]
+    pub fn is_iso_639(&self) -> bool {
+        matches!(self, Tag::ISO639)
+    }
+
+    #[inline(always)]
+    pub fn is_dvb_network_name(&self) -> bool {
+        matches!(self, Tag::DVB(TagDVB::NetworkName))
+    }
+
+    #[inline(always)]
+    pub fn is_dvb_service_list(&self) -> bool {
+        matches!(self, Tag::DVB(TagDVB::ServiceList))
+    }
+
+    #[inline(always)]
+    pub fn is_dvb_satellite_delivery_system(&self) -> bool {
+        matches!(self, Tag::DVB(TagDVB::SatelliteDeliverySystem))
+    }
+
+    #[inline(always)]
+    pub fn is_dvb_cable_delivery_system(&self) -> bool {
+        matches!(self, Tag::DVB(TagDVB::CableDeliverySystem))
+    }
+
+    #[inline(always)]
+    pub fn is_dvb_bouquet_name(&self) -> bool {
+        matches!(self, Tag::DVB(TagDVB::BouquetName))
+    }
+
     #[inline(always)]
     pub fn is_dvb_service(&self) -> bool {
-        match self {
-            Tag::DVB(TagDVB::Service) => true,
-            _ => false,
-        }
+        matches!(self, Tag::DVB(TagDVB::Service))
+    }
+
+    #[inline(always)]
+    pub fn is_dvb_country_availability(&self) -> bool {
+        matches!(self, Tag::DVB(TagDVB::CountryAvailability))
+    }
+
+    #[inline(always)]
+    pub fn is_dvb_linkage(&self) -> bool {
+        matches!(self, Tag::DVB(TagDVB::Linkage))
     }
 
     #[inline(always)]
     pub fn is_dvb_short_event(&self) -> bool {
-        match self {
-            Tag::DVB(TagDVB::ShortEvent) => true,
-            _ => false,
-        }
+        matches!(self, Tag::DVB(TagDVB::ShortEvent))
+    }
+
+    #[inline(always)]
+    pub fn is_dvb_extended_event(&self) -> bool {
+        matches!(self, Tag::DVB(TagDVB::ExtendedEvent))
+    }
+
+    #[inline(always)]
+    pub fn is_dvb_component(&self) -> bool {
+        matches!(self, Tag::DVB(TagDVB::Component))
+    }
+
+    #[inline(always)]
+    pub fn is_dvb_stream_identifier(&self) -> bool {
+        matches!(self, Tag::DVB(TagDVB::StreamIdentifier))
+    }
+
+    #[inline(always)]
+    pub fn is_dvb_parental_rating(&self) -> bool {
+        matches!(self, Tag::DVB(TagDVB::ParentalRating))
+    }
+
+    #[inline(always)]
+    pub fn is_dvb_subtitling(&self) -> bool {
+        matches!(self, Tag::DVB(TagDVB::Subtitling))
+    }
+
+    #[inline(always)]
+    pub fn is_dvb_terrestrial_delivery_system(&self) -> bool {
+        matches!(self, Tag::DVB(TagDVB::TerrestrialDeliverySystem))
+    }
+
+    #[inline(always)]
+    pub fn is_dvb_multilingual_service_name(&self) -> bool {
+        matches!(self, Tag::DVB(TagDVB::MultilingualServiceName))
+    }
+
+    #[inline(always)]
+    pub fn is_dvb_data_broadcast_id(&self) -> bool {
+        matches!(self, Tag::DVB(TagDVB::DataBroadcastId))
     }
 }
 
@@ -440,3 +524,43 @@ impl From<Tag> for u8 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Tag, TagDVB};
+
+    #[test]
+    fn from_into_u8() {
+        for d in 0x00..=0xFF {
+            assert_eq!(u8::from(Tag::from(d)), d);
+        }
+    }
+
+    #[test]
+    fn dvb() {
+        for (d, tag) in [
+            (0x40, TagDVB::NetworkName),
+            (0x41, TagDVB::ServiceList),
+            (0x43, TagDVB::SatelliteDeliverySystem),
+            (0x44, TagDVB::CableDeliverySystem),
+            (0x47, TagDVB::BouquetName),
+            (0x49, TagDVB::CountryAvailability),
+            (0x4A, TagDVB::Linkage),
+            (0x50, TagDVB::Component),
+            (0x52, TagDVB::StreamIdentifier),
+            (0x55, TagDVB::ParentalRating),
+            (0x59, TagDVB::Subtitling),
+            (0x5A, TagDVB::TerrestrialDeliverySystem),
+            (0x5D, TagDVB::MultilingualServiceName),
+            (0x66, TagDVB::DataBroadcastId),
+        ] {
+            assert_eq!(Tag::from(d), Tag::DVB(tag));
+        }
+
+        assert!(Tag::from(0x47).is_dvb_bouquet_name());
+        assert!(Tag::from(0x5A).is_dvb_terrestrial_delivery_system());
+        assert!(Tag::from(0x66).is_dvb_data_broadcast_id());
+        assert!(!Tag::from(0x0A).is_dvb());
+        assert!(Tag::from(0x0A).is_iso_639());
+    }
+}
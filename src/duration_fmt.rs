@@ -1,7 +1,11 @@
 //! golang style duration format wrapper
-use std::cmp;
-use std::fmt;
-use std::time::Duration;
+use core::cmp;
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use crate::error::{Error, Kind as ErrorKind};
 
 pub struct DurationFmt(pub Duration);
 
@@ -46,7 +50,13 @@ impl DurationFmt {
 
     #[inline(always)]
     fn pure_hours(&self) -> u128 {
-        self.0.as_nanos() / Duration::from_secs(60 * 60).as_nanos()
+        (self.0.as_nanos() % Duration::from_secs(24 * 60 * 60).as_nanos())
+            / Duration::from_secs(60 * 60).as_nanos()
+    }
+
+    #[inline(always)]
+    fn pure_days(&self) -> u128 {
+        self.0.as_nanos() / Duration::from_secs(24 * 60 * 60).as_nanos()
     }
 }
 
@@ -86,10 +96,15 @@ impl fmt::Display for DurationFmt {
                 }
             }
             _ => {
+                let d = self.pure_days();
                 let h = self.pure_hours();
                 let m = self.pure_mins();
                 let s = self.pure_secs_as_f64();
 
+                if d != 0 {
+                    write!(f, "{}d", d)?;
+                }
+
                 if h != 0 {
                     write!(f, "{}h", h)?;
                 }
@@ -108,15 +123,151 @@ impl fmt::Display for DurationFmt {
     }
 }
 
+/// parse Display output back, e.g. "10h30m15.10s", "23ms17us", "1ns";
+/// units: d, h, m, s, ms, us (µs), ns; any component may be fractional;
+/// Display rounds seconds to 2 decimals, so round-trip is not exact
+impl FromStr for DurationFmt {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const UNITS: [(&str, u128); 8] = [
+            ("ns", 1),
+            ("us", 1_000),
+            ("µs", 1_000),
+            ("ms", 1_000_000),
+            ("s", 1_000_000_000),
+            ("m", 60 * 1_000_000_000),
+            ("h", 60 * 60 * 1_000_000_000),
+            ("d", 24 * 60 * 60 * 1_000_000_000),
+        ];
+
+        let err = |rest: &str| Error::new(ErrorKind::DurationFmt(s.len() - rest.len()));
+
+        if s.is_empty() {
+            return Err(err(s));
+        }
+
+        let mut nanos: u128 = 0;
+        let mut rest = s;
+
+        while !rest.is_empty() {
+            let int_sz = rest.bytes().take_while(u8::is_ascii_digit).count();
+            let (int, tail) = rest.split_at(int_sz);
+
+            let (frac, tail) = match tail.strip_prefix('.') {
+                Some(tail) => {
+                    let frac_sz = tail.bytes().take_while(u8::is_ascii_digit).count();
+                    tail.split_at(frac_sz)
+                }
+                None => ("", tail),
+            };
+
+            if int.is_empty() && frac.is_empty() {
+                return Err(err(rest));
+            }
+
+            // longest unit first: "ms" before "m"
+            let (unit, unit_nanos) = UNITS
+                .iter()
+                .filter(|(unit, _)| tail.starts_with(unit))
+                .max_by_key(|(unit, _)| unit.len())
+                .ok_or_else(|| err(tail))?;
+
+            let int = int.parse::<u128>().unwrap_or(0);
+            let (frac_num, frac_den) =
+                frac.bytes().take(18).fold((0u128, 1u128), |(num, den), b| {
+                    (num * 10 + u128::from(b - b'0'), den * 10)
+                });
+
+            nanos = int
+                .checked_mul(*unit_nanos)
+                .and_then(|n| n.checked_add(frac_num * unit_nanos / frac_den))
+                .and_then(|n| nanos.checked_add(n))
+                .ok_or_else(|| err(rest))?;
+
+            rest = &tail[unit.len()..];
+        }
+
+        let secs = u64::try_from(nanos / 1_000_000_000).map_err(|_| err(s))?;
+
+        Ok(DurationFmt(Duration::new(
+            secs,
+            (nanos % 1_000_000_000) as u32,
+        )))
+    }
+}
+
 impl fmt::Debug for DurationFmt {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self)
     }
 }
 
+/// signed golang style duration format wrapper
+/// e.g. for PCR/PTS deltas
+pub struct SignedDurationFmt {
+    is_negative: bool,
+    d: DurationFmt,
+}
+
+impl SignedDurationFmt {
+    pub fn new(is_negative: bool, d: Duration) -> SignedDurationFmt {
+        SignedDurationFmt {
+            is_negative,
+            d: DurationFmt(d),
+        }
+    }
+
+    pub fn from_nanos(nanos: i64) -> SignedDurationFmt {
+        SignedDurationFmt::new(nanos < 0, Duration::from_nanos(nanos.unsigned_abs()))
+    }
+
+    /// a - b
+    pub fn between(a: Duration, b: Duration) -> SignedDurationFmt {
+        if a >= b {
+            SignedDurationFmt::new(false, a - b)
+        } else {
+            SignedDurationFmt::new(true, b - a)
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_negative(&self) -> bool {
+        self.is_negative && self.d.duration() != Duration::from_nanos(0)
+    }
+
+    #[inline(always)]
+    pub fn abs(&self) -> &DurationFmt {
+        &self.d
+    }
+}
+
+impl cmp::PartialEq for SignedDurationFmt {
+    fn eq(&self, other: &Self) -> bool {
+        self.is_negative() == other.is_negative() && self.d == other.d
+    }
+}
+
+impl fmt::Display for SignedDurationFmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_negative() {
+            write!(f, "-")?;
+        }
+
+        self.d.fmt(f)
+    }
+}
+
+impl fmt::Debug for SignedDurationFmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::DurationFmt;
+    use super::{DurationFmt, SignedDurationFmt};
+    use crate::error::{Error, Kind as ErrorKind};
 
     use std::time::Duration;
 
@@ -155,4 +306,81 @@ mod tests {
             "23ms17us"
         );
     }
+
+    #[test]
+    fn fmt_d_h_m() {
+        assert_eq!(
+            format!(
+                "{}",
+                DurationFmt::from(
+                    Duration::from_secs(24*3600) + // 1d
+                    Duration::from_secs(2*3600) + // 2h
+                    Duration::from_secs(3*60) // 3m
+                )
+            ),
+            "1d2h3m"
+        );
+    }
+
+    #[test]
+    fn parse() {
+        let parse = |s: &str| s.parse::<DurationFmt>().map(|d| d.0);
+
+        assert_eq!(
+            parse("10h30m15.10s"),
+            Ok(Duration::from_secs(10 * 3600 + 30 * 60 + 15) + Duration::from_millis(100))
+        );
+        assert_eq!(
+            parse("23ms17us"),
+            Ok(Duration::from_millis(23) + Duration::from_micros(17))
+        );
+        assert_eq!(parse("1ns"), Ok(Duration::from_nanos(1)));
+        assert_eq!(
+            parse("1d2h3m"),
+            Ok(Duration::from_secs(24 * 3600 + 2 * 3600 + 3 * 60))
+        );
+        assert_eq!(parse("1.5m"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse("7µs"), Ok(Duration::from_micros(7)));
+
+        for d in [
+            Duration::from_nanos(40),
+            Duration::from_micros(17) + Duration::from_nanos(3),
+            Duration::from_millis(23) + Duration::from_micros(17),
+            Duration::from_secs(24 * 3600 + 5) + Duration::from_millis(250),
+        ] {
+            assert_eq!(parse(&DurationFmt(d).to_string()), Ok(d));
+        }
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let parse = |s: &str| s.parse::<DurationFmt>().map(|d| d.0);
+
+        assert_eq!(parse(""), Err(Error::new(ErrorKind::DurationFmt(0))));
+        assert_eq!(parse("10"), Err(Error::new(ErrorKind::DurationFmt(2))));
+        assert_eq!(parse("1h30x"), Err(Error::new(ErrorKind::DurationFmt(4))));
+        assert_eq!(parse("1h.s"), Err(Error::new(ErrorKind::DurationFmt(2))));
+        assert_eq!(parse("-1s"), Err(Error::new(ErrorKind::DurationFmt(0))));
+    }
+
+    #[test]
+    fn fmt_signed_negative() {
+        assert_eq!(
+            format!("{}", SignedDurationFmt::from_nanos(-1_500_000_000)),
+            "-1.50s"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                SignedDurationFmt::between(Duration::from_millis(10), Duration::from_millis(33))
+            ),
+            "-23ms"
+        );
+    }
+
+    #[test]
+    fn fmt_signed_positive() {
+        assert_eq!(format!("{}", SignedDurationFmt::from_nanos(40)), "40ns");
+        assert!(!SignedDurationFmt::from_nanos(0).is_negative());
+    }
 }
@@ -0,0 +1,1124 @@
+//! ETSI EN 300 743 DVB subtitles in PES (private_stream_1);
+//! segment layer only - pixel data is exposed as is
+use core::fmt;
+use core::time::Duration;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::reader::be_u16;
+use crate::result::Result;
+use crate::section::{Cursor, Szer, TryNewer};
+
+/// PES payload: data_identifier 0x20, subtitle_stream_id 0x00,
+/// segments, end_of_PES_data_field_marker 0xFF
+#[derive(Clone, Copy)]
+pub struct DVBSubtitle<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> DVBSubtitle<'buf> {
+    const HEADER_SZ: usize = 2;
+
+    const DATA_IDENTIFIER: u8 = 0x20;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> DVBSubtitle<'buf> {
+        DVBSubtitle { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<DVBSubtitle<'buf>> {
+        let s = Self::new(buf);
+        s.validate()?;
+        Ok(s)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)))
+        } else if self.data_identifier() != Self::DATA_IDENTIFIER {
+            Err(Error::new(ErrorKind::DVBSubtitleDataIdentifier(
+                self.data_identifier(),
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    pub fn data_identifier(&self) -> u8 {
+        self.buf[0]
+    }
+
+    #[inline(always)]
+    pub fn subtitle_stream_id(&self) -> u8 {
+        self.buf[1]
+    }
+
+    /// seek;
+    /// up to end marker (or garbage)
+    #[inline(always)]
+    fn buf_segments(&self) -> &'buf [u8] {
+        let buf = &self.buf[Self::HEADER_SZ..];
+        let mut pos = 0;
+
+        while pos < buf.len() && buf[pos] == Segment::SYNC_BYTE {
+            pos += Segment::new(&buf[pos..]).sz();
+        }
+
+        &buf[..pos.min(buf.len())]
+    }
+
+    #[inline(always)]
+    pub fn segments(&self) -> Cursor<'buf, Segment<'buf>> {
+        Cursor::new(self.buf_segments())
+    }
+
+    /// segments of subtitle service (subtitling descriptor);
+    /// ancillary page carries segments shared by services
+    pub fn segments_for(
+        &self,
+        composition_page_id: u16,
+        ancillary_page_id: u16,
+    ) -> impl Iterator<Item = Segment<'buf>> {
+        self.segments()
+            .filter_map(Result::ok)
+            .filter(move |s| s.page_id() == composition_page_id || s.page_id() == ancillary_page_id)
+    }
+}
+
+impl<'buf> fmt::Debug for DVBSubtitle<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Err(err) = self.validate() {
+            return write!(f, ":dvb-subtitle (:error {:?})", err);
+        }
+
+        write!(
+            f,
+            ":dvb-subtitle (:stream-id {})",
+            self.subtitle_stream_id()
+        )?;
+
+        write!(f, "\n  :segments")?;
+        for s in self.segments().filter_map(Result::ok) {
+            write!(f, "\n    ")?;
+            s.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SegmentType {
+    PageComposition,
+    RegionComposition,
+    CLUTDefinition,
+    ObjectData,
+    DisplayDefinition,
+    DisparitySignalling,
+    AlternativeCLUT,
+    EndOfDisplaySet,
+    Stuffing,
+
+    Reserved(u8),
+}
+
+impl From<u8> for SegmentType {
+    fn from(d: u8) -> Self {
+        match d {
+            0x10 => SegmentType::PageComposition,
+            0x11 => SegmentType::RegionComposition,
+            0x12 => SegmentType::CLUTDefinition,
+            0x13 => SegmentType::ObjectData,
+            0x14 => SegmentType::DisplayDefinition,
+            0x15 => SegmentType::DisparitySignalling,
+            0x16 => SegmentType::AlternativeCLUT,
+            0x80 => SegmentType::EndOfDisplaySet,
+            0xFF => SegmentType::Stuffing,
+
+            _ => SegmentType::Reserved(d),
+        }
+    }
+}
+
+impl From<SegmentType> for u8 {
+    fn from(st: SegmentType) -> u8 {
+        match st {
+            SegmentType::PageComposition => 0x10,
+            SegmentType::RegionComposition => 0x11,
+            SegmentType::CLUTDefinition => 0x12,
+            SegmentType::ObjectData => 0x13,
+            SegmentType::DisplayDefinition => 0x14,
+            SegmentType::DisparitySignalling => 0x15,
+            SegmentType::AlternativeCLUT => 0x16,
+            SegmentType::EndOfDisplaySet => 0x80,
+            SegmentType::Stuffing => 0xFF,
+
+            SegmentType::Reserved(d) => d,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Segment<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> Segment<'buf> {
+    const HEADER_SZ: usize = 6;
+
+    const SYNC_BYTE: u8 = 0x0F;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> Segment<'buf> {
+        Segment { buf }
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)))
+        } else if self.buf[0] != Self::SYNC_BYTE {
+            Err(Error::new(ErrorKind::DVBSubtitleSyncByte(self.buf[0])))
+        } else if self.buf.len() < self.sz() {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), self.sz())))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    pub fn segment_type(&self) -> SegmentType {
+        SegmentType::from(self.buf[1])
+    }
+
+    #[inline(always)]
+    pub fn page_id(&self) -> u16 {
+        be_u16(&self.buf[2..])
+    }
+
+    #[inline(always)]
+    pub fn segment_length(&self) -> u16 {
+        be_u16(&self.buf[4..])
+    }
+
+    /// seek
+    #[inline(always)]
+    pub fn buf_data(&self) -> &'buf [u8] {
+        &self.buf[Self::HEADER_SZ..self.sz()]
+    }
+
+    /// typed segment by segment-type;
+    /// Raw for not implemented or truncated segments
+    pub fn parsed(&self) -> ParsedSegment<'buf> {
+        let data = self.buf_data();
+
+        match self.segment_type() {
+            SegmentType::PageComposition => PageComposition::try_new(data)
+                .map(ParsedSegment::PageComposition)
+                .unwrap_or(ParsedSegment::Raw(*self)),
+            SegmentType::RegionComposition => RegionComposition::try_new(data)
+                .map(ParsedSegment::RegionComposition)
+                .unwrap_or(ParsedSegment::Raw(*self)),
+            SegmentType::CLUTDefinition => CLUTDefinition::try_new(data)
+                .map(ParsedSegment::CLUTDefinition)
+                .unwrap_or(ParsedSegment::Raw(*self)),
+            SegmentType::ObjectData => ObjectData::try_new(data)
+                .map(ParsedSegment::ObjectData)
+                .unwrap_or(ParsedSegment::Raw(*self)),
+            SegmentType::DisplayDefinition => DisplayDefinition::try_new(data)
+                .map(ParsedSegment::DisplayDefinition)
+                .unwrap_or(ParsedSegment::Raw(*self)),
+            SegmentType::EndOfDisplaySet => ParsedSegment::EndOfDisplaySet,
+            _ => ParsedSegment::Raw(*self),
+        }
+    }
+}
+
+impl<'buf> Szer for Segment<'buf> {
+    #[inline(always)]
+    fn sz(&self) -> usize {
+        if self.buf.len() < Self::HEADER_SZ {
+            self.buf.len()
+        } else {
+            Self::HEADER_SZ + usize::from(self.segment_length())
+        }
+    }
+}
+
+impl<'buf> TryNewer<'buf> for Segment<'buf> {
+    #[inline(always)]
+    fn try_new(buf: &'buf [u8]) -> Result<Segment<'buf>> {
+        let mut s = Segment::new(buf);
+        s.validate()?;
+        s.buf = &buf[..s.sz()]; // slice
+        Ok(s)
+    }
+}
+
+impl<'buf> fmt::Debug for Segment<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ":segment (:type {:?} :page-id {} :length {})",
+            self.segment_type(),
+            self.page_id(),
+            self.segment_length(),
+        )?;
+
+        match self.parsed() {
+            ParsedSegment::Raw(_) | ParsedSegment::EndOfDisplaySet => Ok(()),
+            parsed => {
+                write!(f, "\n      ")?;
+                parsed.fmt(f)
+            }
+        }
+    }
+}
+
+/// segment dispatched by segment-type
+#[derive(Clone, Copy)]
+pub enum ParsedSegment<'buf> {
+    PageComposition(PageComposition<'buf>),
+    RegionComposition(RegionComposition<'buf>),
+    CLUTDefinition(CLUTDefinition<'buf>),
+    ObjectData(ObjectData<'buf>),
+    DisplayDefinition(DisplayDefinition<'buf>),
+    EndOfDisplaySet,
+
+    /// not implemented segment type
+    Raw(Segment<'buf>),
+}
+
+impl<'buf> fmt::Debug for ParsedSegment<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsedSegment::PageComposition(s) => s.fmt(f),
+            ParsedSegment::RegionComposition(s) => s.fmt(f),
+            ParsedSegment::CLUTDefinition(s) => s.fmt(f),
+            ParsedSegment::ObjectData(s) => s.fmt(f),
+            ParsedSegment::DisplayDefinition(s) => s.fmt(f),
+            ParsedSegment::EndOfDisplaySet => write!(f, ":end-of-display-set"),
+            ParsedSegment::Raw(s) => s.fmt(f),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PageState {
+    /// page update
+    NormalCase,
+    /// page refresh
+    AcquisitionPoint,
+    /// new page
+    ModeChange,
+    Reserved,
+}
+
+impl From<u8> for PageState {
+    fn from(d: u8) -> Self {
+        match d {
+            0b00 => PageState::NormalCase,
+            0b01 => PageState::AcquisitionPoint,
+            0b10 => PageState::ModeChange,
+            _ => PageState::Reserved,
+        }
+    }
+}
+
+/// segment data of page composition segment
+#[derive(Clone, Copy)]
+pub struct PageComposition<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> PageComposition<'buf> {
+    const HEADER_SZ: usize = 2;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> PageComposition<'buf> {
+        PageComposition { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<PageComposition<'buf>> {
+        let s = Self::new(buf);
+        s.validate()?;
+        Ok(s)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// page is erased after time-out unless updated
+    #[inline(always)]
+    pub fn page_time_out(&self) -> Duration {
+        Duration::from_secs(u64::from(self.buf[0]))
+    }
+
+    #[inline(always)]
+    pub fn page_version_number(&self) -> u8 {
+        self.buf[1] >> 4
+    }
+
+    #[inline(always)]
+    pub fn page_state(&self) -> PageState {
+        PageState::from((self.buf[1] >> 2) & 0b11)
+    }
+
+    /// regions shown on page
+    #[inline(always)]
+    pub fn regions(&self) -> Cursor<'buf, PageRegion<'buf>> {
+        Cursor::new(&self.buf[Self::HEADER_SZ..])
+    }
+}
+
+impl<'buf> fmt::Debug for PageComposition<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ":page-composition (:time-out {:?} :version {} :state {:?})",
+            self.page_time_out(),
+            self.page_version_number(),
+            self.page_state(),
+        )?;
+
+        for r in self.regions().filter_map(Result::ok) {
+            write!(f, "\n        ")?;
+            r.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct PageRegion<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> PageRegion<'buf> {
+    const SZ: usize = 6;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> PageRegion<'buf> {
+        PageRegion { buf }
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::SZ)))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    pub fn region_id(&self) -> u8 {
+        self.buf[0]
+    }
+
+    #[inline(always)]
+    pub fn region_horizontal_address(&self) -> u16 {
+        be_u16(&self.buf[2..])
+    }
+
+    #[inline(always)]
+    pub fn region_vertical_address(&self) -> u16 {
+        be_u16(&self.buf[4..])
+    }
+}
+
+impl<'buf> Szer for PageRegion<'buf> {
+    #[inline(always)]
+    fn sz(&self) -> usize {
+        Self::SZ
+    }
+}
+
+impl<'buf> TryNewer<'buf> for PageRegion<'buf> {
+    #[inline(always)]
+    fn try_new(buf: &'buf [u8]) -> Result<PageRegion<'buf>> {
+        let r = PageRegion::new(buf);
+        r.validate()?;
+        Ok(r)
+    }
+}
+
+impl<'buf> fmt::Debug for PageRegion<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ":region (:id {} :x {} :y {})",
+            self.region_id(),
+            self.region_horizontal_address(),
+            self.region_vertical_address(),
+        )
+    }
+}
+
+/// segment data of region composition segment
+#[derive(Clone, Copy)]
+pub struct RegionComposition<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> RegionComposition<'buf> {
+    const HEADER_SZ: usize = 10;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> RegionComposition<'buf> {
+        RegionComposition { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<RegionComposition<'buf>> {
+        let s = Self::new(buf);
+        s.validate()?;
+        Ok(s)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    pub fn region_id(&self) -> u8 {
+        self.buf[0]
+    }
+
+    #[inline(always)]
+    pub fn region_version_number(&self) -> u8 {
+        self.buf[1] >> 4
+    }
+
+    /// region is filled with background pixel code
+    #[inline(always)]
+    pub fn region_fill_flag(&self) -> bool {
+        (self.buf[1] & 0b0000_1000) != 0
+    }
+
+    #[inline(always)]
+    pub fn region_width(&self) -> u16 {
+        be_u16(&self.buf[2..])
+    }
+
+    #[inline(always)]
+    pub fn region_height(&self) -> u16 {
+        be_u16(&self.buf[4..])
+    }
+
+    /// minimum CLUT: 0x01 - 2-bit, 0x02 - 4-bit, 0x03 - 8-bit
+    #[inline(always)]
+    pub fn region_level_of_compatibility(&self) -> u8 {
+        self.buf[6] >> 5
+    }
+
+    /// pixel depth: 0x01 - 2-bit, 0x02 - 4-bit, 0x03 - 8-bit
+    #[inline(always)]
+    pub fn region_depth(&self) -> u8 {
+        (self.buf[6] >> 2) & 0b111
+    }
+
+    #[inline(always)]
+    pub fn clut_id(&self) -> u8 {
+        self.buf[7]
+    }
+
+    #[inline(always)]
+    pub fn objects(&self) -> Cursor<'buf, RegionObject<'buf>> {
+        Cursor::new(&self.buf[Self::HEADER_SZ..])
+    }
+}
+
+impl<'buf> fmt::Debug for RegionComposition<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ":region-composition (:id {} :version {} :size {}x{} :depth {} :clut-id {})",
+            self.region_id(),
+            self.region_version_number(),
+            self.region_width(),
+            self.region_height(),
+            self.region_depth(),
+            self.clut_id(),
+        )?;
+
+        for o in self.objects().filter_map(Result::ok) {
+            write!(f, "\n        ")?;
+            o.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct RegionObject<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> RegionObject<'buf> {
+    const HEADER_SZ: usize = 6;
+    /// foreground and background pixel codes
+    const CHARACTER_SZ: usize = 2;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> RegionObject<'buf> {
+        RegionObject { buf }
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)))
+        } else if self.buf.len() < self.sz() {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), self.sz())))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    pub fn object_id(&self) -> u16 {
+        be_u16(self.buf)
+    }
+
+    /// 0x00 - bitmap, 0x01 - character, 0x02 - string of characters
+    #[inline(always)]
+    pub fn object_type(&self) -> u8 {
+        self.buf[2] >> 6
+    }
+
+    #[inline(always)]
+    pub fn object_provider_flag(&self) -> u8 {
+        (self.buf[2] >> 4) & 0b11
+    }
+
+    /// relative to region
+    #[inline(always)]
+    pub fn object_horizontal_position(&self) -> u16 {
+        be_u16(&self.buf[2..]) & 0x0FFF
+    }
+
+    #[inline(always)]
+    pub fn object_vertical_position(&self) -> u16 {
+        be_u16(&self.buf[4..]) & 0x0FFF
+    }
+
+    #[inline(always)]
+    fn is_character(&self) -> bool {
+        matches!(self.object_type(), 0x01 | 0x02)
+    }
+
+    /// character objects only
+    #[inline(always)]
+    pub fn foreground_pixel_code(&self) -> Option<u8> {
+        if self.is_character() {
+            Some(self.buf[6])
+        } else {
+            None
+        }
+    }
+
+    /// character objects only
+    #[inline(always)]
+    pub fn background_pixel_code(&self) -> Option<u8> {
+        if self.is_character() {
+            Some(self.buf[7])
+        } else {
+            None
+        }
+    }
+}
+
+impl<'buf> Szer for RegionObject<'buf> {
+    #[inline(always)]
+    fn sz(&self) -> usize {
+        if self.is_character() {
+            Self::HEADER_SZ + Self::CHARACTER_SZ
+        } else {
+            Self::HEADER_SZ
+        }
+    }
+}
+
+impl<'buf> TryNewer<'buf> for RegionObject<'buf> {
+    #[inline(always)]
+    fn try_new(buf: &'buf [u8]) -> Result<RegionObject<'buf>> {
+        let o = RegionObject::new(buf);
+        o.validate()?;
+        Ok(o)
+    }
+}
+
+impl<'buf> fmt::Debug for RegionObject<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ":object (:id {} :type {} :x {} :y {})",
+            self.object_id(),
+            self.object_type(),
+            self.object_horizontal_position(),
+            self.object_vertical_position(),
+        )
+    }
+}
+
+/// segment data of CLUT definition segment
+#[derive(Clone, Copy)]
+pub struct CLUTDefinition<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> CLUTDefinition<'buf> {
+    const HEADER_SZ: usize = 2;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> CLUTDefinition<'buf> {
+        CLUTDefinition { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<CLUTDefinition<'buf>> {
+        let s = Self::new(buf);
+        s.validate()?;
+        Ok(s)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    pub fn clut_id(&self) -> u8 {
+        self.buf[0]
+    }
+
+    #[inline(always)]
+    pub fn clut_version_number(&self) -> u8 {
+        self.buf[1] >> 4
+    }
+
+    #[inline(always)]
+    pub fn entries(&self) -> Cursor<'buf, CLUTEntry<'buf>> {
+        Cursor::new(&self.buf[Self::HEADER_SZ..])
+    }
+}
+
+impl<'buf> fmt::Debug for CLUTDefinition<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ":clut-definition (:id {} :version {} :entries {})",
+            self.clut_id(),
+            self.clut_version_number(),
+            self.entries().count(),
+        )
+    }
+}
+
+pub struct CLUTEntry<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> CLUTEntry<'buf> {
+    const SZ_FULL_RANGE: usize = 6;
+    const SZ: usize = 4;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> CLUTEntry<'buf> {
+        CLUTEntry { buf }
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < 2 {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), 2)))
+        } else if self.buf.len() < self.sz() {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), self.sz())))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    pub fn clut_entry_id(&self) -> u8 {
+        self.buf[0]
+    }
+
+    /// entry of 2-bit/entry CLUT
+    #[inline(always)]
+    pub fn is_2bit(&self) -> bool {
+        (self.buf[1] & 0b1000_0000) != 0
+    }
+
+    #[inline(always)]
+    pub fn is_4bit(&self) -> bool {
+        (self.buf[1] & 0b0100_0000) != 0
+    }
+
+    #[inline(always)]
+    pub fn is_8bit(&self) -> bool {
+        (self.buf[1] & 0b0010_0000) != 0
+    }
+
+    #[inline(always)]
+    pub fn full_range_flag(&self) -> bool {
+        (self.buf[1] & 0b0000_0001) != 0
+    }
+
+    /// (Y, Cr, Cb, T) scaled to 8 bits;
+    /// Y 0 - fully transparent
+    pub fn ycrcbt(&self) -> (u8, u8, u8, u8) {
+        if self.full_range_flag() {
+            (self.buf[2], self.buf[3], self.buf[4], self.buf[5])
+        } else {
+            let v = be_u16(&self.buf[2..]);
+            (
+                ((v >> 10) as u8) << 2,
+                (((v >> 6) & 0x0F) as u8) << 4,
+                (((v >> 2) & 0x0F) as u8) << 4,
+                ((v & 0b11) as u8) << 6,
+            )
+        }
+    }
+}
+
+impl<'buf> Szer for CLUTEntry<'buf> {
+    #[inline(always)]
+    fn sz(&self) -> usize {
+        if self.full_range_flag() {
+            Self::SZ_FULL_RANGE
+        } else {
+            Self::SZ
+        }
+    }
+}
+
+impl<'buf> TryNewer<'buf> for CLUTEntry<'buf> {
+    #[inline(always)]
+    fn try_new(buf: &'buf [u8]) -> Result<CLUTEntry<'buf>> {
+        let e = CLUTEntry::new(buf);
+        e.validate()?;
+        Ok(e)
+    }
+}
+
+/// segment data of object data segment
+#[derive(Clone, Copy)]
+pub struct ObjectData<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> ObjectData<'buf> {
+    const HEADER_SZ: usize = 3;
+    /// top and bottom field data block lengths
+    const PIXELS_HEADER_SZ: usize = 4;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> ObjectData<'buf> {
+        ObjectData { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<ObjectData<'buf>> {
+        let s = Self::new(buf);
+        s.validate()?;
+        Ok(s)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        let sz = Self::HEADER_SZ + Self::PIXELS_HEADER_SZ;
+
+        if self.buf.len() < Self::HEADER_SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)))
+        } else if self.is_pixels() && self.buf.len() < sz {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), sz)))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    pub fn object_id(&self) -> u16 {
+        be_u16(self.buf)
+    }
+
+    #[inline(always)]
+    pub fn object_version_number(&self) -> u8 {
+        self.buf[2] >> 4
+    }
+
+    /// 0x00 - pixels, 0x01 - string of characters
+    #[inline(always)]
+    pub fn object_coding_method(&self) -> u8 {
+        (self.buf[2] >> 2) & 0b11
+    }
+
+    /// CLUT entry 1 is not painted
+    #[inline(always)]
+    pub fn non_modifying_colour_flag(&self) -> bool {
+        (self.buf[2] & 0b0000_0010) != 0
+    }
+
+    #[inline(always)]
+    fn is_pixels(&self) -> bool {
+        self.object_coding_method() == 0x00
+    }
+
+    #[inline(always)]
+    fn top_field_data_block_length(&self) -> usize {
+        usize::from(be_u16(&self.buf[Self::HEADER_SZ..]))
+    }
+
+    #[inline(always)]
+    fn bottom_field_data_block_length(&self) -> usize {
+        usize::from(be_u16(&self.buf[Self::HEADER_SZ + 2..]))
+    }
+
+    /// seek
+    #[inline(always)]
+    fn buf_field(&self, lft: usize, sz: usize) -> &'buf [u8] {
+        let rght = (lft + sz).min(self.buf.len());
+        &self.buf[lft.min(rght)..rght]
+    }
+
+    /// run-length coded pixel-data sub-blocks;
+    /// pixel coded objects only
+    #[inline(always)]
+    pub fn top_field(&self) -> Option<&'buf [u8]> {
+        if self.is_pixels() {
+            let lft = Self::HEADER_SZ + Self::PIXELS_HEADER_SZ;
+            Some(self.buf_field(lft, self.top_field_data_block_length()))
+        } else {
+            None
+        }
+    }
+
+    /// empty - bottom field is the same as top field
+    #[inline(always)]
+    pub fn bottom_field(&self) -> Option<&'buf [u8]> {
+        if self.is_pixels() {
+            let lft = Self::HEADER_SZ + Self::PIXELS_HEADER_SZ + self.top_field_data_block_length();
+            Some(self.buf_field(lft, self.bottom_field_data_block_length()))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'buf> fmt::Debug for ObjectData<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ":object-data (:id {} :version {} :coding-method {})",
+            self.object_id(),
+            self.object_version_number(),
+            self.object_coding_method(),
+        )
+    }
+}
+
+/// segment data of display definition segment
+#[derive(Clone, Copy)]
+pub struct DisplayDefinition<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> DisplayDefinition<'buf> {
+    const HEADER_SZ: usize = 5;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> DisplayDefinition<'buf> {
+        DisplayDefinition { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<DisplayDefinition<'buf>> {
+        let s = Self::new(buf);
+        s.validate()?;
+        Ok(s)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    pub fn dds_version_number(&self) -> u8 {
+        self.buf[0] >> 4
+    }
+
+    /// display width - 1;
+    /// 719 when display definition segment is absent
+    #[inline(always)]
+    pub fn display_width(&self) -> u16 {
+        be_u16(&self.buf[1..])
+    }
+
+    /// display height - 1;
+    /// 575 when display definition segment is absent
+    #[inline(always)]
+    pub fn display_height(&self) -> u16 {
+        be_u16(&self.buf[3..])
+    }
+}
+
+impl<'buf> fmt::Debug for DisplayDefinition<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ":display-definition (:version {} :size {}x{})",
+            self.dds_version_number(),
+            u32::from(self.display_width()) + 1,
+            u32::from(self.display_height()) + 1,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{DVBSubtitle, PageState, ParsedSegment, SegmentType};
+    use crate::error::{Error, Kind as ErrorKind};
+    use crate::result::Result;
+
+    #[rustfmt::skip]
+    const DVB_SUBTITLE_RAW: [u8; 73] = [
+        0x20, 0x00,
+        // page composition, page 1: time-out 5s, version 1, mode change
+        0x0F, 0x10, 0x00, 0x01, 0x00, 0x08,
+        0x05, 0x18,
+        0x00, 0xFF, 0x00, 0x10, 0x01, 0xB0,
+        // region composition, page 1: region 0 720x60, 4-bit, clut 0
+        0x0F, 0x11, 0x00, 0x01, 0x00, 0x10,
+        0x00, 0x18, 0x02, 0xD0, 0x00, 0x3C, 0x48, 0x00, 0x00, 0x00,
+        0x00, 0x07, 0x00, 0x04, 0x00, 0x02,
+        // CLUT definition, ancillary page 2: entry 1 (4-bit, full range)
+        0x0F, 0x12, 0x00, 0x02, 0x00, 0x08,
+        0x00, 0x00, 0x01, 0x41, 0xEB, 0x80, 0x80, 0x00,
+        // object data, page 3: pixels, 1-byte top field, no bottom field
+        0x0F, 0x13, 0x00, 0x03, 0x00, 0x08,
+        0x00, 0x07, 0x00, 0x00, 0x01, 0x00, 0x00, 0xF0,
+        // end of display set, page 1
+        0x0F, 0x80, 0x00, 0x01, 0x00, 0x00,
+        // end marker
+        0xFF,
+    ];
+
+    #[test]
+    fn segments() {
+        let sub = DVBSubtitle::try_new(&DVB_SUBTITLE_RAW).unwrap();
+        let segments = sub.segments().collect::<Result<Vec<_>>>().unwrap();
+
+        assert_eq!(
+            segments
+                .iter()
+                .map(|s| (s.segment_type(), s.page_id()))
+                .collect::<Vec<_>>(),
+            vec![
+                (SegmentType::PageComposition, 1),
+                (SegmentType::RegionComposition, 1),
+                (SegmentType::CLUTDefinition, 2),
+                (SegmentType::ObjectData, 3),
+                (SegmentType::EndOfDisplaySet, 1),
+            ]
+        );
+
+        match segments[0].parsed() {
+            ParsedSegment::PageComposition(p) => {
+                assert_eq!(p.page_time_out(), Duration::from_secs(5));
+                assert_eq!(p.page_version_number(), 1);
+                assert_eq!(p.page_state(), PageState::ModeChange);
+
+                let region = p.regions().next().unwrap().unwrap();
+                assert_eq!(region.region_id(), 0);
+                assert_eq!(region.region_horizontal_address(), 16);
+                assert_eq!(region.region_vertical_address(), 432);
+            }
+            _ => panic!("expected page composition"),
+        }
+
+        match segments[1].parsed() {
+            ParsedSegment::RegionComposition(r) => {
+                assert_eq!((r.region_width(), r.region_height()), (720, 60));
+                assert!(r.region_fill_flag());
+                assert_eq!(r.region_depth(), 0x02);
+
+                let object = r.objects().next().unwrap().unwrap();
+                assert_eq!(object.object_id(), 7);
+                assert_eq!(object.object_horizontal_position(), 4);
+                assert_eq!(object.object_vertical_position(), 2);
+                assert_eq!(object.foreground_pixel_code(), None);
+            }
+            _ => panic!("expected region composition"),
+        }
+
+        match segments[2].parsed() {
+            ParsedSegment::CLUTDefinition(c) => {
+                let entry = c.entries().next().unwrap().unwrap();
+                assert_eq!(entry.clut_entry_id(), 1);
+                assert!(entry.is_4bit() && !entry.is_2bit());
+                assert_eq!(entry.ycrcbt(), (0xEB, 0x80, 0x80, 0x00));
+            }
+            _ => panic!("expected CLUT definition"),
+        }
+
+        match segments[3].parsed() {
+            ParsedSegment::ObjectData(o) => {
+                assert_eq!(o.object_id(), 7);
+                assert_eq!(o.top_field(), Some(&[0xF0][..]));
+                assert_eq!(o.bottom_field(), Some(&[][..]));
+            }
+            _ => panic!("expected object data"),
+        }
+
+        // composition page 1, ancillary page 2
+        assert_eq!(sub.segments_for(1, 2).count(), 4);
+    }
+
+    #[test]
+    fn data_identifier() {
+        assert_eq!(
+            DVBSubtitle::try_new(&[0x10, 0x00]).err(),
+            Some(Error::new(ErrorKind::DVBSubtitleDataIdentifier(0x10)))
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let sub = DVBSubtitle::new(&DVB_SUBTITLE_RAW[..24]);
+        let mut segments = sub.segments();
+
+        assert!(segments.next().unwrap().is_ok());
+        assert_eq!(
+            segments.next().unwrap().err(),
+            Some(Error::new(ErrorKind::Buf(8, 22)))
+        );
+        assert!(segments.next().is_none());
+    }
+}
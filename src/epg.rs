@@ -0,0 +1,245 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::demuxer::Table;
+use crate::iso_639::ISO639;
+use crate::result::Result;
+use crate::section::{EITEvent, WithHeader, EIT};
+use crate::subtable_id::SubtableID;
+use crate::table_id::TableID;
+
+/// EIT event with owned short event descriptor
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EpgEvent {
+    pub event_id: u16,
+    pub start_time: DateTime<Utc>,
+    pub duration: Duration,
+
+    /// short event descriptor
+    pub language: Option<ISO639>,
+    pub title: Option<String>,
+    pub text: Option<String>,
+}
+
+impl EpgEvent {
+    /// None - undefined start-time (NVOD reference event)
+    /// or not valid duration
+    fn from_event(event: &EITEvent) -> Option<EpgEvent> {
+        let short = event.short_event();
+
+        Some(EpgEvent {
+            event_id: event.event_id(),
+            start_time: event.try_start_time()?,
+            duration: event.try_duration()?,
+            language: short.as_ref().map(|s| s.language),
+            title: short.as_ref().map(|s| s.event_name.clone()),
+            text: short.map(|s| s.text),
+        })
+    }
+
+    /// start-time + duration
+    #[inline(always)]
+    pub fn end_time(&self) -> Option<DateTime<Utc>> {
+        self.start_time
+            .checked_add_signed(chrono::Duration::from_std(self.duration).ok()?)
+    }
+}
+
+/// EIT actual transport stream events (present/following and schedule);
+/// de-duplicated by (service-id, event-id), ordered by start-time;
+/// populated by demuxer on every complete EIT
+#[derive(Default)]
+pub struct EpgStore(BTreeMap<u16, Vec<EpgEvent>>);
+
+impl EpgStore {
+    /// events of service ordered by start-time
+    #[inline(always)]
+    pub fn events(&self, service_id: u16) -> impl Iterator<Item = &EpgEvent> {
+        self.0.get(&service_id).into_iter().flatten()
+    }
+
+    #[inline(always)]
+    pub fn service_ids(&self) -> impl Iterator<Item = u16> + '_ {
+        self.0.keys().copied()
+    }
+
+    /// events of all services
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.0.values().map(Vec::len).sum()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// drop events ended before t;
+    /// long-running collector memory bound
+    pub fn prune(&mut self, t: DateTime<Utc>) {
+        for events in self.0.values_mut() {
+            events.retain(|e| e.end_time().is_none_or(|end_time| end_time >= t));
+        }
+
+        self.0.retain(|_, events| !events.is_empty());
+    }
+
+    /// complete table
+    pub(crate) fn update(&mut self, id: SubtableID, table: &Table) {
+        if let SubtableID::EIT(..) = id {
+            for raw in table.sections_bytes() {
+                self.update_eit(&EIT::new(&raw));
+            }
+        }
+    }
+
+    /// EIT actual transport stream only;
+    /// repeated event-id replaces previous one
+    pub(crate) fn update_eit(&mut self, eit: &EIT) {
+        if !matches!(
+            eit.table_id(),
+            TableID::EISActualTransportStream | TableID::EISActualTransportStreamSchedule(..)
+        ) {
+            return;
+        }
+
+        let events = self.0.entry(eit.service_id()).or_default();
+
+        for event in eit.events().filter_map(Result::ok) {
+            let event = match EpgEvent::from_event(&event) {
+                Some(event) => event,
+                None => continue,
+            };
+
+            events.retain(|e| e.event_id != event.event_id);
+
+            let key = (event.start_time, event.event_id);
+            let pos = events.partition_point(|e| (e.start_time, e.event_id) < key);
+            events.insert(pos, event);
+        }
+
+        if events.is_empty() {
+            self.0.remove(&eit.service_id());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::prelude::*;
+
+    use super::EpgStore;
+    use crate::section::EIT;
+    use crate::synthetic;
+
+    /// 2016-11-21 hh:00:00, 01:00:00; short event "eng" title
+    fn event(event_id: u16, hh: u8, title: &str) -> Vec<u8> {
+        let desc_sz = 5 + title.len();
+
+        let mut buf = vec![
+            (event_id >> 8) as u8,
+            event_id as u8,
+            0xE1,
+            0x71,
+            (hh / 10) << 4 | (hh % 10),
+            0x00,
+            0x00,
+            0x01,
+            0x00,
+            0x00,
+            0x80,
+            (2 + desc_sz) as u8,
+            0x4D,
+            desc_sz as u8,
+            b'e',
+            b'n',
+            b'g',
+            title.len() as u8,
+        ];
+        buf.extend_from_slice(title.as_bytes());
+        buf.push(0x00);
+        buf
+    }
+
+    fn eit(table_id: u8, service_id: u16, events: &[Vec<u8>]) -> Vec<u8> {
+        let mut body = vec![0x00, 0x01, 0x00, 0x01, 0x00, table_id];
+        for e in events {
+            body.extend_from_slice(e);
+        }
+
+        synthetic::section(table_id, service_id, 0, (0, 0), &body)
+    }
+
+    fn titles(epg: &EpgStore, service_id: u16) -> Vec<&str> {
+        epg.events(service_id)
+            .map(|e| e.title.as_deref().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn sorted() {
+        let mut epg = EpgStore::default();
+
+        epg.update_eit(&EIT::new(&eit(
+            0x50,
+            1,
+            &[event(3, 20, "Film"), event(1, 18, "News")],
+        )));
+        epg.update_eit(&EIT::new(&eit(0x51, 1, &[event(2, 19, "Sport")])));
+
+        assert_eq!(titles(&epg, 1), ["News", "Sport", "Film"]);
+        assert_eq!(epg.len(), 3);
+
+        let first = epg.events(1).next().unwrap();
+        assert_eq!(
+            first.start_time,
+            Utc.with_ymd_and_hms(2016, 11, 21, 18, 0, 0).unwrap()
+        );
+        assert_eq!(first.language.unwrap().as_str(), "eng");
+        assert_eq!(first.text.as_deref(), Some(""));
+    }
+
+    #[test]
+    fn deduplicated() {
+        let mut epg = EpgStore::default();
+
+        // present/following and schedule carry the same event
+        epg.update_eit(&EIT::new(&eit(0x4E, 1, &[event(1, 18, "News")])));
+        epg.update_eit(&EIT::new(&eit(0x50, 1, &[event(1, 18, "News")])));
+        // new version moves event
+        epg.update_eit(&EIT::new(&eit(0x50, 1, &[event(1, 21, "Late News")])));
+
+        assert_eq!(titles(&epg, 1), ["Late News"]);
+    }
+
+    #[test]
+    fn actual_only() {
+        let mut epg = EpgStore::default();
+
+        epg.update_eit(&EIT::new(&eit(0x4F, 1, &[event(1, 18, "News")])));
+        epg.update_eit(&EIT::new(&eit(0x60, 2, &[event(1, 18, "News")])));
+
+        assert!(epg.is_empty());
+        assert_eq!(epg.events(1).count(), 0);
+    }
+
+    #[test]
+    fn prune() {
+        let mut epg = EpgStore::default();
+
+        epg.update_eit(&EIT::new(&eit(
+            0x50,
+            1,
+            &[event(1, 18, "News"), event(2, 19, "Sport")],
+        )));
+        epg.update_eit(&EIT::new(&eit(0x50, 2, &[event(1, 12, "Morning")])));
+
+        epg.prune(Utc.with_ymd_and_hms(2016, 11, 21, 19, 30, 0).unwrap());
+
+        assert_eq!(titles(&epg, 1), ["Sport"]);
+        assert_eq!(epg.service_ids().collect::<Vec<_>>(), [1]);
+    }
+}
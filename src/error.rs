@@ -1,109 +1,254 @@
+use alloc::borrow::Cow;
+use core::fmt;
+use core::str::Utf8Error;
+#[cfg(feature = "std")]
 use std::error::Error as StdError;
-use std::fmt;
+#[cfg(feature = "std")]
 use std::io::Error as IoError;
 
 #[derive(Debug)]
 pub enum Kind {
     SyncByte(u8),
+    /// (actual, expected)
+    CC(u8, u8),
     Buf(usize, usize),
     PESStartCode(u32),
     SectionSyntaxIndicatorNotSet,
+    /// (actual, max) section-length
+    SectionLength(usize, usize),
     AnnexA2EmptyBuf,
     AnnexA2UnsupportedEncoding,
     AnnexA2Decode,
     AnnexA2TableA3Unexpected(u8),
     AnnexA2TableA4Buf(usize, usize),
     AnnexA2TableA4Unexpected(u8),
+    AnnexA2EncodingTypeIdBuf(usize, usize),
     AnnexCBuf(usize, usize),
+    ISO639Buf(usize, usize),
+    ISO639Unexpected(u8),
+    /// (tag) descriptor body is not parsed yet
+    DescriptorUnimplemented(u8),
+    RTPVersion(u8),
+    RTPPayloadType(u8),
+    DVBSubtitleDataIdentifier(u8),
+    DVBSubtitleSyncByte(u8),
+    /// (position) of unexpected input
+    DurationFmt(usize),
+    Utf8(Utf8Error),
 
+    #[cfg(feature = "std")]
     Io(IoError),
 }
 
-pub struct Error(Kind);
+pub struct Error {
+    kind: Kind,
+
+    /// where error happened: table, PID, offset...
+    details: Option<Cow<'static, str>>,
+}
 
 impl Error {
     pub fn new(kind: Kind) -> Error {
-        Error(kind)
+        Error {
+            kind,
+            details: None,
+        }
+    }
+
+    pub fn new_with_details<I>(kind: Kind, details: I) -> Error
+    where
+        I: Into<Cow<'static, str>>,
+    {
+        Error {
+            kind,
+            details: Some(details.into()),
+        }
+    }
+
+    /// attach context;
+    /// outer context is prepended to existing one
+    pub fn with_context<I>(mut self, context: I) -> Error
+    where
+        I: Into<Cow<'static, str>>,
+    {
+        let context = context.into();
+
+        self.details = Some(match self.details.take() {
+            Some(details) => Cow::Owned(alloc::format!("{} {}", context, details)),
+            None => context,
+        });
+
+        self
+    }
+
+    #[inline(always)]
+    pub fn kind(&self) -> &Kind {
+        &self.kind
+    }
+
+    #[inline(always)]
+    pub fn details(&self) -> Option<&str> {
+        self.details.as_deref()
+    }
+
+    fn txt(&self) -> &str {
+        match self.kind {
+            Kind::SyncByte(..) => "expected sync byte as first element",
+            Kind::CC(..) => "unexpected continuity-counter",
+            Kind::Buf(..) => "buffer is too small, more data required",
+            Kind::PESStartCode(..) => "(pes) unexpected start code",
+            Kind::SectionSyntaxIndicatorNotSet => "(psi) section-syntax-indicator must be set",
+            Kind::SectionLength(..) => "(psi) section-length exceeds maximum",
+
+            Kind::AnnexA2UnsupportedEncoding => "(annex-a2) unsupported encoding",
+            Kind::AnnexA2Decode => "(annex-a2) decode error",
+            Kind::AnnexA2EmptyBuf => "(annex-a2 parse) got empty character buffer",
+            Kind::AnnexA2TableA3Unexpected(..) => "(annex-a2 table-a3 parse) unexpected value",
+            Kind::AnnexA2TableA4Buf(..) => {
+                "(annex-a2 table-a4 parse) buffer is too small, more data required"
+            }
+            Kind::AnnexA2TableA4Unexpected(..) => "(annex-a2 table-a4 parse) unexpected value",
+            Kind::AnnexA2EncodingTypeIdBuf(..) => {
+                "(annex-a2 encoding-type-id parse) buffer is too small, more data required"
+            }
+
+            Kind::AnnexCBuf(..) => "(annex-c parse) buffer is too small, more data required",
+
+            Kind::ISO639Buf(..) => "(iso-639 parse) buffer is too small, more data required",
+            Kind::ISO639Unexpected(..) => "(iso-639 parse) unexpected non-alphabetic value",
+
+            Kind::DescriptorUnimplemented(..) => "(descriptor) parsing is not implemented",
+
+            Kind::RTPVersion(..) => "(rtp) unsupported version",
+            Kind::RTPPayloadType(..) => "(rtp) payload type is not MP2T (33)",
+
+            Kind::DVBSubtitleDataIdentifier(..) => "(dvb-subtitle) data identifier is not 0x20",
+            Kind::DVBSubtitleSyncByte(..) => "(dvb-subtitle) expected segment sync byte 0x0F",
+
+            Kind::DurationFmt(..) => "(duration-fmt parse) invalid duration",
+
+            Kind::Utf8(..) => "invalid utf-8 text",
+
+            #[cfg(feature = "std")]
+            Kind::Io(..) => "i/o error",
+        }
     }
 }
 
+/// human-readable message;
+/// wrapped error is reported via source
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        f.write_str(self.txt())?;
+
+        match self.kind {
+            Kind::SyncByte(b)
+            | Kind::AnnexA2TableA3Unexpected(b)
+            | Kind::AnnexA2TableA4Unexpected(b)
+            | Kind::ISO639Unexpected(b)
+            | Kind::DVBSubtitleDataIdentifier(b)
+            | Kind::DVBSubtitleSyncByte(b) => write!(f, ": got 0x{:02X}", b)?,
+            Kind::DescriptorUnimplemented(tag) => write!(f, ": tag 0x{:02X}", tag)?,
+            Kind::RTPVersion(v) | Kind::RTPPayloadType(v) => write!(f, ": got {}", v)?,
+            Kind::CC(actual, expected) => write!(f, ": got {}, expected {}", actual, expected)?,
+            Kind::Buf(actual, expected)
+            | Kind::AnnexA2TableA4Buf(actual, expected)
+            | Kind::AnnexA2EncodingTypeIdBuf(actual, expected)
+            | Kind::AnnexCBuf(actual, expected)
+            | Kind::ISO639Buf(actual, expected) => {
+                write!(f, ": got {} bytes, expected {}", actual, expected)?
+            }
+            Kind::PESStartCode(actual) => write!(f, ": got 0x{:08X}", actual)?,
+            Kind::SectionLength(actual, max) => write!(f, ": got {}, max {}", actual, max)?,
+            Kind::DurationFmt(pos) => write!(f, ": at position {}", pos)?,
+            _ => {}
+        }
+
+        if let Some(details) = &self.details {
+            write!(f, " ({})", details)?;
+        }
+
+        Ok(())
     }
 }
 
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            r#"(:error ({:?}) (:txt "{}""#,
-            self.0,
-            self.description()
-        )?;
-
-        match self.0 {
+        write!(f, r#"(:error ({:?}) (:txt "{}""#, self.kind, self.txt())?;
+
+        match self.kind {
             Kind::SyncByte(b) => write!(f, " (:got 0x{:02X})", b)?,
+            Kind::CC(actual, expected) => {
+                write!(f, " (:actual {} :expected {})", actual, expected)?
+            }
             Kind::Buf(actual, expected) => {
                 write!(f, " (:sz-actual {} :sz-expected {})", actual, expected)?
             }
             Kind::PESStartCode(actual) => write!(f, " (:actual 0x{:08X})", actual)?,
+            Kind::SectionLength(actual, max) => {
+                write!(f, " (:section-length {} :max {})", actual, max)?
+            }
 
             Kind::AnnexA2TableA3Unexpected(b) => write!(f, " (:got 0x{:02X})", b)?,
             Kind::AnnexA2TableA4Buf(actual, expected) => {
                 write!(f, " (:sz-actual {} :sz-expected {})", actual, expected)?
             }
             Kind::AnnexA2TableA4Unexpected(b) => write!(f, " (:got 0x{:02X})", b)?,
+            Kind::AnnexA2EncodingTypeIdBuf(actual, expected) => {
+                write!(f, " (:sz-actual {} :sz-expected {})", actual, expected)?
+            }
 
             Kind::AnnexCBuf(actual, expected) => {
                 write!(f, " (:sz-actual {} :sz-expected {})", actual, expected)?
             }
 
-            _ => {}
-        }
+            Kind::ISO639Buf(actual, expected) => {
+                write!(f, " (:sz-actual {} :sz-expected {})", actual, expected)?
+            }
+            Kind::ISO639Unexpected(b) => write!(f, " (:got 0x{:02X})", b)?,
 
-        write!(f, "))")
-    }
-}
+            Kind::DescriptorUnimplemented(tag) => write!(f, " (:tag 0x{:02X})", tag)?,
 
-impl StdError for Error {
-    fn description(&self) -> &str {
-        match self.0 {
-            Kind::SyncByte(..) => "expected sync byte as first element",
-            Kind::Buf(..) => "buffer is too small, more data required",
-            Kind::PESStartCode(..) => "(pes) unexpected start code",
-            Kind::SectionSyntaxIndicatorNotSet => "(psi) section-syntax-indicator must be set",
+            Kind::RTPVersion(v) | Kind::RTPPayloadType(v) => write!(f, " (:got {})", v)?,
 
-            Kind::AnnexA2UnsupportedEncoding => "(annex-a2) unsupported encoding",
-            Kind::AnnexA2Decode => "(annex-a2) decode error",
-            Kind::AnnexA2EmptyBuf => "(annex-a2 parse) got empty character buffer",
-            Kind::AnnexA2TableA3Unexpected(..) => "(annex-a2 table-a3 parse) unexpected value",
-            Kind::AnnexA2TableA4Buf(..) => {
-                "(annex-a2 table-a4 parse) buffer is too small, more data required"
+            Kind::DVBSubtitleDataIdentifier(b) | Kind::DVBSubtitleSyncByte(b) => {
+                write!(f, " (:got 0x{:02X})", b)?
             }
-            Kind::AnnexA2TableA4Unexpected(..) => "(annex-a2 table-a4 parse) unexpected value",
 
-            Kind::AnnexCBuf(..) => "(annex-c parse) buffer is too small, more data required",
+            Kind::DurationFmt(pos) => write!(f, " (:pos {})", pos)?,
+
+            _ => {}
+        }
 
-            Kind::Io(ref err) => err.description(),
+        if let Some(details) = &self.details {
+            write!(f, r#" (:details "{}")"#, details)?;
         }
+
+        write!(f, "))")
     }
+}
 
-    fn cause(&self) -> Option<&dyn StdError> {
-        match self.0 {
+#[cfg(feature = "std")]
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self.kind {
+            Kind::Utf8(ref err) => Some(err),
             Kind::Io(ref err) => Some(err),
             _ => None,
         }
     }
 }
 
+/// kind only; details are not compared
 impl PartialEq for Error {
     fn eq(&self, other: &Error) -> bool {
-        match (&self.0, &other.0) {
+        match (&self.kind, &other.kind) {
             (Kind::SyncByte(a1), Kind::SyncByte(a2)) => a1 == a2,
+            (Kind::CC(a1, b1), Kind::CC(a2, b2)) => a1 == a2 && b1 == b2,
             (Kind::Buf(a1, b1), Kind::Buf(a2, b2)) => a1 == a2 && b1 == b2,
             (Kind::PESStartCode(a1), Kind::PESStartCode(a2)) => a1 == a2,
             (Kind::SectionSyntaxIndicatorNotSet, Kind::SectionSyntaxIndicatorNotSet) => true,
+            (Kind::SectionLength(a1, b1), Kind::SectionLength(a2, b2)) => a1 == a2 && b1 == b2,
             (Kind::AnnexA2EmptyBuf, Kind::AnnexA2EmptyBuf) => true,
             (Kind::AnnexA2UnsupportedEncoding, Kind::AnnexA2UnsupportedEncoding) => true,
             (Kind::AnnexA2Decode, Kind::AnnexA2Decode) => true,
@@ -112,7 +257,20 @@ impl PartialEq for Error {
                 a1 == a2 && b1 == b2
             }
             (Kind::AnnexA2TableA4Unexpected(a1), Kind::AnnexA2TableA4Unexpected(a2)) => a1 == a2,
-            (Kind::AnnexCBuf(a1, a2), Kind::AnnexCBuf(b1, b2)) => a1 == a2 && b1 == b2,
+            (Kind::AnnexA2EncodingTypeIdBuf(a1, b1), Kind::AnnexA2EncodingTypeIdBuf(a2, b2)) => {
+                a1 == a2 && b1 == b2
+            }
+            (Kind::AnnexCBuf(a1, b1), Kind::AnnexCBuf(a2, b2)) => a1 == a2 && b1 == b2,
+            (Kind::ISO639Buf(a1, b1), Kind::ISO639Buf(a2, b2)) => a1 == a2 && b1 == b2,
+            (Kind::ISO639Unexpected(a1), Kind::ISO639Unexpected(a2)) => a1 == a2,
+            (Kind::DescriptorUnimplemented(a1), Kind::DescriptorUnimplemented(a2)) => a1 == a2,
+            (Kind::RTPVersion(a1), Kind::RTPVersion(a2)) => a1 == a2,
+            (Kind::RTPPayloadType(a1), Kind::RTPPayloadType(a2)) => a1 == a2,
+            (Kind::DVBSubtitleDataIdentifier(a1), Kind::DVBSubtitleDataIdentifier(a2)) => a1 == a2,
+            (Kind::DVBSubtitleSyncByte(a1), Kind::DVBSubtitleSyncByte(a2)) => a1 == a2,
+            (Kind::DurationFmt(a1), Kind::DurationFmt(a2)) => a1 == a2,
+            (Kind::Utf8(a1), Kind::Utf8(a2)) => a1 == a2,
+            #[cfg(feature = "std")]
             (Kind::Io(..), Kind::Io(..)) => true,
             _ => false,
         }
@@ -120,8 +278,49 @@ impl PartialEq for Error {
 }
 impl Eq for Error {}
 
+#[cfg(feature = "std")]
 impl From<IoError> for Error {
     fn from(err: IoError) -> Error {
         Error::new(Kind::Io(err))
     }
 }
+
+impl From<Utf8Error> for Error {
+    fn from(err: Utf8Error) -> Error {
+        Error::new(Kind::Utf8(err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, Kind};
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            Error::new(Kind::Buf(3, 4)).to_string(),
+            "buffer is too small, more data required: got 3 bytes, expected 4"
+        );
+        assert_eq!(
+            Error::new_with_details(Kind::SyncByte(0x11), ":pid 0x0100").to_string(),
+            "expected sync byte as first element: got 0x11 (:pid 0x0100)"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn source() {
+        use std::error::Error as StdError;
+        use std::io;
+
+        let err = Error::from(io::Error::other("boom"));
+        assert_eq!(err.source().unwrap().to_string(), "boom");
+
+        let raw = vec![b'a', 0xFF];
+        let err = Error::from(core::str::from_utf8(&raw).unwrap_err());
+        assert!(matches!(err.kind(), Kind::Utf8(..)));
+        assert!(err.source().is_some());
+
+        assert!(Error::new(Kind::AnnexA2Decode).source().is_none());
+    }
+}
@@ -0,0 +1,290 @@
+//! H.264 Annex B elementary stream framing
+//!
+//! ITU-T H.264 (7.4.1.2.3, B.1)
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+
+/// NAL unit without start code
+pub struct NAL<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> NAL<'buf> {
+    pub const TYPE_IDR: u8 = 5;
+    pub const TYPE_SEI: u8 = 6;
+    pub const TYPE_SPS: u8 = 7;
+    pub const TYPE_PPS: u8 = 8;
+    pub const TYPE_AUD: u8 = 9;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> NAL<'buf> {
+        NAL { buf }
+    }
+
+    #[inline(always)]
+    pub fn buf(&self) -> &'buf [u8] {
+        self.buf
+    }
+
+    #[inline(always)]
+    pub fn nal_ref_idc(&self) -> u8 {
+        (self.buf[0] & 0b0110_0000) >> 5
+    }
+
+    #[inline(always)]
+    pub fn nal_unit_type(&self) -> u8 {
+        self.buf[0] & 0b0001_1111
+    }
+
+    /// coded slice
+    #[inline(always)]
+    pub fn is_vcl(&self) -> bool {
+        (1..=5).contains(&self.nal_unit_type())
+    }
+
+    /// slice starts new picture;
+    /// first-mb-in-slice ue(v) is 0 - single "1" bit
+    #[inline(always)]
+    fn first_mb_in_slice_zero(&self) -> bool {
+        self.buf.get(1).is_some_and(|b| (b & 0b1000_0000) != 0)
+    }
+
+    /// NAL unit begins new access unit
+    /// after access unit with coded slices;
+    /// arbitrary slice order is not supported
+    #[inline(always)]
+    fn is_au_start(&self) -> bool {
+        match self.nal_unit_type() {
+            Self::TYPE_SEI..=Self::TYPE_AUD | 14..=18 => true,
+            1..=5 => self.first_mb_in_slice_zero(),
+            _ => false,
+        }
+    }
+}
+
+impl<'buf> fmt::Debug for NAL<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ":NAL (:type {} :ref-idc {} :sz {})",
+            self.nal_unit_type(),
+            self.nal_ref_idc(),
+            self.buf.len(),
+        )
+    }
+}
+
+/// Annex B byte stream NAL units;
+/// trailing zero bytes belong to the next start code
+pub struct NALs<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> NALs<'buf> {
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> NALs<'buf> {
+        NALs { buf }
+    }
+
+    /// position after 0x000001
+    #[inline(always)]
+    fn start_code_end(buf: &[u8]) -> Option<usize> {
+        buf.windows(3)
+            .position(|w| w == [0, 0, 1])
+            .map(|pos| pos + 3)
+    }
+}
+
+impl<'buf> Iterator for NALs<'buf> {
+    type Item = NAL<'buf>;
+
+    fn next(&mut self) -> Option<NAL<'buf>> {
+        loop {
+            let lft = Self::start_code_end(self.buf)?;
+            let buf = &self.buf[lft..];
+
+            let (nal, rest) = match Self::start_code_end(buf) {
+                Some(pos) => {
+                    // drop next start code with leading zero_byte(s)
+                    let mut rght = pos - 3;
+                    while rght > 0 && buf[rght - 1] == 0 {
+                        rght -= 1;
+                    }
+                    (&buf[..rght], &buf[pos - 3..])
+                }
+                None => (buf, &buf[buf.len()..]),
+            };
+
+            self.buf = rest;
+
+            if !nal.is_empty() {
+                return Some(NAL::new(nal));
+            }
+        }
+    }
+}
+
+/// complete access unit in Annex B form
+#[derive(Clone, Debug, Default)]
+pub struct AccessUnit {
+    /// PES timestamps;
+    /// None for not the first access unit started in PES
+    pub pts: Option<Duration>,
+    pub dts: Option<Duration>,
+
+    /// contains IDR picture
+    pub random_access: bool,
+
+    /// NAL units with 4-byte start codes
+    pub buf: Vec<u8>,
+}
+
+/// access unit reassembler;
+/// PES payload must end at NAL unit boundary
+#[derive(Default)]
+pub struct AccessUnits {
+    au: AccessUnit,
+
+    /// current access unit got coded slice
+    vcl: bool,
+}
+
+impl AccessUnits {
+    const START_CODE: [u8; 4] = [0, 0, 0, 1];
+
+    pub fn new() -> AccessUnits {
+        Default::default()
+    }
+
+    /// feed PES payload;
+    /// access units completed by payload are passed to on_au
+    pub fn push<F>(
+        &mut self,
+        payload: &[u8],
+        pts: Option<Duration>,
+        dts: Option<Duration>,
+        mut on_au: F,
+    ) where
+        F: FnMut(&AccessUnit),
+    {
+        // PES timestamps apply to the first access unit started in PES
+        let mut timestamps = Some((pts, dts));
+
+        for nal in NALs::new(payload) {
+            if self.vcl && nal.is_au_start() {
+                self.emit(&mut on_au);
+            }
+
+            if self.au.buf.is_empty() {
+                let (pts, dts) = timestamps.take().unwrap_or_default();
+                self.au.pts = pts;
+                self.au.dts = dts;
+            }
+
+            self.vcl |= nal.is_vcl();
+            self.au.random_access |= nal.nal_unit_type() == NAL::TYPE_IDR;
+            self.au.buf.extend_from_slice(&Self::START_CODE);
+            self.au.buf.extend_from_slice(nal.buf());
+        }
+    }
+
+    /// end of stream;
+    /// pending access unit is passed to on_au
+    pub fn flush<F>(&mut self, mut on_au: F)
+    where
+        F: FnMut(&AccessUnit),
+    {
+        if !self.au.buf.is_empty() {
+            self.emit(&mut on_au);
+        }
+    }
+
+    #[inline(always)]
+    fn emit<F>(&mut self, on_au: &mut F)
+    where
+        F: FnMut(&AccessUnit),
+    {
+        on_au(&self.au);
+
+        self.au.buf.clear();
+        self.au.random_access = false;
+        self.vcl = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use super::{AccessUnit, AccessUnits, NALs, NAL};
+
+    #[test]
+    fn nals() {
+        // 4-byte and 3-byte start codes, trailing zero_byte
+        let buf = [
+            0x00, 0x00, 0x00, 0x01, 0x09, 0xF0, 0x00, 0x00, 0x01, 0x67, 0x42, 0x00, 0x00, 0x00,
+            0x01, 0x65, 0x88,
+        ];
+
+        let nals = NALs::new(&buf)
+            .map(|n| n.buf().to_vec())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            nals,
+            vec![vec![0x09, 0xF0], vec![0x67, 0x42], vec![0x65, 0x88]]
+        );
+        assert_eq!(NAL::new(&nals[2]).nal_unit_type(), NAL::TYPE_IDR);
+        assert_eq!(NAL::new(&nals[2]).nal_ref_idc(), 3);
+    }
+
+    #[test]
+    fn access_units() {
+        let ms = Duration::from_millis;
+        let mut aus = AccessUnits::new();
+        let mut out: Vec<AccessUnit> = Vec::new();
+
+        // AUD + SPS + PPS + IDR slice
+        let pes1 = [
+            0x00, 0x00, 0x01, 0x09, 0xF0, 0x00, 0x00, 0x01, 0x67, 0x42, 0x00, 0x00, 0x01, 0x68,
+            0xCE, 0x00, 0x00, 0x01, 0x65, 0x88,
+        ];
+        // second slice of the same picture, then AUD + P slice
+        let pes2 = [
+            0x00, 0x00, 0x01, 0x65, 0x40, 0x00, 0x00, 0x01, 0x09, 0xF0, 0x00, 0x00, 0x01, 0x41,
+            0x9A,
+        ];
+
+        aus.push(&pes1, Some(ms(40)), None, |au| out.push(au.clone()));
+        assert!(out.is_empty());
+
+        aus.push(&pes2, Some(ms(80)), None, |au| out.push(au.clone()));
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].pts, Some(ms(40)));
+        assert!(out[0].random_access);
+        assert_eq!(NALs::new(&out[0].buf).count(), 5);
+
+        // two access units in single PES
+        let pes3 = [
+            0x00, 0x00, 0x01, 0x09, 0xF0, 0x00, 0x00, 0x01, 0x41, 0x9A, 0x00, 0x00, 0x01, 0x09,
+            0xF0, 0x00, 0x00, 0x01, 0x41, 0x9B,
+        ];
+        aus.push(&pes3, Some(ms(120)), None, |au| out.push(au.clone()));
+        assert_eq!(out.len(), 3);
+
+        // first access unit started in second PES
+        assert_eq!(out[1].pts, Some(ms(80)));
+        assert!(!out[1].random_access);
+        assert_eq!(
+            out[1].buf,
+            vec![0x00, 0x00, 0x00, 0x01, 0x09, 0xF0, 0x00, 0x00, 0x00, 0x01, 0x41, 0x9A]
+        );
+        assert_eq!(out[2].pts, Some(ms(120)));
+
+        // not the first access unit started in PES
+        aus.flush(|au| out.push(au.clone()));
+        assert_eq!(out.len(), 4);
+        assert_eq!(out[3].pts, None);
+    }
+}
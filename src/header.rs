@@ -1,9 +1,12 @@
 use crate::error::{Error, Kind as ErrorKind};
+use crate::packet::Packet;
 use crate::pcr::PCR;
+use crate::pes::Timestamp;
 use crate::pid::PID;
+use crate::reader::{be_u16, be_u24, bits};
 use crate::result::Result;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum TransportScramblingControl {
     NotScrambled,
     ScrambledReserved,
@@ -25,6 +28,7 @@ impl From<u8> for TransportScramblingControl {
     }
 }
 
+#[derive(Clone, Copy)]
 pub struct Adaptation<'buf> {
     buf: &'buf [u8],
 }
@@ -32,6 +36,8 @@ pub struct Adaptation<'buf> {
 impl<'buf> Adaptation<'buf> {
     const HEADER_SZ: usize = 1;
     const HEADER_FULL_SZ: usize = 2;
+    const LTW_SZ: usize = 2;
+    const PIECEWISE_RATE_SZ: usize = 3;
 
     #[inline(always)]
     pub fn new(buf: &'buf [u8]) -> Adaptation<'buf> {
@@ -66,108 +72,260 @@ impl<'buf> Adaptation<'buf> {
         self.buf[0] as usize
     }
 
+    /// adaptation flags;
+    /// zero for stuffing-only (empty) adaptation field
     #[inline(always)]
-    #[allow(dead_code)]
-    fn discontinuity_indicator(&self) -> bool {
-        (self.buf[1] & 0b1000_0000) != 0
+    fn flags(&self) -> u8 {
+        if self.field_length() == 0 {
+            0
+        } else {
+            self.buf[1]
+        }
     }
 
     #[inline(always)]
-    #[allow(dead_code)]
-    fn random_access_indicator(&self) -> bool {
-        (self.buf[1] & 0b0100_0000) != 0
+    pub fn discontinuity_indicator(&self) -> bool {
+        (self.flags() & 0b1000_0000) != 0
+    }
+
+    /// PES packet or PSI section starts in this packet and
+    /// is a random access point (e.g. video key frame)
+    #[inline(always)]
+    pub fn random_access_indicator(&self) -> bool {
+        (self.flags() & 0b0100_0000) != 0
     }
 
     #[inline(always)]
-    #[allow(dead_code)]
     pub fn elementary_stream_priority_indicator(&self) -> bool {
-        (self.buf[1] & 0b0010_0000) != 0
+        (self.flags() & 0b0010_0000) != 0
     }
 
     /// PCR field is present?
     #[inline(always)]
-    #[allow(dead_code)]
-    fn pcr_flag(&self) -> bool {
-        (self.buf[1] & 0b0001_0000) != 0
+    pub fn pcr_flag(&self) -> bool {
+        (self.flags() & 0b0001_0000) != 0
     }
 
     /// OPCR field is present?
     #[inline(always)]
-    #[allow(dead_code)]
     pub fn opcr_flag(&self) -> bool {
-        (self.buf[1] & 0b0000_1000) != 0
+        (self.flags() & 0b0000_1000) != 0
     }
 
     /// splice countdown field is present?
     #[inline(always)]
-    #[allow(dead_code)]
     pub fn splicing_point_flag(&self) -> bool {
-        (self.buf[1] & 0b0000_0100) != 0
+        (self.flags() & 0b0000_0100) != 0
     }
 
     /// transport private data is present?
     #[inline(always)]
-    #[allow(dead_code)]
     pub fn transport_private_data_flag(&self) -> bool {
-        (self.buf[1] & 0b0000_0010) != 0
+        (self.flags() & 0b0000_0010) != 0
     }
 
-    /// transport private data is present?
+    /// adaptation field extension is present?
     #[inline(always)]
-    #[allow(dead_code)]
     pub fn adaptation_field_extension_flag(&self) -> bool {
-        (self.buf[1] & 0b0000_0001) != 0
+        (self.flags() & 0b0000_0001) != 0
     }
 
-    /// seek to PCR start position
+    /// adaptation field bounded by adaptation-field-length;
+    /// None - buffer is shorter
     #[inline(always)]
-    #[allow(dead_code)]
-    fn buf_seek_pcr(&self) -> &'buf [u8] {
-        &self.buf[Self::HEADER_FULL_SZ..]
+    fn buf_field(&self) -> Option<&'buf [u8]> {
+        self.buf.get(..self.sz())
     }
 
-    /// seek to OPCR start position
+    /// OPCR position
     #[inline(always)]
-    #[allow(dead_code)]
-    fn buf_seek_opcr(&self) -> &'buf [u8] {
-        let mut buf = self.buf_seek_pcr();
+    fn pos_opcr(&self) -> usize {
+        let mut pos = Self::HEADER_FULL_SZ;
         if self.pcr_flag() {
-            buf = &buf[PCR::SZ..];
+            pos += PCR::SZ;
+        }
+        pos
+    }
+
+    /// splice countdown position
+    #[inline(always)]
+    fn pos_splice_countdown(&self) -> usize {
+        let mut pos = self.pos_opcr();
+        if self.opcr_flag() {
+            pos += PCR::SZ;
+        }
+        pos
+    }
+
+    /// transport private data length position
+    #[inline(always)]
+    fn pos_transport_private_data(&self) -> usize {
+        let mut pos = self.pos_splice_countdown();
+        if self.splicing_point_flag() {
+            pos += 1;
         }
-        buf
+        pos
     }
 
+    /// None - not present or overruns adaptation field
     #[inline(always)]
     pub fn pcr(&self) -> Option<PCR<'buf>> {
-        if self.pcr_flag() {
-            Some(PCR::new(self.buf_seek_pcr()))
+        if !self.pcr_flag() {
+            return None;
+        }
+
+        let pos = Self::HEADER_FULL_SZ;
+        self.buf_field()?.get(pos..pos + PCR::SZ).map(PCR::new)
+    }
+
+    /// None - not present or overruns adaptation field
+    #[inline(always)]
+    pub fn opcr(&self) -> Option<PCR<'buf>> {
+        if !self.opcr_flag() {
+            return None;
+        }
+
+        let pos = self.pos_opcr();
+        self.buf_field()?.get(pos..pos + PCR::SZ).map(PCR::new)
+    }
+
+    /// packets remaining until splicing point (two's complement);
+    /// None - not present or overruns adaptation field
+    #[inline(always)]
+    pub fn splice_countdown(&self) -> Option<i8> {
+        if !self.splicing_point_flag() {
+            return None;
+        }
+
+        self.buf_field()?
+            .get(self.pos_splice_countdown())
+            .map(|b| *b as i8)
+    }
+
+    /// None - not present or overruns adaptation field
+    #[inline(always)]
+    pub fn transport_private_data(&self) -> Option<&'buf [u8]> {
+        if !self.transport_private_data_flag() {
+            return None;
+        }
+
+        let buf = self.buf_field()?;
+        let pos = self.pos_transport_private_data();
+        let sz = usize::from(*buf.get(pos)?);
+
+        buf.get(pos + 1..pos + 1 + sz)
+    }
+
+    /// adaptation field extension without length;
+    /// None - not present or overruns adaptation field
+    fn buf_adaptation_field_extension(&self) -> Option<&'buf [u8]> {
+        if !self.adaptation_field_extension_flag() {
+            return None;
+        }
+
+        let buf = self.buf_field()?;
+
+        let mut pos = self.pos_transport_private_data();
+        if self.transport_private_data_flag() {
+            pos += 1 + usize::from(*buf.get(pos)?);
+        }
+
+        let sz = usize::from(*buf.get(pos)?);
+
+        buf.get(pos + 1..pos + 1 + sz)
+    }
+
+    /// extension flags;
+    /// zero for empty extension
+    #[inline(always)]
+    fn extension_flags(ext: &[u8]) -> u8 {
+        ext.first().copied().unwrap_or(0)
+    }
+
+    /// seek to legal-time-window
+    #[inline(always)]
+    fn buf_seek_ltw(&self) -> Option<&'buf [u8]> {
+        let ext = self.buf_adaptation_field_extension()?;
+
+        if (Self::extension_flags(ext) & 0b1000_0000) != 0 {
+            ext.get(1..1 + Self::LTW_SZ)
         } else {
             None
         }
     }
 
+    /// seek to piecewise-rate
     #[inline(always)]
-    #[allow(dead_code)]
-    pub fn opcr(&self) -> Option<PCR> {
-        if self.opcr_flag() {
-            Some(PCR::new(self.buf_seek_opcr()))
+    fn buf_seek_piecewise_rate(&self) -> Option<&'buf [u8]> {
+        let ext = self.buf_adaptation_field_extension()?;
+        let flags = Self::extension_flags(ext);
+
+        let mut pos = 1;
+        if (flags & 0b1000_0000) != 0 {
+            pos += Self::LTW_SZ;
+        }
+
+        if (flags & 0b0100_0000) != 0 {
+            ext.get(pos..pos + Self::PIECEWISE_RATE_SZ)
         } else {
             None
         }
     }
 
+    /// seek to splice-type and DTS-next-AU
     #[inline(always)]
-    #[allow(dead_code)]
-    pub fn splice_countdown(&self) -> Option<u8> {
-        if self.splicing_point_flag() {
-            // TODO: implement
-            unimplemented!()
+    fn buf_seek_seamless_splice(&self) -> Option<&'buf [u8]> {
+        let ext = self.buf_adaptation_field_extension()?;
+        let flags = Self::extension_flags(ext);
+
+        let mut pos = 1;
+        if (flags & 0b1000_0000) != 0 {
+            pos += Self::LTW_SZ;
+        }
+        if (flags & 0b0100_0000) != 0 {
+            pos += Self::PIECEWISE_RATE_SZ;
+        }
+
+        if (flags & 0b0010_0000) != 0 {
+            ext.get(pos..pos + Timestamp::SZ)
         } else {
             None
         }
     }
+
+    /// legal-time-window offset is valid
+    #[inline(always)]
+    pub fn ltw_valid_flag(&self) -> Option<bool> {
+        self.buf_seek_ltw().map(|b| (b[0] & 0b1000_0000) != 0)
+    }
+
+    /// legal-time-window offset;
+    /// 27MHz / 300 units
+    #[inline(always)]
+    pub fn ltw_offset(&self) -> Option<u16> {
+        self.buf_seek_ltw().map(|b| be_u16(b) & 0x7FFF)
+    }
+
+    /// 50 bytes/second units
+    #[inline(always)]
+    pub fn piecewise_rate(&self) -> Option<u32> {
+        self.buf_seek_piecewise_rate()
+            .map(|b| be_u24(b) & 0x3F_FFFF)
+    }
+
+    #[inline(always)]
+    pub fn splice_type(&self) -> Option<u8> {
+        self.buf_seek_seamless_splice().map(|b| b[0] >> 4)
+    }
+
+    /// decoding time of the first access unit after splicing point
+    #[inline(always)]
+    pub fn dts_next_au(&self) -> Option<Timestamp<'buf>> {
+        self.buf_seek_seamless_splice().map(Timestamp::new)
+    }
 }
 
+#[derive(Clone, Copy)]
 pub struct Header<'buf> {
     buf: &'buf [u8],
 }
@@ -180,9 +338,22 @@ impl<'buf> Header<'buf> {
         Header { buf }
     }
 
+    /// header-only fast path for PID/PUSI/CC filtering;
+    /// sync-byte is checked, packet is not validated;
+    /// None - buffer is too small or sync-byte mismatch
     #[inline(always)]
-    #[allow(dead_code)]
-    fn tei(&self) -> bool {
+    pub fn peek(buf: &'buf [u8]) -> Option<Header<'buf>> {
+        if buf.len() >= Self::SZ && buf[0] == Packet::SYNC_BYTE {
+            Some(Header::new(&buf[..Self::SZ]))
+        } else {
+            None
+        }
+    }
+
+    /// transport-error-indicator;
+    /// uncorrectable packet flagged by demodulator
+    #[inline(always)]
+    pub fn tei(&self) -> bool {
         (self.buf[1] & 0b1000_0000) != 0
     }
 
@@ -200,14 +371,13 @@ impl<'buf> Header<'buf> {
     /// Packet Identifier, describing the payload data.
     #[inline(always)]
     pub fn pid(&self) -> PID {
-        PID::from((u16::from(self.buf[1] & 0b0001_1111) << 8) | u16::from(self.buf[2]))
+        PID::from(be_u16(&self.buf[1..]) & 0x1FFF)
     }
 
     /// transport-scrambling-control
     #[inline(always)]
-    #[allow(dead_code)]
-    fn tsc(&self) -> TransportScramblingControl {
-        TransportScramblingControl::from((self.buf[3] & 0b1100_0000) >> 6)
+    pub fn tsc(&self) -> TransportScramblingControl {
+        TransportScramblingControl::from(bits(self.buf[3], 6, 2))
     }
 
     #[inline(always)]
@@ -225,3 +395,131 @@ impl<'buf> Header<'buf> {
         self.buf[3] & 0b0000_1111
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Adaptation, Header};
+    use crate::pid::PID;
+    use crate::synthetic::PacketBuilder;
+
+    #[test]
+    fn adaptation_flags() {
+        // random-access + PCR + private data "ab"
+        #[rustfmt::skip]
+        let raw = [
+            0x0A, 0b0101_0010,
+            // pcr
+            0x00, 0x00, 0x00, 0x00, 0x7E, 0x00,
+            // private data
+            0x02, b'a', b'b',
+        ];
+        let a = Adaptation::try_new(&raw).unwrap();
+
+        assert!(a.random_access_indicator());
+        assert!(!a.discontinuity_indicator());
+        assert!(!a.elementary_stream_priority_indicator());
+        assert!(a.pcr().is_some());
+        assert!(a.opcr().is_none());
+        assert_eq!(a.splice_countdown(), None);
+        assert_eq!(a.transport_private_data(), Some(&b"ab"[..]));
+    }
+
+    #[test]
+    fn adaptation_extension() {
+        #[rustfmt::skip]
+        let raw = [
+            0x14, 0b0001_0001,
+            // pcr
+            0x00, 0x00, 0x00, 0x00, 0x7E, 0x00,
+            // extension: ltw + piecewise-rate + seamless-splice
+            0x0B, 0b1110_0000,
+            0x81, 0x02,
+            0xC0, 0x12, 0x34,
+            // splice-type 3, DTS-next-AU 90000
+            0x31, 0x00, 0x05, 0xBF, 0x21,
+            // stuffing
+            0xFF,
+        ];
+        let a = Adaptation::try_new(&raw).unwrap();
+
+        assert_eq!(a.ltw_valid_flag(), Some(true));
+        assert_eq!(a.ltw_offset(), Some(0x0102));
+        assert_eq!(a.piecewise_rate(), Some(0x1234));
+        assert_eq!(a.splice_type(), Some(3));
+        assert_eq!(a.dts_next_au().map(|t| t.value()), Some(90_000));
+
+        // piecewise-rate only
+        let raw = [0x06, 0b0000_0001, 0x04, 0b0100_0000, 0x00, 0x00, 0x32];
+        let a = Adaptation::try_new(&raw).unwrap();
+
+        assert_eq!(a.ltw_offset(), None);
+        assert_eq!(a.piecewise_rate(), Some(50));
+        assert_eq!(a.splice_type(), None);
+    }
+
+    #[test]
+    fn adaptation_extension_overrun() {
+        // extension-length points past adaptation field
+        let raw = [0x03, 0b0000_0001, 0x08, 0b1000_0000, 0x81, 0x02];
+        let a = Adaptation::try_new(&raw).unwrap();
+
+        assert_eq!(a.ltw_offset(), None);
+
+        // ltw flag set, field truncated
+        let raw = [0x03, 0b0000_0001, 0x01, 0b1000_0000, 0x81, 0x02];
+        let a = Adaptation::try_new(&raw).unwrap();
+
+        assert_eq!(a.ltw_offset(), None);
+    }
+
+    #[test]
+    fn adaptation_field_overrun() {
+        // PCR flag, adaptation-field-length 1
+        let a = Adaptation::try_new(&[0x01, 0x10]).unwrap();
+        assert!(a.pcr().is_none());
+
+        // OPCR, splice countdown, private data past adaptation-field-length;
+        // bytes beyond it belong to payload
+        let mut raw = [0xAA; 16];
+        raw[..2].copy_from_slice(&[0x01, 0b0000_1110]);
+        let a = Adaptation::try_new(&raw).unwrap();
+        assert!(a.opcr().is_none());
+        assert_eq!(a.splice_countdown(), None);
+        assert_eq!(a.transport_private_data(), None);
+
+        // private data length overruns
+        let a = Adaptation::try_new(&[0x04, 0b0000_0110, 0xFE, 0x05, b'a']).unwrap();
+        assert_eq!(a.splice_countdown(), Some(-2));
+        assert_eq!(a.transport_private_data(), None);
+    }
+
+    #[test]
+    fn adaptation_empty() {
+        // stuffing-only adaptation; next byte is payload
+        let raw = [0x00, 0xFF];
+        let a = Adaptation::try_new(&raw).unwrap();
+
+        assert_eq!(a.sz(), 1);
+        assert!(!a.random_access_indicator());
+        assert!(a.pcr().is_none());
+    }
+
+    #[test]
+    fn peek() {
+        let raw = PacketBuilder::new(0x100)
+            .pusi(true)
+            .cc(5)
+            .payload(&[0x01])
+            .build();
+
+        let header = Header::peek(&raw).unwrap();
+        assert_eq!(header.pid(), PID::Other(0x100));
+        assert!(header.pusi());
+        assert_eq!(header.cc(), 5);
+
+        // header only is enough
+        assert!(Header::peek(&raw[..4]).is_some());
+        assert!(Header::peek(&raw[..3]).is_none());
+        assert!(Header::peek(&[0x00, 0x01, 0x00, 0x10]).is_none());
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{ErrorKind as IoErrorKind, Read};
+use std::path::Path;
+
+use crate::demuxer::{Demuxer, DemuxerEvents};
+use crate::packet::Packet as TsPacket;
+use crate::reader::be_u16;
+use crate::result::Result;
+
+/// offline input summary
+#[derive(Clone, Debug, Default)]
+pub struct InputStats {
+    /// demuxed ts-packets
+    pub packets: usize,
+
+    /// ts-packets per PID
+    pub pids: BTreeMap<u16, usize>,
+
+    /// garbage bytes skipped to regain sync
+    pub skipped: usize,
+
+    /// ts-packets failed to demux
+    pub errors: usize,
+}
+
+/// sync-byte alignment state
+#[derive(Default)]
+pub(crate) struct Aligner {
+    /// previous packet was aligned;
+    /// otherwise next sync-byte must be confirmed
+    synced: bool,
+
+    pub(crate) stats: InputStats,
+}
+
+impl Aligner {
+    /// demux aligned packets;
+    /// return: consumed sz, unconsumed tail waits for more data
+    pub(crate) fn demux<T>(&mut self, demuxer: &mut Demuxer<T>, buf: &[u8], eof: bool) -> usize
+    where
+        T: DemuxerEvents,
+    {
+        let mut pos = 0;
+
+        while pos + TsPacket::SZ <= buf.len() {
+            if buf[pos] != TsPacket::SYNC_BYTE {
+                self.synced = false;
+                self.stats.skipped += 1;
+                pos += 1;
+                continue;
+            }
+
+            if !self.synced {
+                // sync-byte of the next packet confirms alignment
+                match buf.get(pos + TsPacket::SZ) {
+                    Some(b) if *b != TsPacket::SYNC_BYTE => {
+                        self.stats.skipped += 1;
+                        pos += 1;
+                        continue;
+                    }
+                    None if !eof => break,
+                    _ => self.synced = true,
+                }
+            }
+
+            let raw = &buf[pos..pos + TsPacket::SZ];
+
+            if demuxer.demux(raw).is_err() {
+                self.stats.errors += 1;
+            }
+
+            let pid = be_u16(&raw[1..]) & 0x1FFF;
+            *self.stats.pids.entry(pid).or_default() += 1;
+            self.stats.packets += 1;
+
+            pos += TsPacket::SZ;
+        }
+
+        if eof {
+            // trailing incomplete packet
+            self.stats.skipped += buf.len() - pos;
+            pos = buf.len();
+        }
+
+        pos
+    }
+}
+
+impl<T> Demuxer<T>
+where
+    T: DemuxerEvents,
+{
+    /// demux whole in-memory capture;
+    /// leading/inner garbage is skipped until sync-byte is found
+    pub fn demux_buf(&mut self, buf: &[u8]) -> InputStats {
+        let mut aligner = Aligner::default();
+        aligner.demux(self, buf, true);
+        aligner.stats
+    }
+
+    /// demux until EOF
+    pub fn demux_reader<R>(&mut self, mut reader: R) -> Result<InputStats>
+    where
+        R: Read,
+    {
+        let mut aligner = Aligner::default();
+        let mut buf = vec![0u8; 1024 * TsPacket::SZ];
+        let mut sz = 0;
+
+        loop {
+            let n = match reader.read(&mut buf[sz..]) {
+                Ok(n) => n,
+                Err(err) if err.kind() == IoErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+            sz += n;
+
+            let eof = n == 0;
+            let consumed = aligner.demux(self, &buf[..sz], eof);
+
+            if eof {
+                break;
+            }
+
+            buf.copy_within(consumed..sz, 0);
+            sz -= consumed;
+        }
+
+        Ok(aligner.stats)
+    }
+
+    /// demux capture file until EOF
+    pub fn demux_file<P>(&mut self, path: P) -> Result<InputStats>
+    where
+        P: AsRef<Path>,
+    {
+        self.demux_reader(File::open(path)?)
+    }
+
+    /// demux memory-mapped capture file;
+    /// avoids copying for large captures
+    #[cfg(feature = "mmap")]
+    pub fn demux_file_mmap<P>(&mut self, path: P) -> Result<InputStats>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::open(path)?;
+        // file must not be truncated while mapped
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+
+        Ok(self.demux_buf(&mmap))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::demuxer::{Demuxer, DemuxerEvents};
+    use crate::synthetic::{self, Packetizer};
+
+    struct Events;
+
+    impl DemuxerEvents for Events {}
+
+    fn capture() -> Vec<u8> {
+        let mut buf = Vec::new();
+
+        for raw in Packetizer::new(0x0000).section(&synthetic::pat(1, 0, &[(1, 0x100)])) {
+            buf.extend_from_slice(&raw);
+        }
+        for raw in Packetizer::new(0x0100).section(&synthetic::pmt(1, 0x101, &[(0x1B, 0x101)])) {
+            buf.extend_from_slice(&raw);
+        }
+
+        buf
+    }
+
+    #[test]
+    fn demux_buf() {
+        let stats = Demuxer::new(Events).demux_buf(&capture());
+
+        assert_eq!(stats.packets, 2);
+        assert_eq!(stats.pids.get(&0x0000), Some(&1));
+        assert_eq!(stats.pids.get(&0x0100), Some(&1));
+        assert_eq!(stats.skipped, 0);
+        assert_eq!(stats.errors, 0);
+    }
+
+    #[test]
+    fn demux_buf_resync() {
+        let capture = capture();
+
+        // leading garbage with false sync-byte, trailing partial packet
+        let mut buf = vec![0x00, 0x47, 0x11];
+        buf.extend_from_slice(&capture);
+        buf.extend_from_slice(&capture[..10]);
+
+        let stats = Demuxer::new(Events).demux_buf(&buf);
+
+        assert_eq!(stats.packets, 2);
+        assert_eq!(stats.skipped, 13);
+    }
+
+    #[test]
+    fn demux_reader() {
+        let mut buf = vec![0xFF; 5];
+        buf.extend_from_slice(&capture());
+
+        // small reads split packets
+        struct Chunked<'a>(&'a [u8]);
+
+        impl std::io::Read for Chunked<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let n = self.0.len().min(buf.len()).min(100);
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+
+        let stats = Demuxer::new(Events).demux_reader(Chunked(&buf)).unwrap();
+
+        assert_eq!(stats.packets, 2);
+        assert_eq!(stats.skipped, 5);
+    }
+}
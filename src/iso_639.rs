@@ -1,16 +1,137 @@
-use std::fmt;
+use core::fmt;
+use core::str;
 
-#[derive(Debug)]
-pub struct ISO639([char; 3]);
+use crate::error::{Error, Kind as ErrorKind};
+use crate::result::Result;
+
+/// ISO 639-2 three-letter language code
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ISO639([u8; 3]);
 
 impl ISO639 {
+    const SZ: usize = 3;
+
     pub fn must_from_bytes_3(b: &[u8]) -> ISO639 {
-        ISO639([char::from(b[0]), char::from(b[1]), char::from(b[2])])
+        ISO639([b[0], b[1], b[2]])
+    }
+
+    /// validating constructor;
+    /// only alphabetic ASCII is allowed
+    pub fn try_from_bytes_3(b: &[u8]) -> Result<ISO639> {
+        if b.len() < Self::SZ {
+            return Err(Error::new(ErrorKind::ISO639Buf(b.len(), Self::SZ)));
+        }
+
+        if let Some(c) = b[..Self::SZ].iter().find(|c| !c.is_ascii_alphabetic()) {
+            return Err(Error::new(ErrorKind::ISO639Unexpected(*c)));
+        }
+
+        Ok(Self::must_from_bytes_3(b))
+    }
+
+    /// 3-char code;
+    /// "---" in case code is not valid UTF-8
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        str::from_utf8(&self.0).unwrap_or("---")
+    }
+
+    /// human readable name for common ISO 639-2 codes
+    /// (both bibliographic and terminologic forms)
+    pub fn language_name(&self) -> Option<&'static str> {
+        let mut code = self.0;
+        code.make_ascii_lowercase();
+
+        Some(match &code {
+            b"eng" => "English",
+            b"ger" | b"deu" => "German",
+            b"fre" | b"fra" => "French",
+            b"rus" => "Russian",
+            b"spa" => "Spanish",
+            b"ita" => "Italian",
+            b"por" => "Portuguese",
+            b"dut" | b"nld" => "Dutch",
+            b"pol" => "Polish",
+            b"ukr" => "Ukrainian",
+            b"cze" | b"ces" => "Czech",
+            b"slo" | b"slk" => "Slovak",
+            b"hun" => "Hungarian",
+            b"rum" | b"ron" => "Romanian",
+            b"bul" => "Bulgarian",
+            b"gre" | b"ell" => "Greek",
+            b"tur" => "Turkish",
+            b"swe" => "Swedish",
+            b"nor" => "Norwegian",
+            b"dan" => "Danish",
+            b"fin" => "Finnish",
+            b"ara" => "Arabic",
+            b"heb" => "Hebrew",
+            b"chi" | b"zho" => "Chinese",
+            b"jpn" => "Japanese",
+            b"kor" => "Korean",
+            b"hin" => "Hindi",
+            b"qaa" => "Original language",
+            b"mul" => "Multiple languages",
+            b"und" => "Undetermined",
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ISO639 {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
     }
 }
 
 impl fmt::Display for ISO639 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}{}{}", self.0[0], self.0[1], self.0[2])
+        write!(
+            f,
+            "{}{}{}",
+            char::from(self.0[0]),
+            char::from(self.0[1]),
+            char::from(self.0[2])
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ISO639;
+    use crate::error::{Error, Kind as ErrorKind};
+
+    #[test]
+    fn try_from_bytes_3() {
+        let code = ISO639::try_from_bytes_3(b"eng").unwrap();
+
+        assert_eq!(code.as_str(), "eng");
+        assert_eq!(code.language_name(), Some("English"));
+        assert_eq!(format!("{}", code), "eng");
+    }
+
+    #[test]
+    fn err_try_from_bytes_3() {
+        assert_eq!(
+            ISO639::try_from_bytes_3(b"e\x01g"),
+            Err(Error::new(ErrorKind::ISO639Unexpected(0x01)))
+        );
+        assert_eq!(
+            ISO639::try_from_bytes_3(b"en"),
+            Err(Error::new(ErrorKind::ISO639Buf(2, 3)))
+        );
+    }
+
+    #[test]
+    fn language_name_unknown() {
+        assert_eq!(ISO639::must_from_bytes_3(b"xyz").language_name(), None);
+        assert_eq!(
+            ISO639::must_from_bytes_3(b"DEU").language_name(),
+            Some("German")
+        );
     }
 }
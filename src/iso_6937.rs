@@ -0,0 +1,204 @@
+//! ISO/IEC 6937 into UTF-8 transcoder
+//!
+//! ETSI EN 300 468 V1.15.1 (Figure A.1)
+//! character code table 00 - Latin alphabet
+use crate::error::{Error, Kind as ErrorKind};
+use crate::result::Result;
+
+/// not used code point
+const NA: char = '\0';
+
+/// 0xA0...0xFF spacing characters;
+/// 0xC1...0xCF are non-spacing diacritics
+#[rustfmt::skip]
+const TABLE_A0_FF: [char; 96] = [
+    // 0xA0
+    '\u{00A0}', '¡', '¢', '£', '€', '¥', '#', '§', '¤', '‘', '“', '«', '←', '↑', '→', '↓',
+    // 0xB0
+    '°', '±', '²', '³', '×', 'µ', '¶', '·', '÷', '’', '”', '»', '¼', '½', '¾', '¿',
+    // 0xC0
+    NA, NA, NA, NA, NA, NA, NA, NA, NA, NA, NA, NA, NA, NA, NA, NA,
+    // 0xD0
+    '―', '¹', '®', '©', '™', '♪', '¬', '¦', NA, NA, NA, NA, '⅛', '⅜', '⅝', '⅞',
+    // 0xE0
+    'Ω', 'Æ', 'Đ', 'ª', 'Ħ', NA, 'Ĳ', 'Ŀ', 'Ł', 'Ø', 'Œ', 'º', 'Þ', 'Ŧ', 'Ŋ', 'ŉ',
+    // 0xF0
+    'ĸ', 'æ', 'đ', 'ð', 'ħ', 'ı', 'ĳ', 'ŀ', 'ł', 'ø', 'œ', 'ß', 'þ', 'ŧ', 'ŋ', '\u{00AD}',
+];
+
+/// non-spacing diacritic prefix byte:
+/// (combining mark, base letters, composed letters)
+fn diacritic(b: u8) -> Option<(char, &'static str, &'static str)> {
+    Some(match b {
+        // grave
+        0xC1 => ('\u{0300}', "AEIOUaeiou", "ÀÈÌÒÙàèìòù"),
+        // acute
+        0xC2 => (
+            '\u{0301}',
+            "ACEILNORSUYZacegilnorsuyz",
+            "ÁĆÉÍĹŃÓŔŚÚÝŹáćéǵíĺńóŕśúýź",
+        ),
+        // circumflex
+        0xC3 => (
+            '\u{0302}',
+            "ACEGHIJOSUWYaceghijosuwy",
+            "ÂĈÊĜĤÎĴÔŜÛŴŶâĉêĝĥîĵôŝûŵŷ",
+        ),
+        // tilde
+        0xC4 => ('\u{0303}', "AINOUainou", "ÃĨÑÕŨãĩñõũ"),
+        // macron
+        0xC5 => ('\u{0304}', "AEIOUaeiou", "ĀĒĪŌŪāēīōū"),
+        // breve
+        0xC6 => ('\u{0306}', "AGUagu", "ĂĞŬăğŭ"),
+        // dot above
+        0xC7 => ('\u{0307}', "CEGIZcegz", "ĊĖĠİŻċėġż"),
+        // diaeresis
+        0xC8 => ('\u{0308}', "AEIOUYaeiouy", "ÄËÏÖÜŸäëïöüÿ"),
+        // ring above
+        0xCA => ('\u{030A}', "AUau", "ÅŮåů"),
+        // cedilla
+        0xCB => ('\u{0327}', "CGKLNRSTcgklnrst", "ÇĢĶĻŅŖŞŢçģķļņŗşţ"),
+        // double acute
+        0xCD => ('\u{030B}', "OUou", "ŐŰőű"),
+        // ogonek
+        0xCE => ('\u{0328}', "AEIUaeiu", "ĄĘĮŲąęįų"),
+        // caron
+        0xCF => ('\u{030C}', "CDELNRSTZcdelnrstz", "ČĎĚĽŇŘŠŤŽčďěľňřšťž"),
+        _ => return None,
+    })
+}
+
+/// compose base letter with diacritic;
+/// fallback to base letter + combining mark
+#[inline(always)]
+fn compose(b: u8, letter: char) -> (char, Option<char>) {
+    match diacritic(b) {
+        Some((mark, bases, composed)) => match bases.chars().position(|c| c == letter) {
+            Some(i) => (composed.chars().nth(i).unwrap_or(letter), None),
+            None => (letter, Some(mark)),
+        },
+        None => (letter, None),
+    }
+}
+
+#[inline(always)]
+fn spacing(b: u8) -> Option<char> {
+    match b {
+        0x00..=0x9F => Some(char::from(b)),
+        _ => match TABLE_A0_FF[usize::from(b - 0xA0)] {
+            NA => None,
+            c => Some(c),
+        },
+    }
+}
+
+/// write char as UTF-8;
+/// return false if there is no space left
+#[inline(always)]
+fn push(dst: &mut [u8], n: &mut usize, c: char) -> bool {
+    if *n + c.len_utf8() > dst.len() {
+        return false;
+    }
+
+    *n += c.encode_utf8(&mut dst[*n..]).len();
+
+    true
+}
+
+/// transcode ISO/IEC 6937 buffer into UTF-8;
+/// return written sz
+pub fn transcode(src: &[u8], dst: &mut [u8]) -> Result<usize> {
+    let mut n = 0;
+    let mut i = 0;
+
+    while i < src.len() {
+        let b = src[i];
+        i += 1;
+
+        let (c, mark) = if diacritic(b).is_some() {
+            // diacritic at the end of buffer - nothing to combine with
+            let letter = match src.get(i) {
+                Some(b2) => spacing(*b2).ok_or_else(|| Error::new(ErrorKind::AnnexA2Decode))?,
+                None => break,
+            };
+            i += 1;
+
+            compose(b, letter)
+        } else {
+            (
+                spacing(b).ok_or_else(|| Error::new(ErrorKind::AnnexA2Decode))?,
+                None,
+            )
+        };
+
+        if !push(dst, &mut n, c) {
+            break;
+        }
+
+        if let Some(mark) = mark {
+            if !push(dst, &mut n, mark) {
+                break;
+            }
+        }
+    }
+
+    Ok(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diacritic, transcode};
+    use crate::error::{Error, Kind as ErrorKind};
+
+    fn decode(src: &[u8]) -> String {
+        let mut dst = [0u8; 256];
+        let n = transcode(src, &mut dst).unwrap();
+
+        String::from_utf8(dst[..n].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn diacritic_table() {
+        for b in 0xC1..=0xCF {
+            if let Some((_, bases, composed)) = diacritic(b) {
+                assert_eq!(bases.chars().count(), composed.chars().count());
+            }
+        }
+    }
+
+    #[test]
+    fn transcode_ascii() {
+        assert_eq!(decode(b"BBC One"), "BBC One");
+    }
+
+    #[test]
+    fn transcode_diacritics() {
+        assert_eq!(decode(b"\xC1a la carte"), "à la carte");
+        assert_eq!(decode(b"T\xC2el\xC2evision"), "Télévision");
+        assert_eq!(decode(b"Espa\xC4na"), "España");
+        assert_eq!(decode(b"\xC8Ubersicht"), "Übersicht");
+        assert_eq!(decode(b"\xCFCesk\xC2a"), "Česká");
+        assert_eq!(decode(b"Gar\xCBcon"), "Garçon");
+    }
+
+    #[test]
+    fn transcode_diacritic_fallback() {
+        // no precomposed "q" with tilde
+        assert_eq!(decode(b"\xC4q"), "q\u{0303}");
+    }
+
+    #[test]
+    fn transcode_spacing() {
+        assert_eq!(decode(b"\xA4 5 \xB0C \xE9 \xFB"), "€ 5 °C Ø ß");
+    }
+
+    #[test]
+    fn err_transcode_not_used() {
+        let mut dst = [0u8; 16];
+
+        assert_eq!(
+            transcode(b"\xC0", &mut dst),
+            Err(Error::new(ErrorKind::AnnexA2Decode))
+        );
+    }
+}
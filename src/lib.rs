@@ -1,33 +1,104 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![allow(clippy::upper_case_acronyms)]
+
+extern crate alloc;
+
 pub mod error;
 pub mod result;
 
 mod annex_a2;
 mod annex_c;
+mod audio_params;
+mod bits;
+mod cc_repair;
+mod codec;
+mod crc32;
+#[cfg(feature = "std")]
 mod demuxer;
 mod descriptor;
 mod duration_fmt;
+mod dvb_subtitle;
+#[cfg(feature = "std")]
+mod epg;
+mod h264;
 mod header;
+#[cfg(feature = "std")]
+mod input;
 mod iso_639;
+mod iso_6937;
 mod packet;
 mod pcr;
 mod pes;
 mod pid;
+mod ps;
 mod rational;
+mod reader;
+mod remap;
+mod rtp;
+mod running_status;
 mod section;
+#[cfg(feature = "std")]
+mod service;
+mod service_type;
 mod stream_type;
 mod subtable_id;
+#[cfg(test)]
+mod synthetic;
 mod table_id;
+#[cfg(feature = "teletext")]
+mod teletext;
+#[cfg(feature = "tokio")]
+mod ts_stream;
+mod tuning;
+mod video_params;
 
 pub use annex_a2::AnnexA2;
-pub use demuxer::{Demuxer, DemuxerEvents, Packet as DemuxedPacket, Table as DemuxedTable};
-pub use descriptor::{DescDVB0x48, DescDVB0x4D, Tag, TagDVB};
-pub use duration_fmt::DurationFmt;
-pub use packet::Packet;
-pub use pes::PES;
+pub use audio_params::AudioParams;
+pub use cc_repair::CcRepair;
+pub use codec::Codec;
+#[cfg(feature = "std")]
+pub use demuxer::{
+    Demuxer, DemuxerEvents, Packet as DemuxedPacket, PidRole, PidStats, Severity, SignalingIssue,
+    Stats as DemuxerStats, Table as DemuxedTable, TableView, Trace,
+};
+pub use descriptor::{
+    Desc0x05, Desc0x06, DescDVB0x43, DescDVB0x44, DescDVB0x47, DescDVB0x48, DescDVB0x49,
+    DescDVB0x4D, DescDVB0x59, DescDVB0x5A, DescDVB0x66, Descriptor, ParsedDescriptor, Tag, TagDVB,
+};
+pub use duration_fmt::{DurationFmt, SignedDurationFmt};
+pub use dvb_subtitle::{
+    CLUTDefinition, DVBSubtitle, DisplayDefinition, ObjectData, PageComposition, PageState,
+    ParsedSegment, RegionComposition, Segment, SegmentType,
+};
+#[cfg(feature = "std")]
+pub use epg::{EpgEvent, EpgStore};
+pub use h264::{AccessUnit, AccessUnits, NALs, NAL};
+pub use header::{Adaptation, Header, TransportScramblingControl};
+#[cfg(feature = "std")]
+pub use input::InputStats;
+pub use iso_639::ISO639;
+pub use packet::{next_cc, Packet};
+pub use pes::{DSMTrickMode, StreamID, TrickModeControl, PES};
 pub use pid::PID;
+pub use ps::{PSDemuxer, PSDemuxerEvents, PackHeader};
+pub use remap::PidRemapper;
 pub use result::Result;
+pub use rtp::RTP;
+pub use running_status::RunningStatus;
 pub use section::Bufer;
-pub use section::{WithHeader, WithSyntaxSection, EIT, PAT, PMT, SDT};
-pub use stream_type::StreamType;
-pub use subtable_id::SubtableID;
+pub use section::{
+    DescriptorContext, EITEvent, EventInfo, PATProgram, PMTStream, SectionAssembler, ShortEvent,
+    WithCRC32, WithHeader, WithSyntaxSection, WithTableIDExtension, EIT, NIT, PAT, PMT, SDT,
+};
+#[cfg(feature = "std")]
+pub use service::{Service, ServiceCatalog, Stream as ServiceStream};
+pub use service_type::ServiceType;
+pub use stream_type::{StreamCategory, StreamType};
+pub use subtable_id::{SubtableID, SubtableKind};
 pub use table_id::TableID;
+#[cfg(feature = "teletext")]
+pub use teletext::{TeletextDecoder, TeletextLine, TeletextPage};
+#[cfg(feature = "tokio")]
+pub use ts_stream::{TsEvent, TsPes, TsStream};
+pub use tuning::{Cable, Delivery, Satellite, Terrestrial, TuningInfo};
+pub use video_params::VideoParams;
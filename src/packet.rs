@@ -1,16 +1,28 @@
 use crate::error::{Error, Kind as ErrorKind};
-use crate::header::{Adaptation, Header};
+use crate::header::{Adaptation, Header, TransportScramblingControl};
 use crate::pcr::PCR;
 use crate::pid::PID;
 use crate::result::Result;
 
+/// continuity-counter following current;
+/// 4-bit counter increments only for packets with payload
+#[inline(always)]
+pub fn next_cc(has_payload: bool, current: u8) -> u8 {
+    if has_payload {
+        (current + 1) & 0x0F
+    } else {
+        current & 0x0F
+    }
+}
+
+#[derive(Clone, Copy)]
 pub struct Packet<'buf> {
     buf: &'buf [u8],
 }
 
 impl<'buf> Packet<'buf> {
     pub const SZ: usize = 188;
-    const SYNC_BYTE: u8 = 0x47;
+    pub const SYNC_BYTE: u8 = 0x47;
 
     #[inline(always)]
     pub fn new(buf: &'buf [u8]) -> Result<Packet<'buf>> {
@@ -32,6 +44,13 @@ impl<'buf> Packet<'buf> {
         }
     }
 
+    /// whole 188-byte ts-packet as received;
+    /// forward untouched on pass-through remux
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &'buf [u8] {
+        self.buf
+    }
+
     /// adaptation start position
     #[inline(always)]
     fn buf_pos_adaptation() -> usize {
@@ -40,9 +59,10 @@ impl<'buf> Packet<'buf> {
 
     // TODO: try_seek?
     //       or pos_<name> + seek?
-    /// position payload start
+    /// position payload start;
+    /// section: pointer-field must point inside the packet
     #[inline(always)]
-    fn buf_pos_payload(&self, is_section: bool) -> usize {
+    fn buf_pos_payload(&self, is_section: bool) -> Result<usize> {
         let mut pos = Self::buf_pos_adaptation();
         let header = self.header();
 
@@ -66,10 +86,19 @@ impl<'buf> Packet<'buf> {
             // So the section start actually is an offset from the payload:
             //
             // uint8_t* section_start = payload + *payload + 1;
-            pos += (self.buf[pos] as usize) + 1;
+            let pointer_field = match self.buf.get(pos) {
+                Some(pointer_field) => usize::from(*pointer_field),
+                None => return Err(Error::new(ErrorKind::Buf(self.buf.len(), pos + 1))),
+            };
+
+            pos += pointer_field + 1;
+
+            if pos > Self::SZ {
+                return Err(Error::new(ErrorKind::Buf(self.buf.len(), pos)));
+            }
         }
 
-        pos
+        Ok(pos)
     }
 
     #[inline(always)]
@@ -93,7 +122,7 @@ impl<'buf> Packet<'buf> {
 
     #[inline(always)]
     fn buf_payload(&self, is_section: bool) -> Result<&'buf [u8]> {
-        self.buf_try_seek(self.buf_pos_payload(is_section))
+        self.buf_try_seek(self.buf_pos_payload(is_section)?)
     }
 
     #[inline(always)]
@@ -101,6 +130,23 @@ impl<'buf> Packet<'buf> {
         self.buf_payload(true)
     }
 
+    /// continuation of in-progress section:
+    /// PUSI - bytes between pointer-field and pointed section start,
+    /// otherwise - whole payload
+    #[inline(always)]
+    pub fn buf_section_tail(&self) -> Result<&'buf [u8]> {
+        let lft = self.buf_pos_payload(false)?;
+
+        if !self.pusi() {
+            return self.buf_try_seek(lft);
+        }
+
+        // skip pointer-field itself
+        let rght = self.buf_pos_payload(true)?;
+
+        Ok(&self.buf[lft + 1..rght])
+    }
+
     #[inline(always)]
     pub fn buf_payload_pes(&self) -> Result<&'buf [u8]> {
         self.buf_payload(false)
@@ -112,8 +158,9 @@ impl<'buf> Packet<'buf> {
         Header::new(self.buf)
     }
 
+    /// adaptation field if present
     #[inline(always)]
-    fn adaptation(&self) -> Option<Result<Adaptation<'buf>>> {
+    pub fn adaptation(&self) -> Option<Result<Adaptation<'buf>>> {
         let header = self.header();
 
         if header.got_adaptation() {
@@ -137,11 +184,55 @@ impl<'buf> Packet<'buf> {
         self.header().cc()
     }
 
+    /// payload present;
+    /// adaptation-only packets do not advance cc
+    #[inline(always)]
+    pub fn got_payload(&self) -> bool {
+        self.header().got_payload()
+    }
+
+    /// cc this packet must carry after previous packet of the same PID
+    #[inline(always)]
+    pub fn expected_cc(&self, prev: u8) -> u8 {
+        next_cc(self.got_payload(), prev)
+    }
+
+    /// duplicate packet (same cc with payload) is not an error
+    #[inline(always)]
+    pub fn validate_cc(&self, prev: u8) -> Result<()> {
+        let (cc, expected) = (self.cc(), self.expected_cc(prev));
+
+        if cc == expected || (self.got_payload() && cc == prev) {
+            Ok(())
+        } else {
+            Err(Error::new(ErrorKind::CC(cc, expected)))
+        }
+    }
+
     #[inline(always)]
     pub fn pusi(&self) -> bool {
         self.header().pusi()
     }
 
+    /// transport-error-indicator
+    #[inline(always)]
+    pub fn tei(&self) -> bool {
+        self.header().tei()
+    }
+
+    /// transport-scrambling-control
+    #[inline(always)]
+    pub fn tsc(&self) -> TransportScramblingControl {
+        self.header().tsc()
+    }
+
+    /// payload is scrambled;
+    /// adaptation-field is never scrambled
+    #[inline(always)]
+    pub fn is_scrambled(&self) -> bool {
+        self.tsc() != TransportScramblingControl::NotScrambled
+    }
+
     #[inline(always)]
     pub fn pcr(&self) -> Result<Option<PCR<'buf>>> {
         self.adaptation()
@@ -218,3 +309,110 @@ impl<'buf> Packet<'buf> {
         res.transpose()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{next_cc, Packet};
+    use crate::error::{Error, Kind as ErrorKind};
+    use crate::header::TransportScramblingControl;
+    use crate::synthetic::PacketBuilder;
+
+    #[test]
+    fn pointer_field_out_of_packet() {
+        // adaptation + payload, adaptation-field-length 183:
+        // no room for pointer-field
+        let mut raw = [0xFFu8; Packet::SZ];
+        raw[..6].copy_from_slice(&[0x47, 0x40, 0x00, 0x30, 183, 0x00]);
+        let pkt = Packet::new(&raw).unwrap();
+
+        assert_eq!(
+            pkt.buf_payload_section().err(),
+            Some(Error::new(ErrorKind::Buf(188, 189)))
+        );
+        assert_eq!(
+            pkt.buf_section_tail().err(),
+            Some(Error::new(ErrorKind::Buf(188, 189)))
+        );
+
+        // pointer-field beyond the remaining payload
+        let mut payload = [0x00; 184];
+        payload[0] = 184;
+        let raw = PacketBuilder::new(0x00)
+            .pusi(true)
+            .payload(&payload)
+            .build();
+        let pkt = Packet::new(&raw).unwrap();
+
+        assert_eq!(
+            pkt.buf_section_tail().err(),
+            Some(Error::new(ErrorKind::Buf(188, 189)))
+        );
+        assert!(pkt.buf_payload_section().is_err());
+
+        // pointing right to the packet end: tail only
+        payload[0] = 183;
+        let raw = PacketBuilder::new(0x00)
+            .pusi(true)
+            .payload(&payload)
+            .build();
+        let pkt = Packet::new(&raw).unwrap();
+
+        assert_eq!(pkt.buf_section_tail().unwrap().len(), 183);
+    }
+
+    #[test]
+    fn as_bytes() {
+        let raw = PacketBuilder::new(0x100)
+            .cc(7)
+            .payload(&[0xAA; 184])
+            .build();
+        let pkt = Packet::new(&raw).unwrap();
+
+        assert_eq!(pkt.as_bytes().len(), Packet::SZ);
+        assert_eq!(pkt.as_bytes(), &raw[..]);
+    }
+
+    #[test]
+    fn is_scrambled() {
+        let raw = PacketBuilder::new(0x100).payload(&[0x00]).build();
+        let pkt = Packet::new(&raw).unwrap();
+        assert!(!pkt.is_scrambled());
+
+        let raw = PacketBuilder::new(0x100).tsc(0b11).payload(&[0x00]).build();
+        let pkt = Packet::new(&raw).unwrap();
+        assert_eq!(pkt.tsc(), TransportScramblingControl::ScrambledOdd);
+        assert!(pkt.is_scrambled());
+    }
+
+    #[test]
+    fn next_cc_wraps() {
+        assert_eq!(next_cc(true, 0), 1);
+        assert_eq!(next_cc(true, 15), 0);
+    }
+
+    #[test]
+    fn next_cc_no_payload() {
+        assert_eq!(next_cc(false, 7), 7);
+        assert_eq!(next_cc(false, 15), 15);
+    }
+
+    #[test]
+    fn validate_cc() {
+        let raw = PacketBuilder::new(0x100).cc(3).payload(&[0x00]).build();
+        let pkt = Packet::new(&raw).unwrap();
+
+        assert_eq!(pkt.expected_cc(2), 3);
+        assert_eq!(pkt.validate_cc(2), Ok(()));
+        // duplicate
+        assert_eq!(pkt.validate_cc(3), Ok(()));
+        assert_eq!(pkt.validate_cc(1), Err(Error::new(ErrorKind::CC(3, 2))));
+
+        // adaptation-only
+        let raw = PacketBuilder::new(0x100).cc(3).adaptation(&[0x00]).build();
+        let pkt = Packet::new(&raw).unwrap();
+
+        assert_eq!(pkt.expected_cc(3), 3);
+        assert_eq!(pkt.validate_cc(3), Ok(()));
+        assert_eq!(pkt.validate_cc(2), Err(Error::new(ErrorKind::CC(3, 2))));
+    }
+}
@@ -1,21 +1,27 @@
-use std::fmt;
-use std::time::Duration;
+use core::fmt;
+use core::time::Duration;
 
 use crate::duration_fmt::DurationFmt;
 use crate::error::{Error, Kind as ErrorKind};
 use crate::rational;
 use crate::rational::Rational;
+use crate::reader::be_u16;
 use crate::result::Result;
 
 /// Program clock reference,
 /// stored as 33 bits base, 6 bits reserved, 9 bits extension.
 /// The value is calculated as base * 300 + extension.
+#[derive(Clone, Copy)]
 pub struct PCR<'buf> {
     buf: &'buf [u8],
 }
 
 impl<'buf> PCR<'buf> {
     pub const SZ: usize = 6;
+    /// clock frequency
+    pub const HZ: u64 = 27_000_000;
+    /// 27MHz wraparound; 33 bits base * 300
+    pub const MAX: u64 = (1 << 33) * 300;
     const TB: Rational = rational::TB_27MHZ;
 
     #[inline(always)]
@@ -51,7 +57,7 @@ impl<'buf> PCR<'buf> {
 
     #[inline(always)]
     fn ext(&self) -> u16 {
-        (u16::from(self.buf[4] & 0b0000_00001) << 8) | u16::from(self.buf[5])
+        be_u16(&self.buf[4..]) & 0x01FF
     }
 
     /// 27MHz
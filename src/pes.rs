@@ -1,27 +1,30 @@
-use std::fmt;
-use std::time::Duration;
+use core::fmt;
+use core::time::Duration;
 
 use crate::duration_fmt::DurationFmt;
 use crate::error::{Error, Kind as ErrorKind};
 use crate::rational;
 use crate::rational::Rational;
+use crate::reader::{be_u16, be_u24};
 use crate::result::Result;
 
 /// ISO/IEC 13818-1
+#[derive(Clone, Copy)]
 pub struct Timestamp<'buf> {
     buf: &'buf [u8],
 }
 
 impl<'buf> Timestamp<'buf> {
-    const SZ: usize = 5;
+    pub(crate) const SZ: usize = 5;
     const TB: Rational = rational::TB_90KHZ;
 
     #[inline(always)]
-    fn new(buf: &'buf [u8]) -> Timestamp<'buf> {
+    pub(crate) fn new(buf: &'buf [u8]) -> Timestamp<'buf> {
         Timestamp { buf }
     }
 
-    /// 90kHz
+    /// 90kHz, 33 bits;
+    /// [32..30] are bits 3..1 of the first byte
     pub fn value(&self) -> u64 {
         ((u64::from(self.buf[0]) & 0b0000_1110) << 29) // (>> 1 (<< 30))
             | (u64::from(self.buf[1]) << 22)
@@ -72,8 +75,24 @@ impl<'buf> fmt::Display for Timestamp<'buf> {
     }
 }
 
+/// 6-byte ESCR / MPEG-2 pack SCR (27MHz):
+/// base (33 bits, 90kHz) * 300 + extension (9 bits)
+#[inline(always)]
+pub(crate) fn system_clock_reference(b: &[u8]) -> u64 {
+    let base = (u64::from(b[0] & 0b0011_1000) << 27)
+        | (u64::from(b[0] & 0b0000_0011) << 28)
+        | (u64::from(b[1]) << 20)
+        | (u64::from(b[2] & 0b1111_1000) << 12)
+        | (u64::from(b[2] & 0b0000_0011) << 13)
+        | (u64::from(b[3]) << 5)
+        | (u64::from(b[4] & 0b1111_1000) >> 3);
+    let ext = (u64::from(b[4] & 0b0000_0011) << 7) | (u64::from(b[5]) >> 1);
+
+    base * 300 + ext
+}
+
 /// ISO/IEC 13818-1
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum StreamID {
     ProgramStreamMap,
     PrivateStream1,
@@ -144,6 +163,25 @@ impl StreamID {
     pub fn is3(self) -> bool {
         self == StreamID::PaddingStream
     }
+
+    /// 0xC0...0xDF
+    #[inline(always)]
+    pub fn is_audio(self) -> bool {
+        matches!(self, StreamID::AudioStreamNumber(..))
+    }
+
+    /// 0xE0...0xEF
+    #[inline(always)]
+    pub fn is_video(self) -> bool {
+        matches!(self, StreamID::VideoStreamNumber(..))
+    }
+
+    /// private_stream_1;
+    /// AC-3, DVB subtitles, teletext
+    #[inline(always)]
+    pub fn is_private_1(self) -> bool {
+        self == StreamID::PrivateStream1
+    }
 }
 
 impl From<u8> for StreamID {
@@ -207,7 +245,7 @@ impl From<StreamID> for u8 {
 }
 
 /// ISO/IEC 13818-1
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum ScramblingControl {
     NotScrabled,
     UserDefined(u8),
@@ -224,7 +262,7 @@ impl From<u8> for ScramblingControl {
 }
 
 /// ISO/IEC 13818-1
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum PtsDtsFlag {
     No,
     Pts,
@@ -245,9 +283,104 @@ impl From<u8> for PtsDtsFlag {
     }
 }
 
+/// ISO/IEC 13818-1
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TrickModeControl {
+    FastForward,
+    SlowMotion,
+    FreezeFrame,
+    FastReverse,
+    SlowReverse,
+    Reserved(u8),
+}
+
+impl From<u8> for TrickModeControl {
+    #[inline(always)]
+    fn from(d: u8) -> Self {
+        match d {
+            0b000 => TrickModeControl::FastForward,
+            0b001 => TrickModeControl::SlowMotion,
+            0b010 => TrickModeControl::FreezeFrame,
+            0b011 => TrickModeControl::FastReverse,
+            0b100 => TrickModeControl::SlowReverse,
+            _ => TrickModeControl::Reserved(d),
+        }
+    }
+}
+
+/// ISO/IEC 13818-1
+///
+/// DSM trick mode byte
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DSMTrickMode(u8);
+
+impl DSMTrickMode {
+    #[inline(always)]
+    pub fn control(&self) -> TrickModeControl {
+        TrickModeControl::from(self.0 >> 5)
+    }
+
+    #[inline(always)]
+    fn is_fast(&self) -> bool {
+        matches!(
+            self.control(),
+            TrickModeControl::FastForward | TrickModeControl::FastReverse
+        )
+    }
+
+    #[inline(always)]
+    fn is_slow(&self) -> bool {
+        matches!(
+            self.control(),
+            TrickModeControl::SlowMotion | TrickModeControl::SlowReverse
+        )
+    }
+
+    /// fast forward/reverse, freeze frame
+    #[inline(always)]
+    pub fn field_id(&self) -> Option<u8> {
+        if self.is_fast() || self.control() == TrickModeControl::FreezeFrame {
+            Some((self.0 & 0b0001_1000) >> 3)
+        } else {
+            None
+        }
+    }
+
+    /// fast forward/reverse
+    #[inline(always)]
+    pub fn intra_slice_refresh(&self) -> Option<bool> {
+        if self.is_fast() {
+            Some((self.0 & 0b0000_0100) != 0)
+        } else {
+            None
+        }
+    }
+
+    /// fast forward/reverse
+    #[inline(always)]
+    pub fn frequency_truncation(&self) -> Option<u8> {
+        if self.is_fast() {
+            Some(self.0 & 0b0000_0011)
+        } else {
+            None
+        }
+    }
+
+    /// slow motion/reverse
+    #[inline(always)]
+    pub fn rep_cntrl(&self) -> Option<u8> {
+        if self.is_slow() {
+            Some(self.0 & 0b0001_1111)
+        } else {
+            None
+        }
+    }
+}
+
 /// ISO/IEC 13818-1
 ///
 /// http://dvd.sourceforge.net/dvdinfo/pes-hdr.html
+#[derive(Clone, Copy)]
 pub struct PES<'buf> {
     buf: &'buf [u8],
 }
@@ -256,10 +389,11 @@ impl<'buf> PES<'buf> {
     const HEADER_SZ: usize = 6;
     const HEADER_SZ_1: usize = 3;
     const START_CODE: u32 = 0x0000_0001;
-    const PTS_OFFSET_LFT: usize = 9;
-    const PTS_OFFSET_RGHT: usize = Self::PTS_OFFSET_LFT + Timestamp::SZ;
-    const DTS_OFFSET_LFT: usize = Self::PTS_OFFSET_RGHT;
-    const DTS_OFFSET_RGHT: usize = Self::DTS_OFFSET_LFT + Timestamp::SZ;
+    const ESCR_SZ: usize = 6;
+    const ES_RATE_SZ: usize = 3;
+    const DSM_TRICK_MODE_SZ: usize = 1;
+    const ADDITIONAL_COPY_INFO_SZ: usize = 1;
+    const PES_CRC_SZ: usize = 2;
 
     #[inline(always)]
     pub fn new(buf: &'buf [u8]) -> PES<'buf> {
@@ -275,12 +409,16 @@ impl<'buf> PES<'buf> {
 
     #[inline(always)]
     pub fn validate(&self) -> Result<()> {
-        let sz1 = || PES::HEADER_SZ + PES::HEADER_SZ_1 + (self.pes_header_data_length() as usize);
+        let sz0 = PES::HEADER_SZ + PES::HEADER_SZ_1;
+        let sz1 = || sz0 + self.pes_header_data_length();
 
         if self.buf.len() < Self::HEADER_SZ {
             Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)))
         } else if self.start_code() != Self::START_CODE {
             Err(Error::new(ErrorKind::PESStartCode(self.start_code())))
+        } else if self.stream_id().is1() && self.buf.len() < sz0 {
+            // flags and PES-header-data-length
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), sz0)))
         } else if self.stream_id().is1() && self.buf.len() < sz1() {
             Err(Error::new(ErrorKind::Buf(self.buf.len(), sz1())))
         } else {
@@ -290,18 +428,18 @@ impl<'buf> PES<'buf> {
 
     #[inline(always)]
     fn start_code(&self) -> u32 {
-        (u32::from(self.buf[0]) << 16) | (u32::from(self.buf[1]) << 8) | u32::from(self.buf[2])
+        be_u24(self.buf)
     }
 
     #[inline(always)]
-    fn stream_id(&self) -> StreamID {
+    pub fn stream_id(&self) -> StreamID {
         StreamID::from(self.buf[3])
     }
 
     #[inline(always)]
     #[allow(dead_code)]
     fn packet_length(&self) -> u16 {
-        u16::from(self.buf[4]) << 8 | u16::from(self.buf[5])
+        be_u16(&self.buf[4..])
     }
 
     #[inline(always)]
@@ -313,38 +451,218 @@ impl<'buf> PES<'buf> {
         }
     }
 
+    /// PES-scrambling-control;
+    /// stream-id without PES header flags (padding, private-2, ...) is not scrambled
+    #[inline(always)]
+    pub fn scrambling_control(&self) -> ScramblingControl {
+        if self.stream_id().is1() {
+            ScramblingControl::from((self.buf[6] & 0b0011_0000) >> 4)
+        } else {
+            ScramblingControl::NotScrabled
+        }
+    }
+
+    /// payload is scrambled on PES level;
+    /// elementary stream must not be parsed
+    #[inline(always)]
+    pub fn is_scrambled(&self) -> bool {
+        self.scrambling_control() != ScramblingControl::NotScrabled
+    }
+
+    /// optional field flag from flags byte
+    #[inline(always)]
+    fn flag(&self, mask: u8) -> bool {
+        self.stream_id().is1() && (self.buf[7] & mask) != 0
+    }
+
+    #[inline(always)]
+    pub fn escr_flag(&self) -> bool {
+        self.flag(0b0010_0000)
+    }
+
+    #[inline(always)]
+    pub fn es_rate_flag(&self) -> bool {
+        self.flag(0b0001_0000)
+    }
+
+    #[inline(always)]
+    pub fn dsm_trick_mode_flag(&self) -> bool {
+        self.flag(0b0000_1000)
+    }
+
+    #[inline(always)]
+    pub fn additional_copy_info_flag(&self) -> bool {
+        self.flag(0b0000_0100)
+    }
+
+    #[inline(always)]
+    pub fn pes_crc_flag(&self) -> bool {
+        self.flag(0b0000_0010)
+    }
+
+    #[inline(always)]
+    pub fn pes_extension_flag(&self) -> bool {
+        self.flag(0b0000_0001)
+    }
+
     #[inline(always)]
     fn pes_header_data_length(&self) -> usize {
         usize::from(self.buf[8])
     }
 
+    /// optional fields start position
+    #[inline(always)]
+    fn buf_pos_pts(&self) -> usize {
+        Self::HEADER_SZ + Self::HEADER_SZ_1
+    }
+
+    #[inline(always)]
+    fn buf_pos_escr(&self) -> usize {
+        self.buf_pos_pts()
+            + match self.pts_dts_flag() {
+                Some(PtsDtsFlag::Pts) => Timestamp::SZ,
+                Some(PtsDtsFlag::PtsDts) => 2 * Timestamp::SZ,
+                _ => 0,
+            }
+    }
+
+    #[inline(always)]
+    fn buf_pos_es_rate(&self) -> usize {
+        self.buf_pos_escr() + if self.escr_flag() { Self::ESCR_SZ } else { 0 }
+    }
+
+    #[inline(always)]
+    fn buf_pos_dsm_trick_mode(&self) -> usize {
+        self.buf_pos_es_rate()
+            + if self.es_rate_flag() {
+                Self::ES_RATE_SZ
+            } else {
+                0
+            }
+    }
+
+    #[inline(always)]
+    fn buf_pos_additional_copy_info(&self) -> usize {
+        self.buf_pos_dsm_trick_mode()
+            + if self.dsm_trick_mode_flag() {
+                Self::DSM_TRICK_MODE_SZ
+            } else {
+                0
+            }
+    }
+
+    #[inline(always)]
+    fn buf_pos_pes_crc(&self) -> usize {
+        self.buf_pos_additional_copy_info()
+            + if self.additional_copy_info_flag() {
+                Self::ADDITIONAL_COPY_INFO_SZ
+            } else {
+                0
+            }
+    }
+
+    /// optional field bounded by PES header data
     #[inline(always)]
-    pub fn pts(&self) -> Option<Timestamp> {
-        self.pts_dts_flag().and_then(|flag| match flag {
-            PtsDtsFlag::Pts | PtsDtsFlag::PtsDts => Some(Timestamp::new(
-                &self.buf[Self::PTS_OFFSET_LFT..Self::PTS_OFFSET_RGHT],
-            )),
+    fn buf_optional(&self, pos: usize, sz: usize) -> Option<&'buf [u8]> {
+        let rght = (self.buf_pos_pts() + self.pes_header_data_length()).min(self.buf.len());
+
+        if pos + sz <= rght {
+            Some(&self.buf[pos..pos + sz])
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    pub fn pts(&self) -> Option<Timestamp<'buf>> {
+        match self.pts_dts_flag() {
+            Some(PtsDtsFlag::Pts) | Some(PtsDtsFlag::PtsDts) => self
+                .buf_optional(self.buf_pos_pts(), Timestamp::SZ)
+                .map(Timestamp::new),
             _ => None,
-        })
+        }
     }
 
     #[inline(always)]
-    pub fn dts(&self) -> Option<Timestamp> {
-        self.pts_dts_flag().and_then(|flag| match flag {
-            PtsDtsFlag::PtsDts => Some(Timestamp::new(
-                &self.buf[Self::DTS_OFFSET_LFT..Self::DTS_OFFSET_RGHT],
-            )),
+    pub fn dts(&self) -> Option<Timestamp<'buf>> {
+        match self.pts_dts_flag() {
+            Some(PtsDtsFlag::PtsDts) => self
+                .buf_optional(self.buf_pos_pts() + Timestamp::SZ, Timestamp::SZ)
+                .map(Timestamp::new),
             _ => None,
-        })
+        }
+    }
+
+    /// elementary stream clock reference; 27MHz
+    #[inline(always)]
+    pub fn escr(&self) -> Option<u64> {
+        if !self.escr_flag() {
+            return None;
+        }
+
+        self.buf_optional(self.buf_pos_escr(), Self::ESCR_SZ)
+            .map(system_clock_reference)
+    }
+
+    /// units of 50 bytes/second
+    #[inline(always)]
+    pub fn es_rate(&self) -> Option<u32> {
+        if !self.es_rate_flag() {
+            return None;
+        }
+
+        self.buf_optional(self.buf_pos_es_rate(), Self::ES_RATE_SZ)
+            .map(|b| {
+                (u32::from(b[0] & 0b0111_1111) << 15)
+                    | (u32::from(b[1]) << 7)
+                    | (u32::from(b[2]) >> 1)
+            })
+    }
+
+    #[inline(always)]
+    pub fn dsm_trick_mode(&self) -> Option<DSMTrickMode> {
+        if !self.dsm_trick_mode_flag() {
+            return None;
+        }
+
+        self.buf_optional(self.buf_pos_dsm_trick_mode(), Self::DSM_TRICK_MODE_SZ)
+            .map(|b| DSMTrickMode(b[0]))
+    }
+
+    #[inline(always)]
+    pub fn additional_copy_info(&self) -> Option<u8> {
+        if !self.additional_copy_info_flag() {
+            return None;
+        }
+
+        self.buf_optional(
+            self.buf_pos_additional_copy_info(),
+            Self::ADDITIONAL_COPY_INFO_SZ,
+        )
+        .map(|b| b[0] & 0b0111_1111)
+    }
+
+    /// previous PES packet CRC
+    #[inline(always)]
+    pub fn pes_crc(&self) -> Option<u16> {
+        if !self.pes_crc_flag() {
+            return None;
+        }
+
+        self.buf_optional(self.buf_pos_pes_crc(), Self::PES_CRC_SZ)
+            .map(be_u16)
     }
 
+    /// skip PES header and optional fields
     #[inline(always)]
     pub fn buf_seek_payload(&self) -> &'buf [u8] {
-        if self.stream_id().is1() {
-            &self.buf[(Self::HEADER_SZ + Self::HEADER_SZ_1)..]
+        let pos = if self.stream_id().is1() {
+            self.buf_pos_pts() + self.pes_header_data_length()
         } else {
-            &self.buf[Self::HEADER_SZ..]
-        }
+            Self::HEADER_SZ
+        };
+
+        &self.buf[pos.min(self.buf.len())..]
     }
 }
 
@@ -363,3 +681,186 @@ impl<'buf> fmt::Debug for PES<'buf> {
         write!(f, ")")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{ScramblingControl, Timestamp, TrickModeControl, PES};
+    use crate::error::{Error, Kind as ErrorKind};
+
+    #[rustfmt::skip]
+    const RAW: [u8; 29] = [
+        0x00, 0x00, 0x01, 0xE0, 0x00, 0x00,
+        // flags: PTS, ESCR, ES-rate, DSM trick mode, copy info, CRC
+        0x80, 0b1011_1110, 18,
+        // PTS 1
+        0x21, 0x00, 0x01, 0x00, 0x03,
+        // ESCR base 2 ext 5
+        0xC4, 0x00, 0x04, 0x00, 0x14, 0x0B,
+        // ES-rate 1000
+        0x80, 0x07, 0xD1,
+        // fast-forward, field-id 1, intra-slice-refresh, frequency-truncation 2
+        0b0000_1110,
+        // additional copy info
+        0x80 | 0x2A,
+        // previous PES CRC
+        0xBE, 0xEF,
+        // payload
+        0xAA, 0xBB,
+    ];
+
+    #[test]
+    fn timestamp_33_bits() {
+        // 2^33 - 1
+        let max = Timestamp::new(&[0x2F, 0xFF, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(max.value(), 8_589_934_591);
+        assert_eq!(Duration::from(&max).as_secs(), 95_443);
+
+        // PTS[32] only
+        let top = Timestamp::new(&[0x29, 0x00, 0x01, 0x00, 0x01]);
+        assert_eq!(top.value(), 1 << 32);
+
+        // PTS[30] only
+        let low = Timestamp::new(&[0x23, 0x00, 0x01, 0x00, 0x01]);
+        assert_eq!(low.value(), 1 << 30);
+    }
+
+    #[test]
+    fn optional_fields() {
+        let pes = PES::try_new(&RAW).unwrap();
+
+        assert_eq!(pes.pts().map(|t| t.value()), Some(1));
+        assert!(pes.dts().is_none());
+        assert_eq!(pes.escr(), Some(2 * 300 + 5));
+        assert_eq!(pes.es_rate(), Some(1000));
+
+        let trick = pes.dsm_trick_mode().unwrap();
+        assert_eq!(trick.control(), TrickModeControl::FastForward);
+        assert_eq!(trick.field_id(), Some(1));
+        assert_eq!(trick.intra_slice_refresh(), Some(true));
+        assert_eq!(trick.frequency_truncation(), Some(2));
+        assert_eq!(trick.rep_cntrl(), None);
+
+        assert_eq!(pes.additional_copy_info(), Some(0x2A));
+        assert_eq!(pes.pes_crc(), Some(0xBEEF));
+        assert!(!pes.pes_extension_flag());
+
+        assert_eq!(pes.buf_seek_payload(), &[0xAA, 0xBB]);
+    }
+
+    #[test]
+    fn header_truncated() {
+        // flags byte / PES-header-data-length are not there yet
+        for sz in [4, 7, 8] {
+            assert!(PES::try_new(&RAW[..sz]).is_err());
+        }
+        assert_eq!(
+            PES::try_new(&RAW[..8]).err(),
+            Some(Error::new(ErrorKind::Buf(8, 9)))
+        );
+        // optional fields are cut
+        assert_eq!(
+            PES::try_new(&RAW[..20]).err(),
+            Some(Error::new(ErrorKind::Buf(20, 27)))
+        );
+    }
+
+    #[test]
+    fn optional_fields_absent() {
+        let mut raw = RAW;
+        raw[7] = 0x00;
+        raw[8] = 0x00;
+        let pes = PES::try_new(&raw[..9]).unwrap();
+
+        assert!(pes.pts().is_none());
+        assert!(pes.escr().is_none());
+        assert!(pes.es_rate().is_none());
+        assert!(pes.dsm_trick_mode().is_none());
+        assert!(pes.additional_copy_info().is_none());
+        assert!(pes.pes_crc().is_none());
+        assert!(pes.buf_seek_payload().is_empty());
+    }
+
+    #[test]
+    fn scrambling_control() {
+        let mut raw = RAW;
+        let pes = PES::try_new(&raw).unwrap();
+
+        assert_eq!(pes.scrambling_control(), ScramblingControl::NotScrabled);
+        assert!(!pes.is_scrambled());
+
+        // '10' marker + scrambling-control 0b11
+        raw[6] = 0x80 | 0b0011_0000;
+        let pes = PES::try_new(&raw).unwrap();
+
+        assert_eq!(pes.scrambling_control(), ScramblingControl::UserDefined(3));
+        assert!(pes.is_scrambled());
+        assert_eq!(pes.pts().map(|t| t.value()), Some(1));
+
+        // padding stream: no PES header flags
+        raw[3] = 0xBE;
+        assert!(!PES::new(&raw).is_scrambled());
+    }
+
+    #[test]
+    fn pts_only() {
+        #[rustfmt::skip]
+        let raw = [
+            0x00, 0x00, 0x01, 0xE0, 0x00, 0x00, 0x84, 0x80, 0x05,
+            // PTS 126000 (1.4s)
+            0x21, 0x00, 0x07, 0xD8, 0x61,
+            // payload looks like timestamp
+            0x11, 0x00, 0x05, 0xBF, 0x21,
+        ];
+        let pes = PES::try_new(&raw).unwrap();
+
+        assert_eq!(pes.pts().map(|t| t.value()), Some(126_000));
+        assert!(pes.dts().is_none());
+        assert_eq!(pes.buf_seek_payload(), &raw[14..]);
+    }
+
+    #[test]
+    fn pts_dts() {
+        #[rustfmt::skip]
+        let raw = [
+            0x00, 0x00, 0x01, 0xE0, 0x00, 0x00, 0x84, 0xC0, 0x0A,
+            // PTS 128832
+            0x31, 0x00, 0x07, 0xEE, 0x81,
+            // DTS 90000 (1s)
+            0x11, 0x00, 0x05, 0xBF, 0x21,
+        ];
+        let pes = PES::try_new(&raw).unwrap();
+
+        assert_eq!(pes.pts().map(|t| t.value()), Some(128_832));
+        assert_eq!(pes.dts().map(|t| t.value()), Some(90_000));
+        assert!(pes.buf_seek_payload().is_empty());
+    }
+
+    #[test]
+    fn pts_dts_forbidden() {
+        #[rustfmt::skip]
+        let raw = [
+            0x00, 0x00, 0x01, 0xE0, 0x00, 0x00, 0x84, 0x40, 0x05,
+            0x21, 0x00, 0x07, 0xD8, 0x61,
+        ];
+        let pes = PES::try_new(&raw).unwrap();
+
+        assert!(pes.pts().is_none());
+        assert!(pes.dts().is_none());
+    }
+
+    #[test]
+    fn pts_truncated_header() {
+        // PTS flagged, but PES header data length is too short
+        #[rustfmt::skip]
+        let raw = [
+            0x00, 0x00, 0x01, 0xE0, 0x00, 0x00, 0x84, 0xC0, 0x05,
+            0x21, 0x00, 0x07, 0xD8, 0x61,
+        ];
+        let pes = PES::try_new(&raw).unwrap();
+
+        assert!(pes.pts().is_some());
+        assert!(pes.dts().is_none());
+    }
+}
@@ -1,3 +1,5 @@
+use core::fmt;
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum PID {
     PAT,
@@ -36,25 +38,52 @@ pub enum PID {
 impl PID {
     #[inline(always)]
     pub fn is_section(self) -> bool {
-        match self {
-            PID::Other(..) | PID::NULL | PID::Reserved(..) => false,
-            _ => true,
-        }
+        !matches!(self, PID::Other(..) | PID::NULL | PID::Reserved(..))
     }
 
     #[inline(always)]
     pub fn is_null(self) -> bool {
-        match self {
-            PID::NULL => true,
-            _ => false,
-        }
+        matches!(self, PID::NULL)
     }
 
     #[inline(always)]
     pub fn is_other(self) -> bool {
-        match self {
-            PID::Other(..) => true,
-            _ => false,
+        matches!(self, PID::Other(..))
+    }
+
+    /// well-known PID name;
+    /// None for PMT/elementary/private PIDs
+    pub fn name(self) -> Option<&'static str> {
+        Some(match self {
+            PID::PAT => "PAT",
+            PID::CAT => "CAT",
+            PID::TSDT => "TSDT",
+            PID::NIT => "NIT",
+            PID::SDT => "SDT",
+            PID::EIT => "EIT",
+            PID::RST => "RST",
+            PID::TDT => "TDT",
+            PID::NetworkSynchronization => "network-synchronization",
+            PID::RNT => "RNT",
+            PID::InbandSignalling => "inband-signalling",
+            PID::Measurement => "measurement",
+            PID::DIT => "DIT",
+            PID::SIT => "SIT",
+            PID::NULL => "NULL",
+            PID::Reserved(..) => "reserved",
+            PID::Other(..) => return None,
+        })
+    }
+}
+
+/// "PAT (0x0000)", "0x0100"
+impl fmt::Display for PID {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let d = u16::from(*self);
+
+        match self.name() {
+            Some(name) => write!(f, "{} (0x{:04X})", name, d),
+            None => write!(f, "0x{:04X}", d),
         }
     }
 }
@@ -112,3 +141,23 @@ impl From<PID> for u16 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PID;
+
+    #[test]
+    fn display() {
+        assert_eq!(format!("{}", PID::PAT), "PAT (0x0000)");
+        assert_eq!(format!("{}", PID::EIT), "EIT (0x0012)");
+        assert_eq!(format!("{}", PID::NULL), "NULL (0x1FFF)");
+        assert_eq!(format!("{}", PID::from(0x0005)), "reserved (0x0005)");
+        assert_eq!(format!("{}", PID::from(0x0100)), "0x0100");
+    }
+
+    #[test]
+    fn name() {
+        assert_eq!(PID::SDT.name(), Some("SDT"));
+        assert_eq!(PID::Other(0x0100).name(), None);
+    }
+}
@@ -0,0 +1,359 @@
+//! ISO/IEC 13818-1 (2.5) program stream;
+//! pack/system header framing around the PES layer
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::pes::{system_clock_reference, StreamID, Timestamp, PES};
+use crate::reader::{be_u16, be_u24};
+use crate::result::Result;
+
+const START_CODE_PREFIX: [u8; 3] = [0x00, 0x00, 0x01];
+
+/// pack_start_code 0x000001BA
+#[derive(Clone, Copy)]
+pub struct PackHeader<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> PackHeader<'buf> {
+    const HEADER_SZ_MPEG1: usize = 12;
+    const HEADER_SZ_MPEG2: usize = 14;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> PackHeader<'buf> {
+        PackHeader { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<PackHeader<'buf>> {
+        let p = PackHeader::new(buf);
+        p.validate()?;
+        Ok(p)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < 5 {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), 5)))
+        } else if self.buf.len() < self.sz() {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), self.sz())))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// ISO/IEC 11172-1 pack ('0010' marker)
+    #[inline(always)]
+    pub fn is_mpeg1(&self) -> bool {
+        (self.buf[4] & 0b1111_0000) == 0b0010_0000
+    }
+
+    /// header with stuffing bytes
+    #[inline(always)]
+    pub fn sz(&self) -> usize {
+        if self.is_mpeg1() {
+            Self::HEADER_SZ_MPEG1
+        } else if self.buf.len() < Self::HEADER_SZ_MPEG2 {
+            Self::HEADER_SZ_MPEG2
+        } else {
+            Self::HEADER_SZ_MPEG2 + usize::from(self.buf[13] & 0b0000_0111)
+        }
+    }
+
+    /// system-clock-reference (27MHz)
+    #[inline(always)]
+    pub fn scr(&self) -> u64 {
+        if self.is_mpeg1() {
+            Timestamp::new(&self.buf[4..]).value() * 300
+        } else {
+            system_clock_reference(&self.buf[4..])
+        }
+    }
+
+    /// units of 50 bytes/second
+    #[inline(always)]
+    pub fn program_mux_rate(&self) -> u32 {
+        if self.is_mpeg1() {
+            (be_u24(&self.buf[9..]) >> 1) & 0x3F_FFFF
+        } else {
+            be_u24(&self.buf[10..]) >> 2
+        }
+    }
+}
+
+impl<'buf> fmt::Debug for PackHeader<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ":pack (:scr {} :mux-rate {} :mpeg1 {})",
+            self.scr(),
+            self.program_mux_rate(),
+            self.is_mpeg1(),
+        )
+    }
+}
+
+pub trait PSDemuxerEvents {
+    fn on_pack(&mut self, _: &PackHeader) {}
+    /// complete PES packet (padding stream is skipped);
+    /// MPEG-2 PES syntax
+    fn on_pes(&mut self, _: &PES) {}
+    /// program_end_code
+    fn on_end(&mut self) {}
+}
+
+/// program stream demuxer;
+/// chunks need not be aligned to packs or PES packets
+pub struct PSDemuxer<T>
+where
+    T: PSDemuxerEvents,
+{
+    /// unconsumed tail of previous chunk
+    buf: Vec<u8>,
+
+    /// bytes skipped while looking for start code
+    skipped: u64,
+
+    events: T,
+}
+
+impl<T> PSDemuxer<T>
+where
+    T: PSDemuxerEvents,
+{
+    const CODE_END: u8 = 0xB9;
+    const CODE_PACK: u8 = 0xBA;
+    const CODE_SYSTEM_HEADER: u8 = 0xBB;
+
+    /// start code with 16-bit length
+    const HEADER_SZ: usize = 6;
+
+    pub fn new(events: T) -> PSDemuxer<T> {
+        PSDemuxer {
+            buf: Vec::new(),
+            skipped: 0,
+            events,
+        }
+    }
+
+    #[inline(always)]
+    pub fn events(&self) -> &T {
+        &self.events
+    }
+
+    #[inline(always)]
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// (start code, unit sz);
+    /// None start code - garbage to skip;
+    /// None - more data required
+    #[inline(always)]
+    fn unit(buf: &[u8]) -> Option<(Option<u8>, usize)> {
+        if buf.len() < 4 {
+            return None;
+        }
+
+        if buf[..3] != START_CODE_PREFIX {
+            // resync; keep possible prefix head
+            let pos = buf
+                .windows(3)
+                .position(|w| w == START_CODE_PREFIX)
+                .unwrap_or(buf.len() - 2);
+            return Some((None, pos.max(1)));
+        }
+
+        match buf[3] {
+            Self::CODE_END => Some((Some(Self::CODE_END), 4)),
+            Self::CODE_PACK => {
+                // header or stuffing may be not complete yet
+                let pack = PackHeader::try_new(buf).ok()?;
+                Some((Some(Self::CODE_PACK), pack.sz()))
+            }
+            code if code >= Self::CODE_SYSTEM_HEADER => {
+                if buf.len() < Self::HEADER_SZ {
+                    return None;
+                }
+
+                let sz = Self::HEADER_SZ + usize::from(be_u16(&buf[4..]));
+                Some((Some(code), sz))
+            }
+            // not a program stream start code
+            _ => Some((None, 3)),
+        }
+    }
+
+    /// demux chunk;
+    /// malformed PES does not stop the rest of chunk,
+    /// the first error is returned
+    pub fn push(&mut self, chunk: &[u8]) -> Result<()> {
+        let PSDemuxer {
+            buf,
+            skipped,
+            events,
+        } = self;
+
+        buf.extend_from_slice(chunk);
+
+        let mut res = Ok(());
+        let mut pos = 0;
+
+        while let Some((code, sz)) = Self::unit(&buf[pos..]) {
+            if pos + sz > buf.len() {
+                break;
+            }
+
+            let raw = &buf[pos..pos + sz];
+            pos += sz;
+
+            match code {
+                None => *skipped += sz as u64,
+                Some(Self::CODE_END) => events.on_end(),
+                Some(Self::CODE_PACK) => events.on_pack(&PackHeader::new(raw)),
+                Some(Self::CODE_SYSTEM_HEADER) => {}
+                Some(_) => match PES::try_new(raw) {
+                    Ok(pes) if pes.stream_id() == StreamID::PaddingStream => {}
+                    Ok(pes) => events.on_pes(&pes),
+                    Err(err) => {
+                        if res.is_ok() {
+                            res = Err(err);
+                        }
+                    }
+                },
+            }
+        }
+
+        buf.drain(..pos);
+
+        res
+    }
+
+    /// end of input;
+    /// return: trailing bytes of incomplete unit
+    pub fn finish(&mut self) -> usize {
+        let tail = self.buf.len();
+        self.buf.clear();
+        tail
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{PSDemuxer, PSDemuxerEvents, PackHeader};
+    use crate::pes::{StreamID, PES};
+    use crate::synthetic;
+
+    #[derive(Default)]
+    struct Events {
+        scrs: Vec<u64>,
+        pes: Vec<(StreamID, Option<Duration>, Vec<u8>)>,
+        end: bool,
+    }
+
+    impl PSDemuxerEvents for Events {
+        fn on_pack(&mut self, pack: &PackHeader) {
+            self.scrs.push(pack.scr());
+        }
+
+        fn on_pes(&mut self, pes: &PES) {
+            self.pes.push((
+                pes.stream_id(),
+                pes.pts().map(Duration::from),
+                pes.buf_seek_payload().to_vec(),
+            ));
+        }
+
+        fn on_end(&mut self) {
+            self.end = true;
+        }
+    }
+
+    /// MPEG-2 pack header with 1 stuffing byte
+    fn pack(scr: u64) -> Vec<u8> {
+        let (base, ext) = (scr / 300, scr % 300);
+
+        vec![
+            0x00,
+            0x00,
+            0x01,
+            0xBA,
+            0x44 | ((base >> 27) & 0x38) as u8 | ((base >> 28) & 0x03) as u8,
+            (base >> 20) as u8,
+            0x04 | ((base >> 12) & 0xF8) as u8 | ((base >> 13) & 0x03) as u8,
+            (base >> 5) as u8,
+            0x04 | ((base << 3) & 0xF8) as u8 | ((ext >> 7) & 0x03) as u8,
+            ((ext << 1) as u8) | 0x01,
+            // mux-rate 25200 (1.26 Mbit/s)
+            0x01,
+            0x89,
+            0xC3,
+            0xF8 | 0x01,
+            0xFF,
+        ]
+    }
+
+    #[test]
+    fn pack_header() {
+        let raw = pack(27_000_000 + 5);
+        let pack = PackHeader::try_new(&raw).unwrap();
+
+        assert!(!pack.is_mpeg1());
+        assert_eq!(pack.sz(), 15);
+        assert_eq!(pack.scr(), 27_000_005);
+        assert_eq!(pack.program_mux_rate(), 25200);
+
+        // MPEG-1: SCR 90000, mux-rate 1
+        let raw = [
+            0x00, 0x00, 0x01, 0xBA, 0x21, 0x00, 0x05, 0xBF, 0x21, 0x80, 0x00, 0x03,
+        ];
+        let pack = PackHeader::try_new(&raw).unwrap();
+
+        assert!(pack.is_mpeg1());
+        assert_eq!(pack.sz(), 12);
+        assert_eq!(pack.scr(), 27_000_000);
+        assert_eq!(pack.program_mux_rate(), 1);
+    }
+
+    #[test]
+    fn demux() {
+        let mut stream = vec![0xAA, 0xBB];
+        stream.extend(pack(27_000_000));
+        // system header, 2 bytes body
+        stream.extend([0x00, 0x00, 0x01, 0xBB, 0x00, 0x02, 0x80, 0x01]);
+        stream.extend(synthetic::pes(0xE0, Some(90_000), None, &[0x01, 0x02]));
+        // padding stream
+        stream.extend([0x00, 0x00, 0x01, 0xBE, 0x00, 0x03, 0xFF, 0xFF, 0xFF]);
+        stream.extend(synthetic::pes(0xC0, Some(93_600), None, &[0x03]));
+        stream.extend([0x00, 0x00, 0x01, 0xB9]);
+
+        let mut demuxer = PSDemuxer::new(Events::default());
+        for chunk in stream.chunks(7) {
+            demuxer.push(chunk).unwrap();
+        }
+        assert_eq!(demuxer.finish(), 0);
+
+        let events = demuxer.events();
+        assert_eq!(events.scrs, vec![27_000_000]);
+        assert_eq!(
+            events.pes,
+            vec![
+                (
+                    StreamID::VideoStreamNumber(0xE0),
+                    Some(Duration::from_secs(1)),
+                    vec![0x01, 0x02]
+                ),
+                (
+                    StreamID::AudioStreamNumber(0xC0),
+                    Some(Duration::from_millis(1040)),
+                    vec![0x03]
+                ),
+            ]
+        );
+        assert!(events.end);
+        assert_eq!(demuxer.skipped(), 2);
+    }
+}
@@ -22,9 +22,56 @@ pub struct Rational {
     den: u64,
 }
 
+#[inline(always)]
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
+
 pub fn rescale(v: u64, src: Rational, dst: Rational) -> u64 {
     let num = u128::from(src.num) * u128::from(dst.den);
     let den = u128::from(src.den) * u128::from(dst.num);
 
-    ((v as f64) * ((num as f64) / (den as f64))) as u64
+    // fold ratio to keep intermediate product small
+    let d = gcd(num, den);
+    let (num, den) = (num / d, den / d);
+
+    (u128::from(v) * num / den) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rescale, TB_1NS, TB_27MHZ, TB_90KHZ};
+
+    #[test]
+    fn rescale_27mhz_to_ns() {
+        assert_eq!(rescale(27_000_000, TB_27MHZ, TB_1NS), 1_000_000_000);
+        assert_eq!(rescale(27, TB_27MHZ, TB_1NS), 1_000);
+    }
+
+    #[test]
+    fn rescale_pcr_full_range_to_ns() {
+        // 33 bits base * 300 + 9 bits extension (max 299)
+        let v: u64 = ((1 << 33) - 1) * 300 + 299;
+
+        assert_eq!(
+            rescale(v, TB_27MHZ, TB_1NS),
+            (u128::from(v) * 1_000 / 27) as u64
+        );
+        assert_eq!(rescale(v, TB_27MHZ, TB_1NS), 95_443_717_688_851);
+    }
+
+    #[test]
+    fn rescale_pts_full_range_to_ns() {
+        let v: u64 = (1 << 33) - 1;
+
+        assert_eq!(
+            rescale(v, TB_90KHZ, TB_1NS),
+            (u128::from(v) * 100_000 / 9) as u64
+        );
+    }
 }
@@ -0,0 +1,49 @@
+//! big-endian field readers;
+//! callers guarantee buffer length
+
+/// 16 bits from buf[0..2]
+#[inline(always)]
+pub(crate) fn be_u16(buf: &[u8]) -> u16 {
+    u16::from_be_bytes([buf[0], buf[1]])
+}
+
+/// 24 bits from buf[0..3]
+#[inline(always)]
+pub(crate) fn be_u24(buf: &[u8]) -> u32 {
+    u32::from_be_bytes([0, buf[0], buf[1], buf[2]])
+}
+
+/// 32 bits from buf[0..4]
+#[inline(always)]
+pub(crate) fn be_u32(buf: &[u8]) -> u32 {
+    u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]])
+}
+
+/// count bits of byte starting at bit lo (0 - LSB)
+#[inline(always)]
+pub(crate) fn bits(byte: u8, lo: u32, count: u32) -> u8 {
+    ((u16::from(byte) >> lo) & ((1 << count) - 1)) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{be_u16, be_u24, be_u32, bits};
+
+    #[test]
+    fn be() {
+        let buf = [0x12, 0x34, 0x56, 0x78, 0x9A];
+
+        assert_eq!(be_u16(&buf), 0x1234);
+        assert_eq!(be_u16(&buf[1..]), 0x3456);
+        assert_eq!(be_u24(&buf), 0x12_3456);
+        assert_eq!(be_u32(&buf[1..]), 0x3456_789A);
+    }
+
+    #[test]
+    fn bit_fields() {
+        assert_eq!(bits(0b1011_0110, 0, 3), 0b110);
+        assert_eq!(bits(0b1011_0110, 4, 4), 0b1011);
+        assert_eq!(bits(0b1011_0110, 7, 1), 1);
+        assert_eq!(bits(0xFF, 0, 8), 0xFF);
+    }
+}
@@ -0,0 +1,523 @@
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::crc32::crc32;
+use crate::packet::Packet;
+use crate::reader::be_u16;
+use crate::result::Result;
+use crate::section::HEADER_MAX_SECTION_LENGTH;
+
+/// table-id, section-syntax-indicator, section-length
+const SECTION_HEADER_SZ: usize = 3;
+
+/// syntax section header (table-id ... last-section-number)
+const SECTION_SYNTAX_SZ: usize = 8;
+
+const CRC32_SZ: usize = 4;
+
+/// PAT PID
+const PID_PAT: u16 = 0x0000;
+
+const TABLE_ID_PAT: u8 = 0x00;
+const TABLE_ID_PMT: u8 = 0x02;
+
+/// completed section bytes with (held packet, offset) of every byte
+type Done = (Vec<u8>, Vec<(usize, usize)>);
+
+/// PAT/PMT PID section patching state
+struct Psi {
+    pid: u16,
+
+    /// packets held until every section they carry is patched
+    held: Vec<[u8; Packet::SZ]>,
+
+    /// in-progress section
+    buf: Vec<u8>,
+    /// (held packet, offset) of each in-progress section byte
+    pos: Vec<(usize, usize)>,
+    /// full section size; None - header is not received yet
+    sz: Option<usize>,
+}
+
+impl Psi {
+    fn new(pid: u16) -> Psi {
+        Psi {
+            pid,
+            held: Vec::new(),
+            buf: Vec::new(),
+            pos: Vec::new(),
+            sz: None,
+        }
+    }
+
+    #[inline(always)]
+    fn clear(&mut self) {
+        self.buf.clear();
+        self.pos.clear();
+        self.sz = None;
+    }
+
+    /// payload of the last held packet starting at lft
+    fn feed(&mut self, lft: usize, pusi: bool, done: &mut Vec<Done>) {
+        let n = self.held.len() - 1;
+
+        if !pusi {
+            if !self.buf.is_empty() {
+                self.write(n, lft, Packet::SZ, done);
+            }
+            return;
+        }
+
+        let mut i = lft + 1;
+        let rght = (i + usize::from(self.held[n][lft])).min(Packet::SZ);
+
+        // tail of in-progress section
+        if !self.buf.is_empty() {
+            self.write(n, i, rght, done);
+        }
+        // broken (cc error / short tail); left as is
+        self.clear();
+
+        i = rght;
+
+        // several sections may be packed into one packet;
+        // 0xFF - stuffing
+        while i < Packet::SZ && self.held[n][i] != 0xFF {
+            i = self.write(n, i, Packet::SZ, done);
+
+            if !self.buf.is_empty() {
+                break;
+            }
+        }
+    }
+
+    /// append bytes [lft, rght) of held packet n up to section end;
+    /// returns position after the last consumed byte
+    fn write(&mut self, n: usize, lft: usize, rght: usize, done: &mut Vec<Done>) -> usize {
+        let mut i = lft;
+
+        while i < rght {
+            self.buf.push(self.held[n][i]);
+            self.pos.push((n, i));
+            i += 1;
+
+            if self.sz.is_none() && self.buf.len() == SECTION_HEADER_SZ {
+                let section_length = usize::from(be_u16(&self.buf[1..]) & 0x0FFF);
+
+                // corrupted length (PAT/PMT max 1021);
+                // section is left as is, resync on the next PUSI
+                if section_length > HEADER_MAX_SECTION_LENGTH {
+                    self.clear();
+                    return rght;
+                }
+
+                self.sz = Some(SECTION_HEADER_SZ + section_length);
+            }
+
+            if Some(self.buf.len()) == self.sz {
+                done.push((mem::take(&mut self.buf), mem::take(&mut self.pos)));
+                self.sz = None;
+                break;
+            }
+        }
+
+        i
+    }
+}
+
+/// remux PID rewrite:
+/// ts-packet header PID and PAT/PMT references
+/// (PMT PIDs, PCR-PID, elementary PIDs) with recomputed CRC;
+/// continuity-counter is left as is - it is per output PID.
+///
+/// PAT/PMT packets are held until the sections they carry are complete
+/// so they may be emitted later than packets of other PIDs;
+/// PMT PIDs are learned from PAT - PMT before the first PAT is not patched;
+/// `flush` must be called at EOF to emit packets still held
+pub struct PidRemapper {
+    /// from -> to
+    map: Vec<(u16, u16)>,
+
+    /// source PMT PIDs
+    pmt_pids: Vec<u16>,
+
+    psi: Vec<Psi>,
+}
+
+impl Default for PidRemapper {
+    fn default() -> Self {
+        PidRemapper::new()
+    }
+}
+
+impl PidRemapper {
+    pub fn new() -> PidRemapper {
+        PidRemapper {
+            map: Vec::new(),
+            pmt_pids: Vec::new(),
+            psi: Vec::new(),
+        }
+    }
+
+    /// not mapped PIDs pass unchanged
+    pub fn map(&mut self, from: u16, to: u16) {
+        match self.map.iter_mut().find(|(f, _)| *f == from) {
+            Some(m) => m.1 = to,
+            None => self.map.push((from, to)),
+        }
+    }
+
+    #[inline(always)]
+    pub fn get(&self, pid: u16) -> u16 {
+        self.map
+            .iter()
+            .find(|(from, _)| *from == pid)
+            .map_or(pid, |(_, to)| *to)
+    }
+
+    #[inline(always)]
+    fn is_psi(&self, pid: u16) -> bool {
+        pid == PID_PAT || self.pmt_pids.contains(&pid)
+    }
+
+    /// 13-bit PID at b[0..2], reserved bits are kept
+    #[inline(always)]
+    fn write_pid(&self, b: &mut [u8]) -> bool {
+        let pid = be_u16(b) & 0x1FFF;
+        let to = self.get(pid);
+
+        b[0] = (b[0] & 0xE0) | (to >> 8) as u8;
+        b[1] = to as u8;
+
+        pid != to
+    }
+
+    /// complete PAT/PMT section
+    fn patch(&mut self, sec: &mut [u8]) {
+        if sec.len() < SECTION_SYNTAX_SZ + CRC32_SZ {
+            return;
+        }
+
+        let crc_pos = sec.len() - CRC32_SZ;
+        let mut changed = false;
+
+        match sec[0] {
+            TABLE_ID_PAT => {
+                // PMT PIDs are rebuilt from every PAT version;
+                // section 0 starts it
+                if sec[6] == 0 {
+                    self.pmt_pids.clear();
+                }
+
+                let mut i = SECTION_SYNTAX_SZ;
+
+                while i + 4 <= crc_pos {
+                    let program_number = be_u16(&sec[i..]);
+                    let pid = be_u16(&sec[i + 2..]) & 0x1FFF;
+
+                    // 0 - network PID
+                    if program_number != 0 && !self.pmt_pids.contains(&pid) {
+                        self.pmt_pids.push(pid);
+                    }
+
+                    changed |= self.write_pid(&mut sec[i + 2..]);
+                    i += 4;
+                }
+            }
+            TABLE_ID_PMT => {
+                // pcr-pid, program-info-length
+                if SECTION_SYNTAX_SZ + 4 > crc_pos {
+                    return;
+                }
+
+                changed |= self.write_pid(&mut sec[SECTION_SYNTAX_SZ..]);
+
+                let program_info_length = usize::from(be_u16(&sec[10..]) & 0x0FFF);
+                let mut i = SECTION_SYNTAX_SZ + 4 + program_info_length;
+
+                // stream-type, elementary-pid, es-info-length
+                while i + 5 <= crc_pos {
+                    changed |= self.write_pid(&mut sec[i + 1..]);
+
+                    let es_info_length = usize::from(be_u16(&sec[i + 3..]) & 0x0FFF);
+                    i += 5 + es_info_length;
+                }
+            }
+            _ => {}
+        }
+
+        // source CRC is kept for untouched sections
+        if changed {
+            let crc = crc32(&sec[..crc_pos]);
+            sec[crc_pos..].copy_from_slice(&crc.to_be_bytes());
+        }
+    }
+
+    /// emit held PAT/PMT packets of PIDs no longer referenced by PAT
+    fn drop_stale<F>(&mut self, out: &mut F)
+    where
+        F: FnMut(&[u8]),
+    {
+        let pmt_pids = &self.pmt_pids;
+
+        self.psi.retain_mut(|psi| {
+            if psi.pid == PID_PAT || pmt_pids.contains(&psi.pid) {
+                return true;
+            }
+
+            for held in psi.held.drain(..) {
+                out(&held);
+            }
+            false
+        });
+    }
+
+    /// emit every held PAT/PMT packet as is (incomplete sections unpatched)
+    /// and forget in-progress sections;
+    /// must be called at EOF - otherwise trailing PSI packets are lost
+    pub fn flush<F>(&mut self, mut out: F)
+    where
+        F: FnMut(&[u8]),
+    {
+        for psi in self.psi.iter_mut() {
+            for held in psi.held.drain(..) {
+                out(&held);
+            }
+            psi.clear();
+        }
+    }
+
+    /// rewrite ts-packet;
+    /// out is called for every packet ready to be written (in PID order)
+    pub fn remap<F>(&mut self, raw: &[u8], mut out: F) -> Result<()>
+    where
+        F: FnMut(&[u8]),
+    {
+        let pkt = Packet::new(raw)?;
+        let pid = u16::from(pkt.pid());
+
+        let mut buf = [0u8; Packet::SZ];
+        buf.copy_from_slice(raw);
+        self.write_pid(&mut buf[1..]);
+
+        if !self.is_psi(pid) {
+            out(&buf);
+            return Ok(());
+        }
+
+        let i = match self.psi.iter().position(|psi| psi.pid == pid) {
+            Some(i) => i,
+            None => {
+                self.psi.push(Psi::new(pid));
+                self.psi.len() - 1
+            }
+        };
+
+        let mut done = Vec::new();
+        {
+            let psi = &mut self.psi[i];
+            psi.held.push(buf);
+
+            if pkt.got_payload() {
+                let lft = Packet::SZ - pkt.buf_payload_pes()?.len();
+                psi.feed(lft, pkt.pusi(), &mut done);
+            }
+        }
+
+        let mut pat = false;
+
+        for (mut sec, pos) in done {
+            pat |= sec[0] == TABLE_ID_PAT;
+            self.patch(&mut sec);
+
+            let psi = &mut self.psi[i];
+            for (b, (n, offset)) in sec.into_iter().zip(pos) {
+                psi.held[n][offset] = b;
+            }
+        }
+
+        let psi = &mut self.psi[i];
+        if psi.buf.is_empty() {
+            for held in psi.held.drain(..) {
+                out(&held);
+            }
+        }
+
+        if pat {
+            self.drop_stale(&mut out);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PidRemapper;
+    use crate::crc32::crc32;
+    use crate::packet::Packet;
+    use crate::reader::be_u16;
+    use crate::section::{PAT, PMT};
+    use crate::synthetic::{self, PacketBuilder, Packetizer};
+
+    fn remap(remapper: &mut PidRemapper, raw: &[u8]) -> Vec<Vec<u8>> {
+        let mut out = Vec::new();
+        remapper.remap(raw, |pkt| out.push(pkt.to_vec())).unwrap();
+        out
+    }
+
+    /// single packet section without stuffing
+    fn section(pkt: &[u8]) -> Vec<u8> {
+        let pkt = Packet::new(pkt).unwrap();
+        let buf = pkt.buf_payload_section().unwrap();
+        let sz = 3 + usize::from(be_u16(&buf[1..]) & 0x0FFF);
+
+        buf[..sz.min(buf.len())].to_vec()
+    }
+
+    #[test]
+    fn pat_pmt_es() {
+        let mut remapper = PidRemapper::new();
+        remapper.map(0x100, 0x200);
+        remapper.map(0x101, 0x201);
+
+        let pat = Packetizer::new(0x00).section(&synthetic::pat(1, 0, &[(1, 0x100)]))[0];
+        let pmt = Packetizer::new(0x100).section(&synthetic::pmt(
+            1,
+            0x101,
+            &[(0x1B, 0x101), (0x03, 0x102)],
+        ))[0];
+        let es = PacketBuilder::new(0x101).payload(&[0x00]).build();
+
+        let out = remap(&mut remapper, &pat);
+        assert_eq!(out.len(), 1);
+        let raw = section(&out[0]);
+        let pat = PAT::new(&raw);
+        assert_eq!(pat.program_map_pids().collect::<Vec<_>>(), [(1, 0x200)]);
+        assert_eq!(crc32(&raw), 0);
+
+        let out = remap(&mut remapper, &pmt);
+        assert_eq!(u16::from(Packet::new(&out[0]).unwrap().pid()), 0x200);
+        let raw = section(&out[0]);
+        let pmt = PMT::new(&raw);
+        assert_eq!(u16::from(pmt.pcr_pid()), 0x201);
+        assert_eq!(
+            pmt.streams().map(|s| s.unwrap().pid()).collect::<Vec<_>>(),
+            [0x201, 0x102]
+        );
+        assert_eq!(crc32(&raw), 0);
+
+        let out = remap(&mut remapper, &es);
+        let pkt = Packet::new(&out[0]).unwrap();
+        assert_eq!(u16::from(pkt.pid()), 0x201);
+        assert_eq!(pkt.cc(), 0);
+        assert_eq!(out[0][4..], es[4..]);
+    }
+
+    #[test]
+    fn section_length_max() {
+        let mut remapper = PidRemapper::new();
+        remapper.map(0x100, 0x200);
+
+        // PAT section-length 1022 - not held, not patched
+        let raw = PacketBuilder::new(0x00)
+            .pusi(true)
+            .payload(&[0x00, 0x00, 0xB3, 0xFE, 0x00, 0x01, 0xC1, 0x00, 0x00])
+            .build();
+        let out = remap(&mut remapper, &raw);
+        assert_eq!(out, [raw.to_vec()]);
+
+        // continuation passes unchanged
+        let raw = PacketBuilder::new(0x00).payload(&[0xAA; 16]).build();
+        assert_eq!(remap(&mut remapper, &raw), [raw.to_vec()]);
+
+        // resync on the next PUSI
+        let pat = Packetizer::new(0x00).section(&synthetic::pat(1, 0, &[(1, 0x100)]))[0];
+        let out = remap(&mut remapper, &pat);
+        let raw = section(&out[0]);
+        assert_eq!(
+            PAT::new(&raw).program_map_pids().collect::<Vec<_>>(),
+            [(1, 0x200)]
+        );
+    }
+
+    #[test]
+    fn pmt_multi_packet() {
+        let mut remapper = PidRemapper::new();
+        remapper.map(0x100, 0x300);
+        remapper.map(0x120, 0x320);
+
+        let streams = (0..48).map(|i| (0x1B, 0x110 + i)).collect::<Vec<_>>();
+        let pat = Packetizer::new(0x00).section(&synthetic::pat(1, 0, &[(1, 0x100)]))[0];
+        let pmt_raw = synthetic::pmt(1, 0x110, &streams);
+        let pmt = Packetizer::new(0x100).section(&pmt_raw);
+        assert_eq!(pmt.len(), 2);
+
+        remap(&mut remapper, &pat);
+
+        // held until section is complete
+        assert!(remap(&mut remapper, &pmt[0]).is_empty());
+        let out = remap(&mut remapper, &pmt[1]);
+        assert_eq!(out.len(), 2);
+
+        let mut raw = Packet::new(&out[0])
+            .unwrap()
+            .buf_payload_section()
+            .unwrap()
+            .to_vec();
+        raw.extend_from_slice(&out[1][4..]);
+        raw.truncate(pmt_raw.len());
+
+        let pmt = PMT::new(&raw);
+        let pids = pmt.streams().map(|s| s.unwrap().pid()).collect::<Vec<_>>();
+        assert_eq!(pids.len(), 48);
+        assert_eq!(pids[0x10], 0x320);
+        assert_eq!(pids[0x2F], 0x13F);
+        assert_eq!(crc32(&raw), 0);
+    }
+
+    #[test]
+    fn flush() {
+        let mut remapper = PidRemapper::new();
+        remapper.map(0x100, 0x300);
+
+        let streams = (0..48).map(|i| (0x1B, 0x110 + i)).collect::<Vec<_>>();
+        let pat = Packetizer::new(0x00).section(&synthetic::pat(1, 0, &[(1, 0x100)]))[0];
+        let pmt = Packetizer::new(0x100).section(&synthetic::pmt(1, 0x110, &streams));
+
+        remap(&mut remapper, &pat);
+        assert!(remap(&mut remapper, &pmt[0]).is_empty());
+
+        // EOF: incomplete section is emitted unpatched
+        let mut out = Vec::new();
+        remapper.flush(|pkt| out.push(pkt.to_vec()));
+        assert_eq!(out.len(), 1);
+        assert_eq!(u16::from(Packet::new(&out[0]).unwrap().pid()), 0x300);
+        assert_eq!(out[0][4..], pmt[0][4..]);
+
+        let mut out = Vec::new();
+        remapper.flush(|pkt| out.push(pkt.to_vec()));
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn pat_pmt_pids_rebuilt() {
+        let mut remapper = PidRemapper::new();
+
+        let streams = (0..48).map(|i| (0x1B, 0x110 + i)).collect::<Vec<_>>();
+        let pat = Packetizer::new(0x00).section(&synthetic::pat(1, 0, &[(1, 0x100)]))[0];
+        let pmt = Packetizer::new(0x100).section(&synthetic::pmt(1, 0x110, &streams));
+
+        remap(&mut remapper, &pat);
+        assert!(remap(&mut remapper, &pmt[0]).is_empty());
+
+        // new PAT version drops 0x100: held packet is emitted
+        let pat = Packetizer::new(0x00).section(&synthetic::pat(1, 1, &[(1, 0x101)]))[0];
+        let out = remap(&mut remapper, &pat);
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[1], pmt[0].to_vec());
+
+        // 0x100 is not PSI anymore - passes without holding
+        assert_eq!(remap(&mut remapper, &pmt[0]), [pmt[0].to_vec()]);
+    }
+}
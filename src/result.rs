@@ -1,4 +1,4 @@
-use std::result::Result as StdResult;
+use core::result::Result as StdResult;
 
 use crate::error::Error;
 
@@ -0,0 +1,200 @@
+use core::fmt;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::reader::{be_u16, be_u32};
+use crate::result::Result;
+
+/// RFC 3550 RTP packet;
+/// RFC 2250 MPEG-2 TS payload
+#[derive(Clone, Copy)]
+pub struct RTP<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> RTP<'buf> {
+    const HEADER_SZ: usize = 12;
+    const CSRC_SZ: usize = 4;
+    const EXTENSION_HEADER_SZ: usize = 4;
+
+    pub const VERSION: u8 = 2;
+    /// MP2T
+    pub const PAYLOAD_TYPE_MP2T: u8 = 33;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> RTP<'buf> {
+        RTP { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<RTP<'buf>> {
+        let p = Self::new(buf);
+        p.validate()?;
+        Ok(p)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            return Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)));
+        }
+
+        if self.version() != Self::VERSION {
+            return Err(Error::new(ErrorKind::RTPVersion(self.version())));
+        }
+
+        let csrc_end = Self::HEADER_SZ + Self::CSRC_SZ * usize::from(self.csrc_count());
+        let ext_end = csrc_end + Self::EXTENSION_HEADER_SZ;
+        if self.extension() && self.buf.len() < ext_end {
+            return Err(Error::new(ErrorKind::Buf(self.buf.len(), ext_end)));
+        }
+
+        let sz = self.header_sz() + self.padding_sz();
+        if self.buf.len() < sz {
+            return Err(Error::new(ErrorKind::Buf(self.buf.len(), sz)));
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn version(&self) -> u8 {
+        self.buf[0] >> 6
+    }
+
+    #[inline(always)]
+    pub fn padding(&self) -> bool {
+        (self.buf[0] & 0b0010_0000) != 0
+    }
+
+    #[inline(always)]
+    pub fn extension(&self) -> bool {
+        (self.buf[0] & 0b0001_0000) != 0
+    }
+
+    #[inline(always)]
+    pub fn csrc_count(&self) -> u8 {
+        self.buf[0] & 0b0000_1111
+    }
+
+    #[inline(always)]
+    pub fn marker(&self) -> bool {
+        (self.buf[1] & 0b1000_0000) != 0
+    }
+
+    #[inline(always)]
+    pub fn payload_type(&self) -> u8 {
+        self.buf[1] & 0b0111_1111
+    }
+
+    #[inline(always)]
+    pub fn sequence_number(&self) -> u16 {
+        be_u16(&self.buf[2..])
+    }
+
+    /// 90kHz for MP2T
+    #[inline(always)]
+    pub fn timestamp(&self) -> u32 {
+        be_u32(&self.buf[4..])
+    }
+
+    #[inline(always)]
+    pub fn ssrc(&self) -> u32 {
+        be_u32(&self.buf[8..])
+    }
+
+    /// fixed header, CSRC list, header extension
+    #[inline(always)]
+    fn header_sz(&self) -> usize {
+        let sz = Self::HEADER_SZ + Self::CSRC_SZ * usize::from(self.csrc_count());
+
+        if self.extension() {
+            // extension length in 32-bit words, without extension header
+            sz + Self::EXTENSION_HEADER_SZ + 4 * usize::from(be_u16(&self.buf[sz + 2..]))
+        } else {
+            sz
+        }
+    }
+
+    /// trailing padding with its count byte
+    #[inline(always)]
+    fn padding_sz(&self) -> usize {
+        if self.padding() {
+            usize::from(self.buf[self.buf.len() - 1])
+        } else {
+            0
+        }
+    }
+
+    /// seek
+    #[inline(always)]
+    pub fn buf_payload(&self) -> &'buf [u8] {
+        &self.buf[self.header_sz()..self.buf.len() - self.padding_sz()]
+    }
+}
+
+impl<'buf> fmt::Debug for RTP<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ":RTP (:pt {} :seq {} :ts {} :ssrc 0x{:08X} :marker {})",
+            self.payload_type(),
+            self.sequence_number(),
+            self.timestamp(),
+            self.ssrc(),
+            self.marker(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RTP;
+    use crate::error::{Error, Kind as ErrorKind};
+
+    #[test]
+    fn header() {
+        #[rustfmt::skip]
+        let raw = [
+            // v2, padding, extension, 1 CSRC; marker, MP2T
+            0xB1, 0xA1,
+            // seq, timestamp, SSRC
+            0x12, 0x34, 0x00, 0x01, 0x5F, 0x90, 0xDE, 0xAD, 0xBE, 0xEF,
+            // CSRC
+            0x00, 0x00, 0x00, 0x01,
+            // extension: profile, 1 word
+            0xBE, 0xDE, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+            // payload
+            0x47, 0x1F,
+            // padding: 2 bytes
+            0x00, 0x02,
+        ];
+        let rtp = RTP::try_new(&raw).unwrap();
+
+        assert!(rtp.marker());
+        assert_eq!(rtp.payload_type(), RTP::PAYLOAD_TYPE_MP2T);
+        assert_eq!(rtp.sequence_number(), 0x1234);
+        assert_eq!(rtp.timestamp(), 90_000);
+        assert_eq!(rtp.ssrc(), 0xDEAD_BEEF);
+        assert_eq!(rtp.buf_payload(), &[0x47, 0x1F]);
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            RTP::try_new(&[0x80, 0x21]).err(),
+            Some(Error::new(ErrorKind::Buf(2, 12)))
+        );
+        assert_eq!(
+            RTP::try_new(&[0x40; 12]).err(),
+            Some(Error::new(ErrorKind::RTPVersion(1)))
+        );
+
+        // extension header is missing
+        let mut raw = [0u8; 12];
+        raw[0] = 0x90;
+        assert_eq!(
+            RTP::try_new(&raw).err(),
+            Some(Error::new(ErrorKind::Buf(12, 16)))
+        );
+    }
+}
@@ -0,0 +1,45 @@
+/// ETSI EN 300 468 V1.15.1 (5.2.3, table 6)
+///
+/// SDT service / EIT event running_status
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum RunningStatus {
+    Undefined,
+    NotRunning,
+    StartsInAFewSeconds,
+    Pausing,
+    Running,
+    ServiceOffAir,
+
+    Reserved(u8),
+}
+
+impl From<u8> for RunningStatus {
+    fn from(d: u8) -> Self {
+        match d {
+            0 => RunningStatus::Undefined,
+            1 => RunningStatus::NotRunning,
+            2 => RunningStatus::StartsInAFewSeconds,
+            3 => RunningStatus::Pausing,
+            4 => RunningStatus::Running,
+            5 => RunningStatus::ServiceOffAir,
+
+            _ => RunningStatus::Reserved(d),
+        }
+    }
+}
+
+impl From<RunningStatus> for u8 {
+    fn from(rs: RunningStatus) -> u8 {
+        match rs {
+            RunningStatus::Undefined => 0,
+            RunningStatus::NotRunning => 1,
+            RunningStatus::StartsInAFewSeconds => 2,
+            RunningStatus::Pausing => 3,
+            RunningStatus::Running => 4,
+            RunningStatus::ServiceOffAir => 5,
+
+            RunningStatus::Reserved(d) => d,
+        }
+    }
+}
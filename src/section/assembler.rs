@@ -0,0 +1,313 @@
+use alloc::vec::Vec;
+
+use crate::error::{Error, Kind as ErrorKind};
+use crate::packet::Packet;
+use crate::reader::be_u16;
+use crate::result::Result;
+
+use super::traits::{HEADER_MAX_PRIVATE_SECTION_LENGTH, HEADER_SZ};
+
+/// single PID section reassembly:
+/// pointer-field, packed sections, 0xFF stuffing,
+/// section-length driven completion;
+/// no table-id / version / CRC handling
+pub struct SectionAssembler {
+    /// full section size; None - header is not received yet
+    sz: Option<usize>,
+
+    /// got section start
+    started: bool,
+
+    /// section head is reported
+    begun: bool,
+
+    /// section head size reported before completion
+    begin_sz: usize,
+
+    max_section_length: usize,
+
+    buf: Vec<u8>,
+}
+
+impl Default for SectionAssembler {
+    fn default() -> Self {
+        SectionAssembler::new()
+    }
+}
+
+impl SectionAssembler {
+    pub fn new() -> SectionAssembler {
+        SectionAssembler {
+            sz: None,
+            started: false,
+            begun: false,
+            begin_sz: HEADER_SZ,
+            max_section_length: HEADER_MAX_PRIVATE_SECTION_LENGTH,
+            buf: Vec::new(),
+        }
+    }
+
+    /// section with larger section-length is dropped with error;
+    /// default: 4093
+    #[inline(always)]
+    pub fn set_max_section_length(&mut self, max_section_length: usize) {
+        self.max_section_length = max_section_length;
+    }
+
+    /// section head size reported by push_with,
+    /// e.g. full syntax-section header to parse table-id-extension;
+    /// default: 3
+    #[inline(always)]
+    pub fn set_begin_sz(&mut self, begin_sz: usize) {
+        self.begin_sz = begin_sz;
+    }
+
+    /// drop in-progress section (e.g. on cc error);
+    /// next section starts with the next PUSI
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        self.buf.clear();
+        self.sz = None;
+        self.started = false;
+        self.begun = false;
+    }
+
+    /// feed ts-packet payload;
+    /// with PUSI payload starts with pointer-field;
+    /// complete sections (header, data, CRC) are passed to on_section
+    pub fn push<F>(&mut self, payload: &[u8], pusi: bool, mut on_section: F) -> Result<()>
+    where
+        F: FnMut(&[u8]),
+    {
+        self.push_with(payload, pusi, |raw, complete| {
+            if complete {
+                on_section(raw)
+            }
+        })
+    }
+
+    /// feed ts-packet
+    #[inline(always)]
+    pub fn push_packet<F>(&mut self, pkt: &Packet, on_section: F) -> Result<()>
+    where
+        F: FnMut(&[u8]),
+    {
+        self.push(pkt.buf_payload_pes()?, pkt.pusi(), on_section)
+    }
+
+    /// like push; on is also called with section head
+    /// (begin-sz bytes or whole shorter section) as soon as it is buffered;
+    /// on(raw, complete)
+    pub fn push_with<F>(&mut self, payload: &[u8], pusi: bool, mut on: F) -> Result<()>
+    where
+        F: FnMut(&[u8], bool),
+    {
+        if !pusi {
+            self.write(payload, &mut on)?;
+            return Ok(());
+        }
+
+        let lft = 1 + usize::from(*payload.first().ok_or(Error::new(ErrorKind::Buf(0, 1)))?);
+        if lft > payload.len() {
+            return Err(Error::new(ErrorKind::Buf(payload.len(), lft)));
+        }
+
+        // tail of in-progress section
+        self.write(&payload[1..lft], &mut on)?;
+
+        let mut buf = &payload[lft..];
+
+        // several sections may be packed into one packet;
+        // 0xFF - stuffing
+        while buf.first().is_some_and(|b| *b != 0xFF) {
+            self.begin();
+            let sz = self.write(buf, &mut on)?;
+
+            buf = &buf[sz..];
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn begin(&mut self) {
+        self.buf.clear();
+        self.sz = None;
+        self.started = true;
+        self.begun = false;
+    }
+
+    /// feed in-progress section;
+    /// return: consumed sz
+    #[inline(always)]
+    fn write<F>(&mut self, buf: &[u8], on: &mut F) -> Result<usize>
+    where
+        F: FnMut(&[u8], bool),
+    {
+        let mut consumed = 0;
+
+        while self.started && consumed < buf.len() {
+            // section header may be split across packets
+            let sz_need = match self.sz {
+                Some(sz) if !self.begun => sz.min(self.begin_sz) - self.buf.len(),
+                Some(sz) => sz - self.buf.len(),
+                None => HEADER_SZ - self.buf.len(),
+            };
+            let n = sz_need.min(buf.len() - consumed);
+
+            self.buf.extend_from_slice(&buf[consumed..consumed + n]);
+            consumed += n;
+
+            if self.sz.is_none() && self.buf.len() == HEADER_SZ {
+                let section_length = usize::from(be_u16(&self.buf[1..]) & 0x0FFF);
+
+                if section_length > self.max_section_length {
+                    self.reset();
+
+                    return Err(Error::new(ErrorKind::SectionLength(
+                        section_length,
+                        self.max_section_length,
+                    )));
+                }
+
+                self.sz = Some(HEADER_SZ + section_length);
+            }
+
+            let sz = match self.sz {
+                Some(sz) => sz,
+                None => continue,
+            };
+
+            if !self.begun && self.buf.len() >= sz.min(self.begin_sz) {
+                self.begun = true;
+
+                // emit
+                on(&self.buf, false);
+            }
+
+            if self.buf.len() == sz {
+                self.started = false;
+
+                // emit
+                on(&self.buf, true);
+            }
+        }
+
+        Ok(consumed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SectionAssembler;
+    use crate::error::{Error, Kind as ErrorKind};
+    use crate::packet::Packet;
+    use crate::synthetic::{self, PacketBuilder, Packetizer};
+
+    #[test]
+    fn across_packets() {
+        let section = synthetic::section(0xC0, 1, 0, (0, 0), &[0xAA; 300]);
+        let mut assembler = SectionAssembler::new();
+        let mut sections = Vec::new();
+
+        for raw in Packetizer::new(0x500).section(&section) {
+            let pkt = Packet::new(&raw).unwrap();
+            assembler
+                .push_packet(&pkt, |s| sections.push(s.to_vec()))
+                .unwrap();
+        }
+
+        assert_eq!(sections, vec![section]);
+    }
+
+    #[test]
+    fn packed_and_pointer_field() {
+        let first = synthetic::section(0xC0, 1, 0, (0, 0), &[0x01; 8]);
+        let second = synthetic::section(0xC1, 1, 0, (0, 0), &[0x02; 4]);
+        let third = synthetic::section(0xC2, 1, 0, (0, 0), &[0x03; 4]);
+        let mut assembler = SectionAssembler::new();
+        let mut sections = Vec::new();
+
+        // first section split; tail, second and head of third share payload
+        assembler
+            .push(&[&[0x00][..], &first[..10]].concat(), true, |s| {
+                sections.push(s.to_vec())
+            })
+            .unwrap();
+
+        let mut payload = vec![(first.len() - 10) as u8];
+        payload.extend_from_slice(&first[10..]);
+        payload.extend_from_slice(&second);
+        payload.extend_from_slice(&third[..2]);
+        assembler
+            .push(&payload, true, |s| sections.push(s.to_vec()))
+            .unwrap();
+
+        assembler
+            .push(&[&third[2..], &[0xFF; 4][..]].concat(), false, |s| {
+                sections.push(s.to_vec())
+            })
+            .unwrap();
+
+        assert_eq!(sections, vec![first, second, third]);
+    }
+
+    #[test]
+    fn begin() {
+        let section = synthetic::section(0xC0, 1, 0, (0, 0), &[0xAA; 300]);
+        let mut assembler = SectionAssembler::new();
+        assembler.set_begin_sz(8);
+        let mut events = Vec::new();
+
+        // head split across pushes
+        let mut payload = vec![0x00];
+        payload.extend_from_slice(&section[..5]);
+        for (buf, pusi) in [(&payload[..], true), (&section[5..], false)] {
+            assembler
+                .push_with(buf, pusi, |s, complete| events.push((s.to_vec(), complete)))
+                .unwrap();
+        }
+
+        assert_eq!(
+            events,
+            vec![(section[..8].to_vec(), false), (section.clone(), true)]
+        );
+    }
+
+    #[test]
+    fn reset_and_errors() {
+        let section = synthetic::section(0xC0, 1, 0, (0, 0), &[0xAA; 300]);
+        let pkts = Packetizer::new(0x500).section(&section);
+        let mut assembler = SectionAssembler::new();
+        let mut sections = 0;
+
+        // continuation without start is skipped
+        assembler
+            .push_packet(&Packet::new(&pkts[1]).unwrap(), |_| sections += 1)
+            .unwrap();
+        assembler
+            .push_packet(&Packet::new(&pkts[0]).unwrap(), |_| sections += 1)
+            .unwrap();
+        assembler.reset();
+        assembler
+            .push_packet(&Packet::new(&pkts[1]).unwrap(), |_| sections += 1)
+            .unwrap();
+        assert_eq!(sections, 0);
+
+        // pointer-field beyond payload
+        let raw = PacketBuilder::new(0x500)
+            .pusi(true)
+            .payload(&[0xFF; 184])
+            .build();
+        assert_eq!(
+            assembler.push_packet(&Packet::new(&raw).unwrap(), |_| {}),
+            Err(Error::new(ErrorKind::Buf(184, 256)))
+        );
+
+        assembler.set_max_section_length(8);
+        assert_eq!(
+            assembler.push(&[&[0x00][..], &section].concat(), true, |_| {}),
+            Err(Error::new(ErrorKind::SectionLength(309, 8)))
+        );
+    }
+}
@@ -1,12 +1,17 @@
-use std::fmt;
-use std::time::Duration;
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::fmt;
+use core::time::Duration;
 
 use chrono::prelude::*;
 
+use crate::annex_a2::AnnexA2;
 use crate::annex_c;
-use crate::descriptor::Descriptor;
+use crate::descriptor::{DescDVB0x4D, Descriptor};
 use crate::duration_fmt::DurationFmt;
 use crate::error::{Error, Kind as ErrorKind};
+use crate::iso_639::ISO639;
+use crate::reader::be_u16;
 use crate::result::Result;
 use crate::subtable_id::{SubtableID, SubtableIDer};
 
@@ -15,13 +20,15 @@ use super::traits::*;
 /// ETSI EN 300 468 V1.15.1
 ///
 /// Event Information Table
+#[derive(Clone, Copy)]
 pub struct EIT<'buf> {
     buf: &'buf [u8],
 }
 
 impl<'buf> EIT<'buf> {
     const HEADER_SPECIFIC_SZ: usize = 6;
-    const HEADER_FULL_SZ: usize = HEADER_SZ + SYNTAX_SECTION_SZ + Self::HEADER_SPECIFIC_SZ;
+    pub(crate) const HEADER_FULL_SZ: usize =
+        HEADER_SZ + SYNTAX_SECTION_SZ + Self::HEADER_SPECIFIC_SZ;
 
     #[inline(always)]
     pub fn new(buf: &'buf [u8]) -> EIT<'buf> {
@@ -37,7 +44,7 @@ impl<'buf> EIT<'buf> {
 
     #[inline(always)]
     pub fn validate(&self) -> Result<()> {
-        Ok(())
+        validate_syntax_section(self.buf, Self::HEADER_FULL_SZ)
     }
 
     /// seek
@@ -56,44 +63,77 @@ impl<'buf> EIT<'buf> {
     }
 
     #[inline(always)]
-    pub fn events(&self) -> Cursor<'buf, Event> {
+    pub fn events(&self) -> Cursor<'buf, Event<'buf>> {
         Cursor::new(self.buf_events())
     }
 
+    /// descriptors of all events
+    pub fn descriptors_flat(
+        &self,
+    ) -> impl Iterator<Item = (DescriptorContext, Descriptor<'buf>)> + 'buf {
+        self.events()
+            .filter_map(Result::ok)
+            .flat_map(|e| descriptors_in(DescriptorContext::Event(e.event_id()), e.descriptors()))
+    }
+
     #[inline(always)]
     pub fn service_id(&self) -> u16 {
         self.table_id_extension()
     }
-}
 
-trait WithEITHeaderSpecific<'buf>: Bufer<'buf> {
-    /// buffer seeked
+    /// buffer seeked to header specific fields
     #[inline(always)]
     fn b(&self) -> &'buf [u8] {
-        &self.buf()[HEADER_SZ + SYNTAX_SECTION_SZ..]
+        &self.buf[HEADER_SZ + SYNTAX_SECTION_SZ..]
     }
 
     #[inline(always)]
-    fn transport_stream_id(&self) -> u16 {
-        u16::from(self.b()[0]) | u16::from(self.b()[1])
+    pub fn transport_stream_id(&self) -> u16 {
+        be_u16(self.b())
     }
 
     #[inline(always)]
-    fn original_network_id(&self) -> u16 {
-        u16::from(self.b()[2]) | u16::from(self.b()[3])
+    pub fn original_network_id(&self) -> u16 {
+        be_u16(&self.b()[2..])
     }
 
+    /// segment of this section;
+    /// schedule sub-table is split into 3-hour segments of 8 sections,
+    /// segment n is section-numbers 8n...8n+7
     #[inline(always)]
-    fn segment_last_section_number(&self) -> u8 {
+    pub fn segment_number(&self) -> u8 {
+        self.section_number() / 8
+    }
+
+    /// last section-number of this segment;
+    /// section-numbers after it up to the next segment are not used,
+    /// so segment is complete once sections
+    /// segment-first...segment-last are received.
+    /// present/following: same as last-section-number
+    #[inline(always)]
+    pub fn segment_last_section_number(&self) -> u8 {
         self.b()[4]
     }
 
+    /// last table-id of schedule in use for this service
+    /// (0x50...0x5F actual, 0x60...0x6F other TS);
+    /// schedule is complete once all sub-tables up to it are.
+    /// present/following: same as table-id
     #[inline(always)]
-    fn last_table_id(&self) -> u8 {
+    pub fn last_table_id(&self) -> u8 {
         self.b()[5]
     }
 }
 
+impl<'buf> TryFrom<&'buf [u8]> for EIT<'buf> {
+    type Error = Error;
+
+    #[inline(always)]
+    fn try_from(buf: &'buf [u8]) -> Result<Self> {
+        Self::try_new(buf)
+    }
+}
+
 impl<'buf> Bufer<'buf> for EIT<'buf> {
     fn buf(&self) -> &'buf [u8] {
         self.buf
@@ -103,7 +143,6 @@ impl<'buf> Bufer<'buf> for EIT<'buf> {
 impl<'buf> WithHeader<'buf> for EIT<'buf> {}
 impl<'buf> WithTableIDExtension<'buf> for EIT<'buf> {}
 impl<'buf> WithSyntaxSection<'buf> for EIT<'buf> {}
-impl<'buf> WithEITHeaderSpecific<'buf> for EIT<'buf> {}
 impl<'buf> WithCRC32<'buf> for EIT<'buf> {}
 
 impl<'buf> fmt::Debug for EIT<'buf> {
@@ -147,6 +186,36 @@ impl<'buf> SubtableIDer for EIT<'buf> {
     }
 }
 
+/// decoded short event descriptor
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ShortEvent {
+    pub language: ISO639,
+    pub event_name: String,
+    pub text: String,
+}
+
+/// owned event;
+/// detached from section buffer
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EventInfo {
+    pub event_id: u16,
+    pub start_time: DateTime<Utc>,
+    /// seconds
+    pub duration: u64,
+    pub short_event: Option<ShortEvent>,
+}
+
+/// empty text field is valid
+fn decode_text(buf: &[u8]) -> Option<String> {
+    if buf.is_empty() {
+        return Some(String::new());
+    }
+
+    AnnexA2::decode_to_string(buf).ok()
+}
+
 pub struct Event<'buf> {
     buf: &'buf [u8],
 }
@@ -172,7 +241,7 @@ impl<'buf> Event<'buf> {
 
     #[inline(always)]
     pub fn event_id(&self) -> u16 {
-        (u16::from(self.buf[0]) << 8) | u16::from(self.buf[1])
+        be_u16(self.buf)
     }
 
     #[inline(always)]
@@ -187,6 +256,39 @@ impl<'buf> Event<'buf> {
         annex_c::from_bytes_into_duration(&self.buf[7..10]).unwrap()
     }
 
+    /// None - undefined start-time (e.g. NVOD reference event)
+    pub fn try_start_time(&self) -> Option<DateTime<Utc>> {
+        // all bits set - undefined
+        if self.buf[2..7].iter().all(|b| *b == 0xFF) {
+            return None;
+        }
+
+        annex_c::from_bytes_into_date_time_utc(&self.buf[2..7]).ok()
+    }
+
+    /// None - not valid BCD
+    #[inline(always)]
+    pub fn try_duration(&self) -> Option<Duration> {
+        annex_c::from_bytes_into_duration(&self.buf[7..10]).ok()
+    }
+
+    /// start-time + duration;
+    /// None - undefined start-time (e.g. NVOD reference event)
+    /// or not representable end-time
+    pub fn end_time(&self) -> Option<DateTime<Utc>> {
+        let start_time = self.try_start_time()?;
+        let duration = self.try_duration()?;
+
+        start_time.checked_add_signed(chrono::Duration::from_std(duration).ok()?)
+    }
+
+    /// start-time <= t < end-time
+    #[inline(always)]
+    pub fn is_airing_at(&self, t: DateTime<Utc>) -> bool {
+        self.end_time()
+            .is_some_and(|end_time| self.start_time() <= t && t < end_time)
+    }
+
     /// seek
     #[inline(always)]
     fn buf_descriptors(&self) -> &'buf [u8] {
@@ -201,7 +303,7 @@ impl<'buf> Event<'buf> {
     }
 
     #[inline(always)]
-    pub fn descriptors(&self) -> Option<Cursor<'buf, Descriptor>> {
+    pub fn descriptors(&self) -> Option<Cursor<'buf, Descriptor<'buf>>> {
         if self.descriptors_loop_length() != 0 {
             Some(Cursor::new(self.buf_descriptors()))
         } else {
@@ -211,7 +313,39 @@ impl<'buf> Event<'buf> {
 
     #[inline(always)]
     pub fn descriptors_loop_length(&self) -> u16 {
-        (u16::from(self.buf[10] & 0b0000_1111) << 8) | u16::from(self.buf[11])
+        be_u16(&self.buf[10..]) & 0x0FFF
+    }
+
+    /// first short event descriptor
+    /// with decodable event-name and text
+    pub fn short_event(&self) -> Option<ShortEvent> {
+        self.descriptors()?
+            .filter_map(Result::ok)
+            .filter(|d| d.is_dvb_short_event())
+            .filter_map(|d| DescDVB0x4D::try_new(d.buf_data()).ok())
+            .find_map(|desc| {
+                Some(ShortEvent {
+                    language: desc.iso_639_language_code(),
+                    event_name: decode_text(desc.event_name())?,
+                    text: decode_text(desc.text())?,
+                })
+            })
+    }
+
+    /// snapshot
+    pub fn info(&self) -> EventInfo {
+        EventInfo {
+            event_id: self.event_id(),
+            start_time: self.start_time(),
+            duration: self.duration().as_secs(),
+            short_event: self.short_event(),
+        }
+    }
+
+    /// short event event-name
+    #[inline(always)]
+    pub fn title(&self) -> Option<String> {
+        self.short_event().map(|e| e.event_name)
     }
 }
 
@@ -261,3 +395,145 @@ impl<'buf> fmt::Debug for Event<'buf> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::prelude::*;
+
+    use super::EIT;
+    use crate::subtable_id::{SubtableID, SubtableIDer};
+    use crate::synthetic;
+
+    /// event 1, 2016-11-21 15:00:00, 01:30:00
+    fn event(descriptors: &[u8]) -> Vec<u8> {
+        let mut buf = vec![
+            0x00,
+            0x01,
+            0xE1,
+            0x71,
+            0x15,
+            0x00,
+            0x00,
+            0x01,
+            0x30,
+            0x00,
+            0x80 | (descriptors.len() >> 8) as u8,
+            descriptors.len() as u8,
+        ];
+        buf.extend_from_slice(descriptors);
+        buf
+    }
+
+    fn eit(events: &[Vec<u8>]) -> Vec<u8> {
+        // transport-stream-id, original-network-id,
+        // segment-last-section-number, last-table-id
+        let mut body = vec![0x00, 0x01, 0x00, 0x01, 0x00, 0x4E];
+        for e in events {
+            body.extend_from_slice(e);
+        }
+
+        synthetic::section(0x4E, 1, 0, (0, 0), &body)
+    }
+
+    #[test]
+    fn schedule_header() {
+        // segment 1 (sections 8...15) uses 8...9, schedule ends at 0x51
+        let body = [0x00, 0x02, 0x00, 0x03, 0x09, 0x51];
+        let raw = synthetic::section(0x50, 1, 0, (9, 0x3F), &body);
+        let eit = EIT::new(&raw);
+
+        assert_eq!(eit.transport_stream_id(), 2);
+        assert_eq!(eit.original_network_id(), 3);
+        assert_eq!(eit.segment_number(), 1);
+        assert_eq!(eit.segment_last_section_number(), 9);
+        assert_eq!(eit.last_table_id(), 0x51);
+        assert_eq!(eit.events().count(), 0);
+    }
+
+    #[test]
+    fn short_event() {
+        #[rustfmt::skip]
+        let raw = eit(&[event(&[
+            // content descriptor
+            0x54, 0x02, 0x10, 0x00,
+            // short event: "deu" "Nachrichten" ""
+            0x4D, 0x10, b'd', b'e', b'u',
+            0x0B, b'N', b'a', b'c', b'h', b'r', b'i', b'c', b'h', b't', b'e', b'n',
+            0x00,
+        ])]);
+        let eit = EIT::new(&raw);
+        let event = eit.events().next().unwrap().unwrap();
+
+        let short = event.short_event().unwrap();
+        assert_eq!(short.language.as_str(), "deu");
+        assert_eq!(short.event_name, "Nachrichten");
+        assert_eq!(short.text, "");
+
+        assert_eq!(event.title().as_deref(), Some("Nachrichten"));
+    }
+
+    #[test]
+    fn info() {
+        #[rustfmt::skip]
+        let raw = eit(&[event(&[
+            0x4D, 0x09, b'e', b'n', b'g', 0x04, b'N', b'e', b'w', b's', 0x00,
+        ])]);
+        let eit = EIT::new(&raw);
+        let info = eit.events().next().unwrap().unwrap().info();
+
+        assert_eq!(info.event_id, 1);
+        assert_eq!(info.duration, 90 * 60);
+        assert_eq!(info.short_event.unwrap().event_name, "News");
+    }
+
+    #[test]
+    fn short_event_absent() {
+        let raw = eit(&[event(&[0x54, 0x02, 0x10, 0x00]), event(&[])]);
+        let eit = EIT::new(&raw);
+
+        for event in eit.events() {
+            let event = event.unwrap();
+
+            assert!(event.short_event().is_none());
+            assert!(event.title().is_none());
+        }
+    }
+
+    #[test]
+    fn end_time() {
+        let mut undefined = event(&[]);
+        undefined[2..7].copy_from_slice(&[0xFF; 5]);
+
+        let raw = eit(&[event(&[]), undefined]);
+        let eit = EIT::new(&raw);
+        let events = eit.events().map(Result::unwrap).collect::<Vec<_>>();
+
+        let at = |h, m| Utc.with_ymd_and_hms(2016, 11, 21, h, m, 0).unwrap();
+
+        assert_eq!(events[0].end_time(), Some(at(16, 30)));
+        assert!(!events[0].is_airing_at(at(14, 59)));
+        assert!(events[0].is_airing_at(at(15, 0)));
+        assert!(events[0].is_airing_at(at(16, 29)));
+        assert!(!events[0].is_airing_at(at(16, 30)));
+
+        assert_eq!(events[1].end_time(), None);
+        assert!(!events[1].is_airing_at(at(15, 0)));
+    }
+
+    #[test]
+    fn subtable_id_big_endian() {
+        // transport-stream-id 0x1234, original-network-id 0x5678
+        let body = [0x12, 0x34, 0x56, 0x78, 0x00, 0x4E];
+        let raw = synthetic::section(0x4E, 0x0102, 3, (0, 0), &body);
+
+        match EIT::new(&raw).subtable_id() {
+            SubtableID::EIT(_, service_id, transport_stream_id, original_network_id, version) => {
+                assert_eq!(service_id, 0x0102);
+                assert_eq!(transport_stream_id, 0x1234);
+                assert_eq!(original_network_id, 0x5678);
+                assert_eq!(version, 3);
+            }
+            id => panic!("expected EIT, got {:?}", id),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+mod assembler;
 mod bat;
 mod cat;
 mod eit;
@@ -7,12 +8,19 @@ mod pmt;
 mod sdt;
 mod traits;
 
+pub use self::assembler::SectionAssembler;
+#[allow(unused_imports)]
 pub use self::bat::BAT;
+#[allow(unused_imports)]
 pub use self::cat::CAT;
-pub use self::eit::EIT;
+pub use self::eit::{Event as EITEvent, EventInfo, ShortEvent, EIT};
 pub use self::nit::NIT;
-pub use self::pat::PAT;
-pub use self::pmt::PMT;
+pub use self::pat::{Program as PATProgram, PAT};
+pub use self::pmt::{Stream as PMTStream, PMT};
 pub use self::sdt::SDT;
-pub use self::traits::{Bufer, Cursor, Szer, TryNewer};
-pub use self::traits::{WithHeader, WithSyntaxSection};
+pub use self::traits::{Bufer, Cursor, DescriptorContext, Szer, TryNewer};
+pub use self::traits::{WithCRC32, WithHeader, WithSyntaxSection, WithTableIDExtension};
+#[allow(unused_imports)]
+pub(crate) use self::traits::{
+    HEADER_MAX_PRIVATE_SECTION_LENGTH, HEADER_MAX_SECTION_LENGTH, HEADER_SZ,
+};
@@ -1,7 +1,339 @@
+use core::convert::TryFrom;
+use core::fmt;
+
+use crate::descriptor::Descriptor;
+use crate::error::{Error, Kind as ErrorKind};
+use crate::reader::be_u16;
+use crate::result::Result;
+
+use super::traits::*;
+
 /// ETSI EN 300 468 V1.15.1
 ///
 /// Network Information Table
-#[allow(dead_code)]
+#[derive(Clone, Copy)]
 pub struct NIT<'buf> {
     buf: &'buf [u8],
 }
+
+impl<'buf> NIT<'buf> {
+    const HEADER_SPECIFIC_SZ: usize = 2;
+    const HEADER_FULL_SZ: usize = HEADER_SZ + SYNTAX_SECTION_SZ + Self::HEADER_SPECIFIC_SZ;
+    /// transport-stream-loop-length
+    const LOOP_HEADER_SZ: usize = 2;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> NIT<'buf> {
+        NIT { buf }
+    }
+
+    #[inline(always)]
+    pub fn try_new(buf: &'buf [u8]) -> Result<NIT<'buf>> {
+        let s = Self::new(buf);
+        s.validate()?;
+        Ok(s)
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        validate_syntax_section(self.buf, Self::HEADER_FULL_SZ)?;
+
+        let sz = self.pos_streams_header() + Self::LOOP_HEADER_SZ;
+        if self.buf.len() < sz {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), sz)))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    fn pos_streams_header(&self) -> usize {
+        Self::HEADER_FULL_SZ + usize::from(self.network_descriptors_length())
+    }
+
+    /// seek
+    #[inline(always)]
+    fn buf_descriptors(&self) -> &'buf [u8] {
+        &self.buf[Self::HEADER_FULL_SZ..self.pos_streams_header()]
+    }
+
+    #[inline(always)]
+    pub fn descriptors(&self) -> Option<Cursor<'buf, Descriptor<'buf>>> {
+        if self.network_descriptors_length() != 0 {
+            Some(Cursor::new(self.buf_descriptors()))
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    pub fn transport_stream_loop_length(&self) -> u16 {
+        let b = &self.buf[self.pos_streams_header()..];
+        be_u16(b) & 0x0FFF
+    }
+
+    /// seek
+    #[inline(always)]
+    fn buf_streams(&self) -> &'buf [u8] {
+        let lft = self.pos_streams_header() + Self::LOOP_HEADER_SZ;
+        let mut rght = lft + (self.transport_stream_loop_length() as usize);
+
+        // CRC
+        let end = (HEADER_SZ + (self.section_length() as usize))
+            .min(self.buf.len())
+            .saturating_sub(CRC32_SZ);
+
+        if rght >= end {
+            rght = end;
+        }
+
+        &self.buf[lft..rght.max(lft)]
+    }
+
+    /// transport stream loop
+    #[inline(always)]
+    pub fn streams(&self) -> Cursor<'buf, Stream<'buf>> {
+        Cursor::new(self.buf_streams())
+    }
+
+    /// network and all transport stream descriptors
+    pub fn descriptors_flat(
+        &self,
+    ) -> impl Iterator<Item = (DescriptorContext, Descriptor<'buf>)> + 'buf {
+        descriptors_in(DescriptorContext::Section, self.descriptors()).chain(
+            self.streams().filter_map(Result::ok).flat_map(|s| {
+                descriptors_in(
+                    DescriptorContext::TransportStream(s.transport_stream_id()),
+                    s.descriptors(),
+                )
+            }),
+        )
+    }
+
+    #[inline(always)]
+    pub fn network_id(&self) -> u16 {
+        self.table_id_extension()
+    }
+}
+
+trait WithNITHeaderSpecific<'buf>: Bufer<'buf> {
+    /// buffer seeked
+    #[inline(always)]
+    fn b(&self) -> &'buf [u8] {
+        &self.buf()[HEADER_SZ + SYNTAX_SECTION_SZ..]
+    }
+
+    #[inline(always)]
+    fn network_descriptors_length(&self) -> u16 {
+        be_u16(self.b()) & 0x0FFF
+    }
+}
+
+impl<'buf> TryFrom<&'buf [u8]> for NIT<'buf> {
+    type Error = Error;
+
+    #[inline(always)]
+    fn try_from(buf: &'buf [u8]) -> Result<Self> {
+        Self::try_new(buf)
+    }
+}
+
+impl<'buf> Bufer<'buf> for NIT<'buf> {
+    fn buf(&self) -> &'buf [u8] {
+        self.buf
+    }
+}
+
+impl<'buf> WithHeader<'buf> for NIT<'buf> {}
+impl<'buf> WithTableIDExtension<'buf> for NIT<'buf> {}
+impl<'buf> WithSyntaxSection<'buf> for NIT<'buf> {}
+impl<'buf> WithNITHeaderSpecific<'buf> for NIT<'buf> {}
+impl<'buf> WithCRC32<'buf> for NIT<'buf> {}
+
+impl<'buf> fmt::Debug for NIT<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Err(err) = self.validate() {
+            return write!(f, ":NIT (:error {:?})", err);
+        }
+
+        write!(
+            f,
+            ":NIT (:table-id {:?} :network-id {} :version {} :section {}/{})",
+            self.table_id(),
+            self.network_id(),
+            self.version_number(),
+            self.section_number(),
+            self.last_section_number(),
+        )?;
+
+        write!(f, "\n  :descriptors")?;
+        match self.descriptors() {
+            Some(descs) => {
+                for d in descs.filter_map(Result::ok) {
+                    write!(f, "\n    ")?;
+                    d.fmt(f)?;
+                }
+            }
+            None => write!(f, " ~")?,
+        }
+
+        write!(f, "\n  :streams")?;
+        for p in self.streams().filter_map(Result::ok) {
+            write!(f, "\n    ")?;
+            p.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// transport stream loop entry
+pub struct Stream<'buf> {
+    buf: &'buf [u8],
+}
+
+impl<'buf> Stream<'buf> {
+    const HEADER_SZ: usize = 6;
+
+    #[inline(always)]
+    pub fn new(buf: &'buf [u8]) -> Stream<'buf> {
+        Stream { buf }
+    }
+
+    #[inline(always)]
+    pub fn validate(&self) -> Result<()> {
+        if self.buf.len() < Self::HEADER_SZ {
+            Err(Error::new(ErrorKind::Buf(self.buf.len(), Self::HEADER_SZ)))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    pub fn transport_stream_id(&self) -> u16 {
+        be_u16(self.buf)
+    }
+
+    #[inline(always)]
+    pub fn original_network_id(&self) -> u16 {
+        be_u16(&self.buf[2..])
+    }
+
+    #[inline(always)]
+    pub fn transport_descriptors_length(&self) -> u16 {
+        be_u16(&self.buf[4..]) & 0x0FFF
+    }
+
+    /// seek
+    #[inline(always)]
+    fn buf_descriptors(&self) -> &'buf [u8] {
+        let lft = Self::HEADER_SZ;
+        let mut rght = lft + (self.transport_descriptors_length() as usize);
+
+        if rght >= self.buf.len() {
+            rght = self.buf.len();
+        }
+
+        &self.buf[lft..rght]
+    }
+
+    #[inline(always)]
+    pub fn descriptors(&self) -> Option<Cursor<'buf, Descriptor<'buf>>> {
+        if self.transport_descriptors_length() != 0 {
+            Some(Cursor::new(self.buf_descriptors()))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'buf> Szer for Stream<'buf> {
+    #[inline(always)]
+    fn sz(&self) -> usize {
+        Self::HEADER_SZ + (self.transport_descriptors_length() as usize)
+    }
+}
+
+impl<'buf> TryNewer<'buf> for Stream<'buf> {
+    #[inline(always)]
+    fn try_new(buf: &'buf [u8]) -> Result<Stream<'buf>> {
+        let s = Stream::new(buf);
+        s.validate()?;
+        Ok(s)
+    }
+}
+
+impl<'buf> fmt::Debug for Stream<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ":stream (:transport-stream-id {} :original-network-id {})",
+            self.transport_stream_id(),
+            self.original_network_id(),
+        )?;
+
+        write!(f, "\n      :descriptors")?;
+        match self.descriptors() {
+            Some(descs) => {
+                for d in descs.filter_map(Result::ok) {
+                    write!(f, "\n        ")?;
+                    d.fmt(f)?;
+                }
+            }
+            None => write!(f, " ~")?,
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NIT;
+    use crate::error::{Error, Kind as ErrorKind};
+    use crate::result::Result;
+    use crate::section::WithSyntaxSection;
+
+    #[rustfmt::skip]
+    const NIT_RAW: [u8; 33] = [
+        0x40, 0xF0, 0x1C, 0x00, 0x01, 0xC3, 0x00, 0x00,
+        // network descriptors: network name "N"
+        0xF0, 0x03, 0x40, 0x01, b'N',
+        // transport stream loop
+        0xF0, 0x0C,
+        // tsid 0x0002, onid 0x0001 + private data specifier
+        0x00, 0x02, 0x00, 0x01, 0xF0, 0x06,
+        0x5F, 0x04, 0x00, 0x00, 0x00, 0x28,
+        // crc
+        0x00, 0x00, 0x00, 0x00,
+        // stuffing
+        0xFF, 0xFF,
+    ];
+
+    #[test]
+    fn nit() {
+        let nit = NIT::try_new(&NIT_RAW).unwrap();
+
+        assert_eq!(nit.network_id(), 1);
+        assert_eq!(nit.version_number(), 1);
+        assert_eq!(nit.descriptors().unwrap().count(), 1);
+
+        let streams = nit.streams().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(streams.len(), 1);
+        assert_eq!(streams[0].transport_stream_id(), 2);
+        assert_eq!(streams[0].original_network_id(), 1);
+        assert_eq!(streams[0].descriptors().unwrap().count(), 1);
+    }
+
+    #[test]
+    fn truncated() {
+        assert_eq!(
+            NIT::try_new(&NIT_RAW[..9]).err(),
+            Some(Error::new(ErrorKind::Buf(9, 10)))
+        );
+        assert_eq!(
+            NIT::try_new(&NIT_RAW[..14]).err(),
+            Some(Error::new(ErrorKind::Buf(14, 15)))
+        );
+    }
+}
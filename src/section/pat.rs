@@ -1,7 +1,9 @@
-use std::fmt;
+use core::convert::TryFrom;
+use core::fmt;
 
-use crate::error::{Error, Kind as ErrorKind};
+use crate::error::Error;
 use crate::pid::PID as TsPID;
+use crate::reader::be_u16;
 use crate::result::Result;
 use crate::subtable_id::{SubtableID, SubtableIDer};
 
@@ -10,12 +12,13 @@ use super::traits::*;
 /// ISO/IEC 13818-1
 ///
 /// Program association Table
+#[derive(Clone, Copy)]
 pub struct PAT<'buf> {
     buf: &'buf [u8],
 }
 
 impl<'buf> PAT<'buf> {
-    const HEADER_FULL_SZ: usize = HEADER_SZ + SYNTAX_SECTION_SZ;
+    pub(crate) const HEADER_FULL_SZ: usize = HEADER_SZ + SYNTAX_SECTION_SZ;
 
     #[inline(always)]
     pub fn new(buf: &'buf [u8]) -> PAT<'buf> {
@@ -31,14 +34,7 @@ impl<'buf> PAT<'buf> {
 
     #[inline(always)]
     pub fn validate(&self) -> Result<()> {
-        if self.buf.len() < Self::HEADER_FULL_SZ {
-            Err(Error::new(ErrorKind::Buf(
-                self.buf.len(),
-                Self::HEADER_FULL_SZ,
-            )))
-        } else {
-            Ok(())
-        }
+        validate_syntax_section(self.buf, Self::HEADER_FULL_SZ)
     }
 
     /// slice buf
@@ -57,18 +53,23 @@ impl<'buf> PAT<'buf> {
     }
 
     #[inline(always)]
-    pub fn programs(&self) -> Cursor<'buf, Program> {
+    pub fn programs(&self) -> Cursor<'buf, Program<'buf>> {
         Cursor::new(self.buf_programs())
     }
 
     pub fn first_program_map_pid(&self) -> Option<u16> {
-        self.programs().next().and_then(|res| match res {
-            Ok(p) => match p.pid() {
-                PID::ProgramMap(v) => Some(v),
+        self.program_map_pids().next().map(|(_, pid)| pid)
+    }
+
+    /// (program-number, program-map-pid) of every program;
+    /// network PID (program-number 0) is skipped
+    pub fn program_map_pids(&self) -> impl Iterator<Item = (u16, u16)> + 'buf {
+        self.programs()
+            .filter_map(Result::ok)
+            .filter_map(|p| match p.pid() {
+                PID::ProgramMap(v) => Some((p.number(), v)),
                 _ => None,
-            },
-            _ => None,
-        })
+            })
     }
 
     #[inline(always)]
@@ -77,6 +78,15 @@ impl<'buf> PAT<'buf> {
     }
 }
 
+impl<'buf> TryFrom<&'buf [u8]> for PAT<'buf> {
+    type Error = Error;
+
+    #[inline(always)]
+    fn try_from(buf: &'buf [u8]) -> Result<Self> {
+        Self::try_new(buf)
+    }
+}
+
 impl<'buf> Bufer<'buf> for PAT<'buf> {
     fn buf(&self) -> &'buf [u8] {
         self.buf
@@ -118,7 +128,7 @@ impl<'buf> SubtableIDer for PAT<'buf> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum PID {
     Network(u16),
 
@@ -128,10 +138,7 @@ pub enum PID {
 impl PID {
     #[inline(always)]
     pub fn is_program_map(&self) -> bool {
-        match self {
-            PID::ProgramMap(..) => true,
-            _ => false,
-        }
+        matches!(self, PID::ProgramMap(..))
     }
 }
 
@@ -158,12 +165,12 @@ impl<'buf> Program<'buf> {
 
     #[inline(always)]
     pub fn number(&self) -> u16 {
-        (u16::from(self.buf[0]) << 8) | u16::from(self.buf[1])
+        be_u16(self.buf)
     }
 
     #[inline(always)]
     pub fn pid_raw(&self) -> u16 {
-        (u16::from(self.buf[2] & 0b0001_1111) << 8) | u16::from(self.buf[3])
+        be_u16(&self.buf[2..]) & 0x1FFF
     }
 
     #[inline(always)]
@@ -201,3 +208,72 @@ impl<'buf> fmt::Debug for Program<'buf> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PAT;
+
+    #[rustfmt::skip]
+    const RAW: [u8; 24] = [
+        0x00, 0xB0, 0x15, 0x00, 0x01, 0xC1, 0x00, 0x00,
+        // network pid 0x10
+        0x00, 0x00, 0xE0, 0x10,
+        // program 1 -> 0x100
+        0x00, 0x01, 0xE1, 0x00,
+        // program 2 -> 0x200
+        0x00, 0x02, 0xE2, 0x00,
+        // crc
+        0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn program_map_pids() {
+        let pat = PAT::try_new(&RAW).unwrap();
+
+        assert_eq!(pat.first_program_map_pid(), Some(0x100));
+        assert_eq!(
+            pat.program_map_pids().collect::<Vec<_>>(),
+            vec![(1, 0x100), (2, 0x200)]
+        );
+    }
+
+    #[test]
+    fn header_fields() {
+        use crate::{WithHeader, WithSyntaxSection, WithTableIDExtension};
+
+        let pat = PAT::try_new(&RAW).unwrap();
+
+        assert_eq!(pat.section_length(), 0x15);
+        assert_eq!(pat.table_id_extension(), 1);
+        assert_eq!(pat.version_number(), 0);
+        assert!(pat.current_next_indicator());
+        assert_eq!(pat.section_number(), 0);
+        assert_eq!(pat.last_section_number(), 0);
+    }
+
+    #[test]
+    fn try_from() {
+        use core::convert::TryFrom;
+
+        fn parse<'buf, T: TryFrom<&'buf [u8]>>(buf: &'buf [u8]) -> Option<T> {
+            T::try_from(buf).ok()
+        }
+
+        let pat: PAT = parse(&RAW).unwrap();
+        assert_eq!(pat.first_program_map_pid(), Some(0x100));
+
+        assert!(parse::<PAT>(&RAW[..4]).is_none());
+    }
+
+    #[test]
+    fn copy_view() {
+        let pat = PAT::try_new(&RAW).unwrap();
+
+        // views share the borrowed section
+        let views = [pat, pat];
+        assert!(views
+            .iter()
+            .all(|v| v.first_program_map_pid() == Some(0x100)));
+        assert_eq!(pat.program_map_pids().count(), 2);
+    }
+}
@@ -1,6 +1,12 @@
-use std::fmt;
-
-use crate::descriptor::Descriptor;
+use core::convert::TryFrom;
+use core::fmt;
+
+use crate::codec::Codec;
+use crate::descriptor::{Desc0x05, Desc0x0A, Descriptor};
+use crate::error::{Error, Kind as ErrorKind};
+use crate::iso_639::ISO639;
+use crate::pid::PID;
+use crate::reader::be_u16;
 use crate::result::Result;
 use crate::stream_type::StreamType;
 use crate::subtable_id::{SubtableID, SubtableIDer};
@@ -10,13 +16,15 @@ use super::traits::*;
 /// ISO/IEC 13818-1
 ///
 /// Program Map Table
+#[derive(Clone, Copy)]
 pub struct PMT<'buf> {
     buf: &'buf [u8],
 }
 
 impl<'buf> PMT<'buf> {
     const HEADER_SPECIFIC_SZ: usize = 4;
-    const HEADER_FULL_SZ: usize = HEADER_SZ + SYNTAX_SECTION_SZ + Self::HEADER_SPECIFIC_SZ;
+    pub(crate) const HEADER_FULL_SZ: usize =
+        HEADER_SZ + SYNTAX_SECTION_SZ + Self::HEADER_SPECIFIC_SZ;
 
     #[inline(always)]
     pub fn new(buf: &'buf [u8]) -> PMT<'buf> {
@@ -32,7 +40,7 @@ impl<'buf> PMT<'buf> {
 
     #[inline(always)]
     pub fn validate(&self) -> Result<()> {
-        Ok(())
+        validate_syntax_section(self.buf, Self::HEADER_FULL_SZ)
     }
 
     /// seek
@@ -60,7 +68,7 @@ impl<'buf> PMT<'buf> {
     }
 
     #[inline(always)]
-    pub fn descriptors(&self) -> Option<Cursor<'buf, Descriptor>> {
+    pub fn descriptors(&self) -> Option<Cursor<'buf, Descriptor<'buf>>> {
         if self.program_info_length() != 0 {
             Some(Cursor::new(self.buf_descriptors()))
         } else {
@@ -69,14 +77,32 @@ impl<'buf> PMT<'buf> {
     }
 
     #[inline(always)]
-    pub fn streams(&self) -> Cursor<'buf, Stream> {
+    pub fn streams(&self) -> Cursor<'buf, Stream<'buf>> {
         Cursor::new(self.buf_streams())
     }
 
+    /// program-info and all elementary stream descriptors
+    pub fn descriptors_flat(
+        &self,
+    ) -> impl Iterator<Item = (DescriptorContext, Descriptor<'buf>)> + 'buf {
+        descriptors_in(DescriptorContext::Section, self.descriptors()).chain(
+            self.streams()
+                .filter_map(Result::ok)
+                .flat_map(|s| descriptors_in(DescriptorContext::Stream(s.pid()), s.descriptors())),
+        )
+    }
+
     #[inline(always)]
     pub fn program_number(&self) -> u16 {
         self.table_id_extension()
     }
+
+    /// PID carrying program PCR;
+    /// 0x1FFF - no PCR (e.g. private stream program)
+    #[inline(always)]
+    pub fn pcr_pid(&self) -> PID {
+        PID::from(self.pcr_pid_raw())
+    }
 }
 
 trait WithPMTHeaderSpecific<'buf>: Bufer<'buf> {
@@ -87,13 +113,22 @@ trait WithPMTHeaderSpecific<'buf>: Bufer<'buf> {
     }
 
     #[inline(always)]
-    fn pcr_pid(&self) -> u16 {
-        u16::from(self.b()[0] & 0b0001_1111) | u16::from(self.b()[1])
+    fn pcr_pid_raw(&self) -> u16 {
+        be_u16(self.b()) & 0x1FFF
     }
 
     #[inline(always)]
     fn program_info_length(&self) -> u16 {
-        u16::from(self.b()[2] & 0b0000_1111) | u16::from(self.b()[3])
+        be_u16(&self.b()[2..]) & 0x0FFF
+    }
+}
+
+impl<'buf> TryFrom<&'buf [u8]> for PMT<'buf> {
+    type Error = Error;
+
+    #[inline(always)]
+    fn try_from(buf: &'buf [u8]) -> Result<Self> {
+        Self::try_new(buf)
     }
 }
 
@@ -115,7 +150,7 @@ impl<'buf> fmt::Debug for PMT<'buf> {
             f,
             ":PMT (:id {:?} :pcr-pid {})",
             self.subtable_id(),
-            self.pcr_pid(),
+            self.pcr_pid_raw(),
         )?;
 
         write!(f, "\n  :descriptors")?;
@@ -163,18 +198,18 @@ impl<'buf> Stream<'buf> {
     }
 
     #[inline(always)]
-    fn stream_type(&self) -> StreamType {
+    pub fn stream_type(&self) -> StreamType {
         StreamType::from(self.buf[0])
     }
 
     #[inline(always)]
     pub fn pid(&self) -> u16 {
-        (u16::from(self.buf[1] & 0b0001_1111) << 8) | u16::from(self.buf[2])
+        be_u16(&self.buf[1..]) & 0x1FFF
     }
 
     #[inline(always)]
     fn es_info_length(&self) -> u16 {
-        (u16::from(self.buf[3] & 0b0000_1111) << 8) | u16::from(self.buf[4])
+        be_u16(&self.buf[3..]) & 0x0FFF
     }
 
     /// seek
@@ -191,13 +226,57 @@ impl<'buf> Stream<'buf> {
     }
 
     #[inline(always)]
-    pub fn descriptors(&self) -> Option<Cursor<'buf, Descriptor>> {
+    pub fn descriptors(&self) -> Option<Cursor<'buf, Descriptor<'buf>>> {
         if self.es_info_length() != 0 {
             Some(Cursor::new(self.buf_descriptors()))
         } else {
             None
         }
     }
+
+    /// first language of ISO 639 language descriptor
+    #[inline(always)]
+    pub fn language(&self) -> Option<ISO639> {
+        self.descriptors()?
+            .filter_map(Result::ok)
+            .filter(|d| d.tag().is_iso_639())
+            .find_map(|d| {
+                Desc0x0A::new(d.buf_data())
+                    .languages()
+                    .filter_map(Result::ok)
+                    .map(|l| l.iso_639_language_code())
+                    .next()
+            })
+    }
+
+    /// registration descriptor format-identifier;
+    /// real codec of private data stream-type (e.g. "Opus", "AC-4")
+    #[inline(always)]
+    pub fn format_identifier(&self) -> Option<[u8; 4]> {
+        self.descriptors()?
+            .filter_map(Result::ok)
+            .filter(|d| d.tag().is_registration())
+            .find_map(|d| Desc0x05::try_new(d.buf_data()).ok())
+            .map(|desc| desc.format_identifier())
+    }
+
+    /// stream-type refined for private data stream-types:
+    /// registration format-identifier first, DVB descriptors next
+    pub fn codec(&self) -> Codec {
+        let codec = Codec::from(self.stream_type());
+        if !codec.is_other() {
+            return codec;
+        }
+
+        self.format_identifier()
+            .and_then(|fourcc| Codec::from_format_identifier(&fourcc))
+            .or_else(|| {
+                self.descriptors()?
+                    .filter_map(Result::ok)
+                    .find_map(|d| Codec::from_dvb_descriptor(&d))
+            })
+            .unwrap_or(codec)
+    }
 }
 
 impl<'buf> Szer for Stream<'buf> {
@@ -210,6 +289,10 @@ impl<'buf> Szer for Stream<'buf> {
 impl<'buf> TryNewer<'buf> for Stream<'buf> {
     #[inline(always)]
     fn try_new(buf: &'buf [u8]) -> Result<Stream<'buf>> {
+        if buf.len() < Self::HEADER_SZ {
+            return Err(Error::new(ErrorKind::Buf(buf.len(), Self::HEADER_SZ)));
+        }
+
         let p = Stream::new(buf);
         Ok(p)
     }
@@ -238,3 +321,205 @@ impl<'buf> fmt::Debug for Stream<'buf> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::PMT;
+    use crate::codec::Codec;
+    use crate::descriptor::{Tag, TagDVB};
+    use crate::error::{Error, Kind as ErrorKind};
+    use crate::pid::PID;
+    use crate::reader::be_u32;
+    use crate::section::DescriptorContext;
+    use crate::section::WithCRC32;
+    use crate::stream_type::StreamType;
+    use crate::synthetic;
+
+    #[test]
+    fn streams() {
+        let raw = synthetic::pmt(7, 0x1FF, &[(0x1B, 0x101), (0x03, 0x102)]);
+        let pmt = PMT::try_new(&raw).unwrap();
+
+        assert_eq!(pmt.program_number(), 7);
+        assert_eq!(pmt.pcr_pid(), PID::Other(0x1FF));
+
+        let streams = pmt.streams().map(Result::unwrap).collect::<Vec<_>>();
+        assert_eq!(streams.len(), 2);
+        assert_eq!(streams[0].pid(), 0x101);
+        assert_eq!(streams[0].stream_type(), StreamType::H264);
+        assert_eq!(streams[1].pid(), 0x102);
+        assert_eq!(streams[1].stream_type(), StreamType::MPEG1Audio);
+
+        let kinds = streams
+            .iter()
+            .map(|s| s.stream_type())
+            .collect::<HashSet<_>>();
+        assert!(kinds.contains(&StreamType::MPEG1Audio));
+        assert_eq!(kinds.len(), 2);
+    }
+
+    #[test]
+    fn stream_language() {
+        #[rustfmt::skip]
+        let body = [
+            0xE1, 0x01, 0xF0, 0x00,
+            // h264 @ 0x101
+            0x1B, 0xE1, 0x01, 0xF0, 0x00,
+            // mp2 @ 0x102 + stream-identifier + iso-639 "eng" "deu"
+            0x03, 0xE1, 0x02, 0xF0, 0x0D,
+            0x52, 0x01, 0x01,
+            0x0A, 0x08, b'e', b'n', b'g', 0x00, b'd', b'e', b'u', 0x00,
+        ];
+        let raw = synthetic::section(0x02, 1, 0, (0, 0), &body);
+        let pmt = PMT::new(&raw);
+
+        let languages = pmt
+            .streams()
+            .map(|s| s.unwrap().language().map(|l| l.to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(languages, vec![None, Some("eng".to_string())]);
+    }
+
+    #[test]
+    fn stream_format_identifier() {
+        #[rustfmt::skip]
+        let body = [
+            0xE1, 0x01, 0xF0, 0x00,
+            // private data @ 0x101 + data-stream-alignment + registration "Opus"
+            0x06, 0xE1, 0x01, 0xF0, 0x09,
+            0x06, 0x01, 0x01,
+            0x05, 0x04, b'O', b'p', b'u', b's',
+            // mp2 @ 0x102
+            0x03, 0xE1, 0x02, 0xF0, 0x00,
+        ];
+        let raw = synthetic::section(0x02, 1, 0, (0, 0), &body);
+        let pmt = PMT::new(&raw);
+
+        let ids = pmt
+            .streams()
+            .map(|s| s.unwrap().format_identifier())
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, vec![Some(*b"Opus"), None]);
+    }
+
+    #[test]
+    fn stream_codec() {
+        #[rustfmt::skip]
+        let body = [
+            0xE1, 0x01, 0xF0, 0x00,
+            // private data @ 0x101 + registration "Opus"
+            0x06, 0xE1, 0x01, 0xF0, 0x06,
+            0x05, 0x04, b'O', b'p', b'u', b's',
+            // private data @ 0x102 + AC-3 descriptor
+            0x06, 0xE1, 0x02, 0xF0, 0x03,
+            0x6A, 0x01, 0x00,
+            // h264 @ 0x103 + registration "HEVC" - stream-type wins
+            0x1B, 0xE1, 0x03, 0xF0, 0x06,
+            0x05, 0x04, b'H', b'E', b'V', b'C',
+            // private data @ 0x104
+            0x06, 0xE1, 0x04, 0xF0, 0x00,
+        ];
+        let raw = synthetic::section(0x02, 1, 0, (0, 0), &body);
+        let pmt = PMT::new(&raw);
+
+        let codecs = pmt
+            .streams()
+            .map(|s| s.unwrap().codec())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            codecs,
+            vec![
+                Codec::Opus,
+                Codec::AC3,
+                Codec::H264,
+                Codec::Other(StreamType::MPEG2PacketizedData)
+            ]
+        );
+    }
+
+    #[test]
+    fn crc32() {
+        let mut raw = synthetic::pmt(1, 0x101, &[(0x1B, 0x101)]);
+        let pmt = PMT::new(&raw);
+
+        assert!(pmt.is_crc32_valid());
+        assert_eq!(pmt.crc32(), Some(be_u32(&raw[raw.len() - 4..])));
+
+        raw[12] ^= 0x01;
+        assert!(!PMT::new(&raw).is_crc32_valid());
+
+        // truncated
+        assert!(!PMT::new(&raw[..raw.len() - 1]).is_crc32_valid());
+        assert_eq!(PMT::new(&raw[..raw.len() - 1]).crc32(), None);
+    }
+
+    #[test]
+    fn section_syntax_indicator() {
+        let mut raw = synthetic::pmt(1, 0x101, &[(0x1B, 0x101)]);
+        assert!(PMT::try_new(&raw).is_ok());
+
+        assert_eq!(
+            PMT::try_new(&raw[..8]).err(),
+            Some(Error::new(ErrorKind::Buf(8, 12)))
+        );
+
+        // short form: not a PMT
+        raw[1] &= 0x7F;
+        assert_eq!(
+            PMT::try_new(&raw).err(),
+            Some(Error::new(ErrorKind::SectionSyntaxIndicatorNotSet))
+        );
+    }
+
+    #[test]
+    fn descriptors_flat() {
+        #[rustfmt::skip]
+        let body = [
+            // program-info: CA descriptor
+            0xE1, 0x01, 0xF0, 0x06,
+            0x09, 0x04, 0x0B, 0x00, 0xE0, 0x50,
+            // h264 @ 0x101
+            0x1B, 0xE1, 0x01, 0xF0, 0x00,
+            // ac-3 @ 0x102 + AC-3 + iso-639 "eng"
+            0x06, 0xE1, 0x02, 0xF0, 0x09,
+            0x6A, 0x01, 0x00,
+            0x0A, 0x04, b'e', b'n', b'g', 0x00,
+        ];
+        let raw = synthetic::section(0x02, 1, 0, (0, 0), &body);
+        let pmt = PMT::new(&raw);
+
+        let flat = pmt
+            .descriptors_flat()
+            .map(|(ctx, d)| (ctx, d.tag()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            flat,
+            vec![
+                (DescriptorContext::Section, Tag::CASEMMECMPID),
+                (DescriptorContext::Stream(0x102), Tag::DVB(TagDVB::AC3)),
+                (DescriptorContext::Stream(0x102), Tag::ISO639),
+            ]
+        );
+    }
+    #[test]
+    fn streams_truncated() {
+        let raw = synthetic::pmt(7, 0x101, &[(0x1B, 0x101), (0x03, 0x102)]);
+        // section cut by 3 bytes; last 4 bytes are taken as CRC,
+        // 2 bytes left for the second stream
+        let pmt = PMT::new(&raw[..raw.len() - 3]);
+
+        let streams = pmt.streams().collect::<Vec<_>>();
+        assert_eq!(streams.len(), 2);
+        assert!(streams[0].is_ok());
+        assert_eq!(
+            streams[1].as_ref().err(),
+            Some(&Error::new(ErrorKind::Buf(2, 5)))
+        );
+    }
+}
@@ -1,21 +1,28 @@
-use std::fmt;
+use core::convert::TryFrom;
+use core::fmt;
 
 use crate::descriptor::Descriptor;
+use crate::error::Error;
+use crate::reader::{be_u16, bits};
 use crate::result::Result;
+use crate::running_status::RunningStatus;
 use crate::subtable_id::{SubtableID, SubtableIDer};
+use crate::table_id::TableID;
 
 use super::traits::*;
 
 /// ETSI EN 300 468 V1.15.1
 ///
 /// Service Description Table
+#[derive(Clone, Copy)]
 pub struct SDT<'buf> {
     buf: &'buf [u8],
 }
 
 impl<'buf> SDT<'buf> {
     const HEADER_SPECIFIC_SZ: usize = 3;
-    const HEADER_FULL_SZ: usize = HEADER_SZ + SYNTAX_SECTION_SZ + Self::HEADER_SPECIFIC_SZ;
+    pub(crate) const HEADER_FULL_SZ: usize =
+        HEADER_SZ + SYNTAX_SECTION_SZ + Self::HEADER_SPECIFIC_SZ;
 
     #[inline(always)]
     pub fn new(buf: &'buf [u8]) -> SDT<'buf> {
@@ -31,7 +38,7 @@ impl<'buf> SDT<'buf> {
 
     #[inline(always)]
     pub fn validate(&self) -> Result<()> {
-        Ok(())
+        validate_syntax_section(self.buf, Self::HEADER_FULL_SZ)
     }
 
     /// seek
@@ -50,10 +57,26 @@ impl<'buf> SDT<'buf> {
     }
 
     #[inline(always)]
-    pub fn streams(&self) -> Cursor<'buf, Stream> {
+    pub fn streams(&self) -> Cursor<'buf, Stream<'buf>> {
         Cursor::new(self.buf_streams())
     }
 
+    /// descriptors of all services
+    pub fn descriptors_flat(
+        &self,
+    ) -> impl Iterator<Item = (DescriptorContext, Descriptor<'buf>)> + 'buf {
+        self.streams().filter_map(Result::ok).flat_map(|s| {
+            descriptors_in(DescriptorContext::Service(s.service_id()), s.descriptors())
+        })
+    }
+
+    /// table-id 0x42 - actual transport stream;
+    /// 0x46 - other transport stream
+    #[inline(always)]
+    pub fn is_actual(&self) -> bool {
+        self.table_id() == TableID::ServiceDescriptionSectionActualTransportStream
+    }
+
     #[inline(always)]
     pub fn transport_stream_id(&self) -> u16 {
         self.table_id_extension()
@@ -69,7 +92,16 @@ trait WithSDTHeaderSpecific<'buf>: Bufer<'buf> {
 
     #[inline(always)]
     fn original_network_id(&self) -> u16 {
-        u16::from(self.b()[0]) | u16::from(self.b()[1])
+        be_u16(self.b())
+    }
+}
+
+impl<'buf> TryFrom<&'buf [u8]> for SDT<'buf> {
+    type Error = Error;
+
+    #[inline(always)]
+    fn try_from(buf: &'buf [u8]) -> Result<Self> {
+        Self::try_new(buf)
     }
 }
 
@@ -133,12 +165,12 @@ impl<'buf> Stream<'buf> {
 
     #[inline(always)]
     pub fn service_id(&self) -> u16 {
-        u16::from(self.buf[0]) | u16::from(self.buf[1])
+        be_u16(self.buf)
     }
 
     #[inline(always)]
     pub fn eit_schedule_flag(&self) -> bool {
-        ((self.buf[2] & 0b0000_0010) >> 1) != 0
+        bits(self.buf[2], 1, 1) != 0
     }
 
     #[inline(always)]
@@ -146,10 +178,9 @@ impl<'buf> Stream<'buf> {
         (self.buf[2] & 0b0000_0001) != 0
     }
 
-    // TODO: add enum
     #[inline(always)]
-    pub fn running_status(&self) -> u8 {
-        (self.buf[3] & 0b1110_0000) >> 5
+    pub fn running_status(&self) -> RunningStatus {
+        RunningStatus::from(bits(self.buf[3], 5, 3))
     }
 
     #[inline(always)]
@@ -159,7 +190,7 @@ impl<'buf> Stream<'buf> {
 
     #[inline(always)]
     pub fn descriptors_loop_length(&self) -> u16 {
-        (u16::from(self.buf[3] & 0b0000_1111) << 8) | u16::from(self.buf[4])
+        be_u16(&self.buf[3..]) & 0x0FFF
     }
 
     /// seek
@@ -176,7 +207,7 @@ impl<'buf> Stream<'buf> {
     }
 
     #[inline(always)]
-    pub fn descriptors(&self) -> Option<Cursor<'buf, Descriptor>> {
+    pub fn descriptors(&self) -> Option<Cursor<'buf, Descriptor<'buf>>> {
         if self.descriptors_loop_length() != 0 {
             Some(Cursor::new(self.buf_descriptors()))
         } else {
@@ -223,3 +254,32 @@ impl<'buf> fmt::Debug for Stream<'buf> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SDT;
+    use crate::subtable_id::{SubtableID, SubtableIDer};
+    use crate::synthetic;
+    use crate::table_id::TableID;
+
+    #[test]
+    fn original_network_id() {
+        // original-network-id, reserved-future-use; service 0x0101
+        let body = [0x12, 0x34, 0xFF, 0x01, 0x01, 0xFC, 0x80, 0x00];
+        let raw = synthetic::section(0x42, 0x0203, 5, (0, 0), &body);
+        let sdt = SDT::new(&raw);
+
+        assert!(sdt.is_actual());
+        assert_eq!(sdt.transport_stream_id(), 0x0203);
+        assert_eq!(
+            sdt.subtable_id(),
+            SubtableID::SDT(
+                TableID::ServiceDescriptionSectionActualTransportStream,
+                0x0203,
+                0x1234,
+                5
+            )
+        );
+        assert_eq!(sdt.streams().next().unwrap().unwrap().service_id(), 0x0101);
+    }
+}
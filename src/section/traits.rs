@@ -1,5 +1,9 @@
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
+use crate::crc32;
+use crate::descriptor::Descriptor;
+use crate::error::{Error, Kind as ErrorKind};
+use crate::reader::{be_u16, be_u32, bits};
 use crate::result::Result;
 use crate::table_id::TableID;
 
@@ -9,8 +13,12 @@ pub trait Bufer<'buf> {
 }
 
 pub const HEADER_SZ: usize = 3;
+/// ISO/IEC 13818-1 PSI (PAT, PMT, CAT)
 #[allow(dead_code)]
 pub const HEADER_MAX_SECTION_LENGTH: usize = 0x3FD; // 1021
+/// ETSI EN 300 468 SI (NIT, SDT, EIT, BAT) and private sections
+#[allow(dead_code)]
+pub const HEADER_MAX_PRIVATE_SECTION_LENGTH: usize = 0xFFD; // 4093
 
 pub trait WithHeader<'buf>: Bufer<'buf> {
     /// buffer seeked
@@ -37,7 +45,7 @@ pub trait WithHeader<'buf>: Bufer<'buf> {
 
     #[inline(always)]
     fn section_length(&self) -> u16 {
-        (u16::from(self.b()[1] & 0b0000_1111) << 8) | u16::from(self.b()[2])
+        be_u16(&self.b()[1..]) & 0x0FFF
     }
 
     /// complete section length
@@ -56,7 +64,7 @@ pub trait WithTableIDExtension<'buf>: Bufer<'buf> {
 
     #[inline(always)]
     fn table_id_extension(&self) -> u16 {
-        (u16::from(self.b()[3]) << 8) | u16::from(self.b()[4])
+        be_u16(&self.b()[3..])
     }
 }
 
@@ -70,9 +78,8 @@ pub trait WithSyntaxSection<'buf>: Bufer<'buf> {
     }
 
     #[inline(always)]
-    #[allow(dead_code)]
     fn version_number(&self) -> u8 {
-        (self.b()[2] & 0b0011_1110) >> 1
+        bits(self.b()[2], 1, 5)
     }
 
     #[inline(always)]
@@ -91,6 +98,20 @@ pub trait WithSyntaxSection<'buf>: Bufer<'buf> {
     }
 }
 
+/// long form section header of sz bytes;
+/// PSI (PAT, PMT) and SI (NIT, SDT, EIT) must have
+/// section-syntax-indicator set - cleared one means
+/// misidentified PID or short form private section
+pub(crate) fn validate_syntax_section(buf: &[u8], sz: usize) -> Result<()> {
+    if buf.len() < sz {
+        Err(Error::new(ErrorKind::Buf(buf.len(), sz)))
+    } else if (buf[1] & 0b1000_0000) == 0 {
+        Err(Error::new(ErrorKind::SectionSyntaxIndicatorNotSet))
+    } else {
+        Ok(())
+    }
+}
+
 pub trait Szer {
     fn sz(&self) -> usize;
 }
@@ -140,17 +161,149 @@ where
             }
         };
 
-        // seek buf
-        if self.buf.len() > row.sz() {
-            self.buf = &self.buf[row.sz()..];
-        } else {
-            self.buf_drain();
+        // seek buf;
+        // zero-sized row makes no progress - stop after it
+        match row.sz() {
+            sz if sz > 0 && sz < self.buf.len() => self.buf = &self.buf[sz..],
+            _ => self.buf_drain(),
         }
 
         Some(Ok(row))
     }
 }
 
+/// descriptor loop the descriptor was found in
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DescriptorContext {
+    /// PMT program-info, NIT network descriptors
+    Section,
+    /// PMT elementary stream (elementary-PID)
+    Stream(u16),
+    /// SDT service (service-id)
+    Service(u16),
+    /// EIT event (event-id)
+    Event(u16),
+    /// NIT transport stream (transport-stream-id)
+    TransportStream(u16),
+}
+
+/// descriptor loop tagged with context;
+/// malformed descriptors are skipped
+#[inline(always)]
+pub(crate) fn descriptors_in<'buf>(
+    ctx: DescriptorContext,
+    descs: Option<Cursor<'buf, Descriptor<'buf>>>,
+) -> impl Iterator<Item = (DescriptorContext, Descriptor<'buf>)> + 'buf {
+    descs
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(move |d| (ctx, d))
+}
+
 pub const CRC32_SZ: usize = 4;
 
-pub(crate) trait WithCRC32<'buf>: Bufer<'buf> {}
+pub trait WithCRC32<'buf>: Bufer<'buf> {
+    /// complete section (header, data, CRC);
+    /// None - buffer is truncated
+    #[inline(always)]
+    fn buf_crc32_section(&self) -> Option<&'buf [u8]> {
+        let buf = self.buf();
+        if buf.len() < HEADER_SZ {
+            return None;
+        }
+
+        let sz = HEADER_SZ + usize::from(be_u16(&buf[1..]) & 0x0FFF);
+        if sz < HEADER_SZ + CRC32_SZ || sz > buf.len() {
+            return None;
+        }
+
+        Some(&buf[..sz])
+    }
+
+    /// CRC_32 field
+    #[inline(always)]
+    fn crc32(&self) -> Option<u32> {
+        self.buf_crc32_section()
+            .map(|section| be_u32(&section[section.len() - CRC32_SZ..]))
+    }
+
+    /// CRC over the whole section (CRC field included) is zero
+    #[inline(always)]
+    fn is_crc32_valid(&self) -> bool {
+        self.buf_crc32_section()
+            .is_some_and(|section| crc32::crc32(section) == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cursor, Szer, TryNewer};
+    use crate::descriptor::Descriptor;
+    use crate::error::{Error, Kind as ErrorKind};
+    use crate::result::Result;
+
+    /// row with broken zero size
+    struct Zero;
+
+    impl Szer for Zero {
+        fn sz(&self) -> usize {
+            0
+        }
+    }
+
+    impl<'buf> TryNewer<'buf> for Zero {
+        fn try_new(_: &'buf [u8]) -> Result<Zero> {
+            Ok(Zero)
+        }
+    }
+
+    #[test]
+    fn cursor_zero_sz() {
+        let cursor: Cursor<Zero> = Cursor::new(&[0x00, 0x01, 0x02]);
+
+        assert_eq!(cursor.take(10).count(), 1);
+    }
+
+    #[test]
+    fn cursor_zero_length_descriptor() {
+        // service, zero-length stuffing, service
+        #[rustfmt::skip]
+        let raw = [
+            0x48, 0x03, 0x01, 0x00, 0x00,
+            0x42, 0x00,
+            0x48, 0x03, 0x02, 0x00, 0x00,
+        ];
+        let cursor: Cursor<Descriptor> = Cursor::new(&raw);
+
+        let tags = cursor
+            .take(10)
+            .map(|d| u8::from(d.unwrap().tag()))
+            .collect::<Vec<_>>();
+        assert_eq!(tags, vec![0x48, 0x42, 0x48]);
+    }
+
+    #[test]
+    fn cursor_descriptor_overrun() {
+        // service, then length 0x10 with 2 bytes left in the loop
+        let raw = [0x48, 0x03, 0x01, 0x00, 0x00, 0x48, 0x10, 0x01, 0x00];
+        let mut cursor: Cursor<Descriptor> = Cursor::new(&raw);
+
+        assert!(cursor.next().unwrap().is_ok());
+        assert_eq!(
+            cursor.next().unwrap().err(),
+            Some(Error::new(ErrorKind::Buf(4, 18)))
+        );
+        assert!(cursor.next().is_none());
+
+        // truncated header
+        let mut cursor: Cursor<Descriptor> = Cursor::new(&raw[..6]);
+
+        assert!(cursor.next().unwrap().is_ok());
+        assert_eq!(
+            cursor.next().unwrap().err(),
+            Some(Error::new(ErrorKind::Buf(1, 2)))
+        );
+        assert!(cursor.next().is_none());
+    }
+}
@@ -0,0 +1,330 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::annex_a2::AnnexA2;
+use crate::demuxer::Table;
+use crate::descriptor::{DescDVB0x48, Descriptor, Tag, TagDVB};
+use crate::iso_639::ISO639;
+use crate::result::Result;
+use crate::running_status::RunningStatus;
+use crate::section::{Cursor, WithSyntaxSection, PAT, PMT, SDT};
+use crate::service_type::ServiceType;
+use crate::stream_type::StreamType;
+use crate::subtable_id::SubtableID;
+
+/// elementary stream of a service (PMT stream loop)
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Stream {
+    pub pid: u16,
+
+    /// codec
+    pub stream_type: StreamType,
+
+    /// ISO 639 language descriptor (first language)
+    pub language: Option<ISO639>,
+}
+
+/// service (program) joined across PAT, PMT and SDT
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Service {
+    /// PAT program-number / PMT program-number / SDT service-id
+    pub service_id: u16,
+
+    /// PAT
+    pub pmt_pid: Option<u16>,
+
+    /// SDT service descriptor
+    pub service_type: Option<ServiceType>,
+    pub provider_name: Option<String>,
+    pub service_name: Option<String>,
+
+    /// SDT service
+    pub running_status: Option<RunningStatus>,
+    /// one or more streams controlled by CA system
+    pub free_ca_mode: Option<bool>,
+
+    /// scrambled payload seen on one of PMT streams
+    pub scrambled: bool,
+
+    /// PMT
+    pub streams: Vec<Stream>,
+}
+
+impl Service {
+    fn new(service_id: u16) -> Service {
+        Service {
+            service_id,
+            pmt_pid: None,
+            service_type: None,
+            provider_name: None,
+            service_name: None,
+            running_status: None,
+            free_ca_mode: None,
+            scrambled: false,
+            streams: Default::default(),
+        }
+    }
+}
+
+fn descriptors<'buf>(
+    descs: Option<Cursor<'buf, Descriptor<'buf>>>,
+) -> impl Iterator<Item = Descriptor<'buf>> {
+    descs.into_iter().flatten().filter_map(Result::ok)
+}
+
+/// services catalog;
+/// populated by demuxer on every complete PAT/PMT/SDT
+#[derive(Default)]
+pub struct ServiceCatalog {
+    services: BTreeMap<u16, Service>,
+
+    /// PIDs scrambled packets were already accounted for;
+    /// reset on PAT/PMT update
+    scrambled_pids: BTreeSet<u16>,
+}
+
+impl ServiceCatalog {
+    #[inline(always)]
+    fn entry(&mut self, service_id: u16) -> &mut Service {
+        self.services
+            .entry(service_id)
+            .or_insert_with(|| Service::new(service_id))
+    }
+
+    #[inline(always)]
+    pub fn get(&self, service_id: u16) -> Option<&Service> {
+        self.services.get(&service_id)
+    }
+
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &Service> {
+        self.services.values()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.services.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.services.is_empty()
+    }
+
+    /// complete table
+    pub(crate) fn update(&mut self, id: SubtableID, table: &Table) {
+        let sections = table.sections_bytes().collect::<Vec<_>>();
+        let raws = sections.iter().map(|raw| &raw[..]);
+
+        match id {
+            SubtableID::PAT(..) => self.update_pat(raws.map(PAT::new)),
+            SubtableID::PMT(..) => raws.for_each(|raw| self.update_pmt(&PMT::new(raw))),
+            SubtableID::SDT(..) => raws.for_each(|raw| self.update_sdt(&SDT::new(raw))),
+            SubtableID::EIT(..) => {}
+        }
+    }
+
+    /// all PAT sections;
+    /// programs removed from PAT are removed from catalog
+    pub(crate) fn update_pat<'buf, I>(&mut self, pats: I)
+    where
+        I: Iterator<Item = PAT<'buf>>,
+    {
+        self.scrambled_pids.clear();
+
+        let mut programs = Vec::new();
+
+        for pat in pats {
+            programs.extend(pat.program_map_pids());
+        }
+
+        self.services
+            .retain(|id, s| s.pmt_pid.is_none() || programs.iter().any(|(n, _)| n == id));
+
+        for (number, pid) in programs {
+            self.entry(number).pmt_pid = Some(pid);
+        }
+    }
+
+    pub(crate) fn update_pmt(&mut self, pmt: &PMT) {
+        self.scrambled_pids.clear();
+
+        let service = self.entry(pmt.program_number());
+
+        if pmt.section_number() == 0 {
+            service.streams.clear();
+        }
+
+        for stream in pmt.streams().filter_map(Result::ok) {
+            service.streams.push(Stream {
+                pid: stream.pid(),
+                stream_type: stream.stream_type(),
+                language: stream.language(),
+            });
+        }
+    }
+
+    /// scrambled packet on PID
+    pub(crate) fn mark_scrambled(&mut self, pid: u16) {
+        if !self.scrambled_pids.insert(pid) {
+            return;
+        }
+
+        for service in self.services.values_mut() {
+            if service.streams.iter().any(|s| s.pid == pid) {
+                service.scrambled = true;
+            }
+        }
+    }
+
+    /// SDT actual transport stream only
+    pub(crate) fn update_sdt(&mut self, sdt: &SDT) {
+        if !sdt.is_actual() {
+            return;
+        }
+
+        for stream in sdt.streams().filter_map(Result::ok) {
+            let service = self.entry(stream.service_id());
+
+            service.running_status = Some(stream.running_status());
+            service.free_ca_mode = Some(stream.free_ca_mode());
+
+            for d in descriptors(stream.descriptors())
+                .filter(|d| matches!(d.tag(), Tag::DVB(TagDVB::Service)))
+            {
+                let desc = DescDVB0x48::new(d.buf_data());
+
+                service.service_type = Some(desc.service_type_kind());
+                service.provider_name =
+                    AnnexA2::decode_to_string(desc.service_provider_name()).ok();
+                service.service_name = AnnexA2::decode_to_string(desc.service_name()).ok();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ServiceCatalog;
+    use crate::running_status::RunningStatus;
+    use crate::section::{PAT, PMT, SDT};
+    use crate::service_type::ServiceType;
+    use crate::stream_type::StreamType;
+
+    #[rustfmt::skip]
+    const PAT_RAW: [u8; 16] = [
+        0x00, 0xB0, 0x0D, 0x00, 0x01, 0xC1, 0x00, 0x00,
+        // program 1 -> pmt pid 0x100
+        0x00, 0x01, 0xE1, 0x00,
+        // crc
+        0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[rustfmt::skip]
+    const PMT_RAW: [u8; 32] = [
+        0x02, 0xB0, 0x1D, 0x00, 0x01, 0xC1, 0x00, 0x00,
+        0xE1, 0x01, 0xF0, 0x00,
+        // h264 @ 0x101
+        0x1B, 0xE1, 0x01, 0xF0, 0x00,
+        // mp2 @ 0x102 + iso-639 "eng"
+        0x03, 0xE1, 0x02, 0xF0, 0x06, 0x0A, 0x04, b'e', b'n', b'g', 0x00,
+        // crc
+        0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[rustfmt::skip]
+    const SDT_RAW: [u8; 31] = [
+        0x42, 0xF0, 0x1C, 0x00, 0x01, 0xC1, 0x00, 0x00,
+        0x00, 0x01, 0xFF,
+        // service 1
+        0x00, 0x01, 0xFC, 0x80, 0x0B,
+        // service descriptor
+        0x48, 0x09, 0x01, 0x03, b'A', b'B', b'C', 0x03, b'O', b'n', b'e',
+        // crc
+        0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn join_pat_pmt_sdt() {
+        let mut catalog = ServiceCatalog::default();
+
+        catalog.update_sdt(&SDT::new(&SDT_RAW));
+        catalog.update_pat(vec![PAT::new(&PAT_RAW)].into_iter());
+        catalog.update_pmt(&PMT::new(&PMT_RAW));
+
+        assert_eq!(catalog.len(), 1);
+
+        let service = catalog.get(1).unwrap();
+
+        assert_eq!(service.pmt_pid, Some(0x100));
+        assert_eq!(service.service_type, Some(ServiceType::DigitalTelevision));
+        assert!(service.service_type.unwrap().is_tv());
+        assert_eq!(service.running_status, Some(RunningStatus::Running));
+        assert_eq!(service.free_ca_mode, Some(false));
+        assert!(!service.scrambled);
+        assert_eq!(service.provider_name.as_deref(), Some("ABC"));
+        assert_eq!(service.service_name.as_deref(), Some("One"));
+
+        assert_eq!(service.streams.len(), 2);
+        assert_eq!(service.streams[0].pid, 0x101);
+        assert_eq!(service.streams[0].stream_type, StreamType::H264);
+        assert_eq!(service.streams[0].language, None);
+        assert_eq!(service.streams[1].pid, 0x102);
+        assert_eq!(service.streams[1].stream_type, StreamType::MPEG1Audio);
+        assert_eq!(
+            service.streams[1].language.map(|l| l.to_string()),
+            Some("eng".to_string())
+        );
+    }
+
+    #[test]
+    fn mark_scrambled() {
+        let mut catalog = ServiceCatalog::default();
+
+        catalog.update_pmt(&PMT::new(&PMT_RAW));
+        catalog.mark_scrambled(0x1FF);
+        assert!(!catalog.get(1).unwrap().scrambled);
+
+        catalog.mark_scrambled(0x102);
+        assert!(catalog.get(1).unwrap().scrambled);
+    }
+
+    #[test]
+    fn mark_scrambled_before_pmt() {
+        let mut catalog = ServiceCatalog::default();
+
+        // no stream yet; PMT update makes the PID count again
+        catalog.mark_scrambled(0x102);
+        catalog.update_pmt(&PMT::new(&PMT_RAW));
+        assert!(!catalog.get(1).unwrap().scrambled);
+
+        catalog.mark_scrambled(0x102);
+        assert!(catalog.get(1).unwrap().scrambled);
+    }
+
+    #[test]
+    fn pmt_update_replaces_streams() {
+        let mut catalog = ServiceCatalog::default();
+
+        catalog.update_pmt(&PMT::new(&PMT_RAW));
+        catalog.update_pmt(&PMT::new(&PMT_RAW));
+
+        assert_eq!(catalog.get(1).unwrap().streams.len(), 2);
+    }
+
+    #[test]
+    fn pat_update_removes_programs() {
+        let mut catalog = ServiceCatalog::default();
+
+        catalog.update_pat(vec![PAT::new(&PAT_RAW)].into_iter());
+        assert_eq!(catalog.len(), 1);
+
+        let mut pat_raw = PAT_RAW;
+        pat_raw[9] = 0x02; // program 1 -> program 2
+        catalog.update_pat(vec![PAT::new(&pat_raw)].into_iter());
+
+        assert!(catalog.get(1).is_none());
+        assert!(catalog.get(2).is_some());
+    }
+}
@@ -0,0 +1,134 @@
+/// ETSI EN 300 468 V1.15.1 (6.2.33, table 87)
+///
+/// service descriptor service_type
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ServiceType {
+    DigitalTelevision,
+    DigitalRadioSound,
+    Teletext,
+    NVODReference,
+    NVODTimeShifted,
+    Mosaic,
+    FMRadio,
+    DVBSRM,
+    AdvancedCodecDigitalRadioSound,
+    H264Mosaic,
+    DataBroadcast,
+    RCSMap,
+    RCSFLS,
+    DVBMHP,
+    MPEG2HDDigitalTelevision,
+    AdvancedCodecSDDigitalTelevision,
+    AdvancedCodecSDNVODTimeShifted,
+    AdvancedCodecSDNVODReference,
+    AdvancedCodecHDDigitalTelevision,
+    AdvancedCodecHDNVODTimeShifted,
+    AdvancedCodecHDNVODReference,
+    AdvancedCodecStereoscopicHDDigitalTelevision,
+    AdvancedCodecStereoscopicHDNVODTimeShifted,
+    AdvancedCodecStereoscopicHDNVODReference,
+    HEVCDigitalTelevision,
+
+    UserDefined(u8),
+
+    Reserved(u8),
+}
+
+impl ServiceType {
+    /// television service of any codec / resolution
+    #[inline(always)]
+    pub fn is_tv(&self) -> bool {
+        matches!(
+            self,
+            ServiceType::DigitalTelevision
+                | ServiceType::MPEG2HDDigitalTelevision
+                | ServiceType::AdvancedCodecSDDigitalTelevision
+                | ServiceType::AdvancedCodecHDDigitalTelevision
+                | ServiceType::AdvancedCodecStereoscopicHDDigitalTelevision
+                | ServiceType::HEVCDigitalTelevision
+        )
+    }
+
+    #[inline(always)]
+    pub fn is_radio(&self) -> bool {
+        matches!(
+            self,
+            ServiceType::DigitalRadioSound
+                | ServiceType::FMRadio
+                | ServiceType::AdvancedCodecDigitalRadioSound
+        )
+    }
+}
+
+impl From<u8> for ServiceType {
+    fn from(d: u8) -> Self {
+        match d {
+            0x01 => ServiceType::DigitalTelevision,
+            0x02 => ServiceType::DigitalRadioSound,
+            0x03 => ServiceType::Teletext,
+            0x04 => ServiceType::NVODReference,
+            0x05 => ServiceType::NVODTimeShifted,
+            0x06 => ServiceType::Mosaic,
+            0x07 => ServiceType::FMRadio,
+            0x08 => ServiceType::DVBSRM,
+            0x0A => ServiceType::AdvancedCodecDigitalRadioSound,
+            0x0B => ServiceType::H264Mosaic,
+            0x0C => ServiceType::DataBroadcast,
+            0x0E => ServiceType::RCSMap,
+            0x0F => ServiceType::RCSFLS,
+            0x10 => ServiceType::DVBMHP,
+            0x11 => ServiceType::MPEG2HDDigitalTelevision,
+            0x16 => ServiceType::AdvancedCodecSDDigitalTelevision,
+            0x17 => ServiceType::AdvancedCodecSDNVODTimeShifted,
+            0x18 => ServiceType::AdvancedCodecSDNVODReference,
+            0x19 => ServiceType::AdvancedCodecHDDigitalTelevision,
+            0x1A => ServiceType::AdvancedCodecHDNVODTimeShifted,
+            0x1B => ServiceType::AdvancedCodecHDNVODReference,
+            0x1C => ServiceType::AdvancedCodecStereoscopicHDDigitalTelevision,
+            0x1D => ServiceType::AdvancedCodecStereoscopicHDNVODTimeShifted,
+            0x1E => ServiceType::AdvancedCodecStereoscopicHDNVODReference,
+            0x1F => ServiceType::HEVCDigitalTelevision,
+
+            0x80..=0xFE => ServiceType::UserDefined(d),
+
+            _ => ServiceType::Reserved(d),
+        }
+    }
+}
+
+impl From<ServiceType> for u8 {
+    fn from(st: ServiceType) -> u8 {
+        match st {
+            ServiceType::DigitalTelevision => 0x01,
+            ServiceType::DigitalRadioSound => 0x02,
+            ServiceType::Teletext => 0x03,
+            ServiceType::NVODReference => 0x04,
+            ServiceType::NVODTimeShifted => 0x05,
+            ServiceType::Mosaic => 0x06,
+            ServiceType::FMRadio => 0x07,
+            ServiceType::DVBSRM => 0x08,
+            ServiceType::AdvancedCodecDigitalRadioSound => 0x0A,
+            ServiceType::H264Mosaic => 0x0B,
+            ServiceType::DataBroadcast => 0x0C,
+            ServiceType::RCSMap => 0x0E,
+            ServiceType::RCSFLS => 0x0F,
+            ServiceType::DVBMHP => 0x10,
+            ServiceType::MPEG2HDDigitalTelevision => 0x11,
+            ServiceType::AdvancedCodecSDDigitalTelevision => 0x16,
+            ServiceType::AdvancedCodecSDNVODTimeShifted => 0x17,
+            ServiceType::AdvancedCodecSDNVODReference => 0x18,
+            ServiceType::AdvancedCodecHDDigitalTelevision => 0x19,
+            ServiceType::AdvancedCodecHDNVODTimeShifted => 0x1A,
+            ServiceType::AdvancedCodecHDNVODReference => 0x1B,
+            ServiceType::AdvancedCodecStereoscopicHDDigitalTelevision => 0x1C,
+            ServiceType::AdvancedCodecStereoscopicHDNVODTimeShifted => 0x1D,
+            ServiceType::AdvancedCodecStereoscopicHDNVODReference => 0x1E,
+            ServiceType::HEVCDigitalTelevision => 0x1F,
+
+            ServiceType::UserDefined(d) => d,
+
+            ServiceType::Reserved(d) => d,
+        }
+    }
+}
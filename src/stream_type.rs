@@ -1,6 +1,7 @@
 /// ETSI EN 300 468 V1.15.1 (2016-03)
 /// ISO/IEC 13818-1
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum StreamType {
     MPEG1Video,
     H262,
@@ -177,3 +178,92 @@ impl From<StreamType> for u8 {
         }
     }
 }
+
+/// coarse stream-type grouping for track lists and filters;
+/// private data (0x06) is Data - codec is resolved via descriptors
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum StreamCategory {
+    Video,
+    Audio,
+    Subtitle,
+    Data,
+    Other,
+}
+
+impl StreamType {
+    pub fn category(self) -> StreamCategory {
+        match self {
+            StreamType::MPEG1Video
+            | StreamType::H262
+            | StreamType::MPEG4H263Video
+            | StreamType::MPEG4AuxiliaryVideo
+            | StreamType::H264
+            | StreamType::SVC
+            | StreamType::MVC
+            | StreamType::JPEG2000Video
+            | StreamType::H265
+            | StreamType::ChineseVideoStandard
+            | StreamType::H262DES64CBC => StreamCategory::Video,
+
+            StreamType::MPEG1Audio
+            | StreamType::MPEG2Audio
+            | StreamType::AAC
+            | StreamType::MPEG4LOAS
+            | StreamType::MPEG4RawAudio
+            | StreamType::AC3
+            | StreamType::DolbyTrueHDAudio
+            | StreamType::AC3DolbyDigitalPlus
+            | StreamType::DTS8
+            | StreamType::AC3DolbyDigitalPlus16 => StreamCategory::Audio,
+
+            StreamType::MPEG4Text | StreamType::SCTESubtitle => StreamCategory::Subtitle,
+
+            StreamType::MPEG2TabledData
+            | StreamType::MPEG2PacketizedData
+            | StreamType::MHEG
+            | StreamType::DSMCCInAPacketizedStream
+            | StreamType::H222AuxiliaryData
+            | StreamType::DSMCCMultiprotocolEncapsulation
+            | StreamType::DSMCCUNMessages
+            | StreamType::DSMCCStreamDescriptors
+            | StreamType::DSMCCTabledData
+            | StreamType::ISOIEC138181AuxiliaryData
+            | StreamType::MPEG4FlexMux
+            | StreamType::MPEG4FlexMuxTables
+            | StreamType::DSMCCSynchronizedDownloadProtocol
+            | StreamType::PacketizedMetadata
+            | StreamType::SectionedMetadata
+            | StreamType::DSMCCDataCarouselMetadata
+            | StreamType::DSMCCObjectCarouselMetadata
+            | StreamType::SynchronizedDownloadProtocolMetadata
+            | StreamType::IPMP
+            | StreamType::IPMPDRM
+            | StreamType::SCTE35 => StreamCategory::Data,
+
+            StreamType::Reserved(_) | StreamType::Other(_) => StreamCategory::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StreamCategory, StreamType};
+
+    #[test]
+    fn category() {
+        let category = |d: u8| StreamType::from(d).category();
+
+        assert_eq!(category(0x02), StreamCategory::Video);
+        assert_eq!(category(0x1B), StreamCategory::Video);
+        assert_eq!(category(0x24), StreamCategory::Video);
+        assert_eq!(category(0x0F), StreamCategory::Audio);
+        assert_eq!(category(0x81), StreamCategory::Audio);
+        assert_eq!(category(0x83), StreamCategory::Audio);
+        assert_eq!(category(0x82), StreamCategory::Subtitle);
+        assert_eq!(category(0x06), StreamCategory::Data);
+        assert_eq!(category(0x86), StreamCategory::Data);
+        assert_eq!(category(0x43), StreamCategory::Other);
+        assert_eq!(category(0xC0), StreamCategory::Other);
+    }
+}
@@ -1,5 +1,17 @@
 use crate::table_id::TableID;
 
+/// subtable kind without table-id / ids / version-number
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SubtableKind {
+    PAT,
+    PMT,
+    SDT,
+    EIT,
+    NIT,
+    BAT,
+    CAT,
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum SubtableID {
     /// (table-id, transport-stream-id(ext) [, version-number])
@@ -15,6 +27,81 @@ pub enum SubtableID {
     EIT(TableID, u16, u16, u16, u8),
 }
 
+impl SubtableID {
+    #[inline(always)]
+    pub fn kind(&self) -> SubtableKind {
+        match *self {
+            SubtableID::PAT(..) => SubtableKind::PAT,
+            SubtableID::PMT(..) => SubtableKind::PMT,
+            SubtableID::SDT(..) => SubtableKind::SDT,
+            SubtableID::EIT(..) => SubtableKind::EIT,
+        }
+    }
+
+    #[inline(always)]
+    pub fn table_id(&self) -> TableID {
+        match *self {
+            SubtableID::PAT(t, ..) => t,
+            SubtableID::PMT(t, ..) => t,
+            SubtableID::SDT(t, ..) => t,
+            SubtableID::EIT(t, ..) => t,
+        }
+    }
+
+    #[inline(always)]
+    pub fn version_number(&self) -> u8 {
+        match *self {
+            SubtableID::PAT(_, _, v) => v,
+            SubtableID::PMT(_, _, v) => v,
+            SubtableID::SDT(_, _, _, v) => v,
+            SubtableID::EIT(_, _, _, _, v) => v,
+        }
+    }
+
+    /// same subtable with another version-number
+    #[inline(always)]
+    pub fn with_version_number(self, v: u8) -> SubtableID {
+        match self {
+            SubtableID::PAT(t, ext, _) => SubtableID::PAT(t, ext, v),
+            SubtableID::PMT(t, ext, _) => SubtableID::PMT(t, ext, v),
+            SubtableID::SDT(t, ext, onid, _) => SubtableID::SDT(t, ext, onid, v),
+            SubtableID::EIT(t, ext, tsid, onid, _) => SubtableID::EIT(t, ext, tsid, onid, v),
+        }
+    }
+}
+
 pub trait SubtableIDer {
     fn subtable_id(&self) -> SubtableID;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{SubtableID, SubtableKind};
+    use crate::table_id::TableID;
+
+    #[test]
+    fn kind() {
+        let pmt = SubtableID::PMT(TableID::ProgramMapSection, 1, 3);
+        let eit = SubtableID::EIT(TableID::from(0x50), 1, 2, 3, 4);
+
+        assert_eq!(pmt.kind(), SubtableKind::PMT);
+        assert_eq!(eit.kind(), SubtableKind::EIT);
+        // kind ignores ids and version-number
+        assert_eq!(
+            pmt.kind(),
+            SubtableID::PMT(TableID::ProgramMapSection, 2, 0).kind()
+        );
+    }
+
+    #[test]
+    fn with_version_number() {
+        let id = SubtableID::PMT(TableID::ProgramMapSection, 1, 3);
+
+        assert_eq!(id.version_number(), 3);
+        assert_eq!(
+            id.with_version_number(4),
+            SubtableID::PMT(TableID::ProgramMapSection, 1, 4)
+        );
+        assert_eq!(id.with_version_number(3), id);
+    }
+}
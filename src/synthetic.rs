@@ -0,0 +1,431 @@
+//! synthetic ts-packets for tests
+//!
+//! builds valid packets, sections (with CRC) and PES headers
+//! to feed parsers and demuxer without captured dumps
+pub(crate) use crate::crc32::crc32;
+use crate::packet::Packet;
+
+const SYNC_BYTE: u8 = 0x47;
+
+/// ts-packet header sz
+const HEADER_SZ: usize = 4;
+
+/// single ts-packet
+pub(crate) struct PacketBuilder<'a> {
+    pid: u16,
+    pusi: bool,
+    tei: bool,
+    /// transport-scrambling-control
+    tsc: u8,
+    cc: u8,
+    /// adaptation-field without length byte
+    adaptation: Option<&'a [u8]>,
+    payload: &'a [u8],
+}
+
+impl<'a> PacketBuilder<'a> {
+    pub(crate) fn new(pid: u16) -> PacketBuilder<'a> {
+        PacketBuilder {
+            pid,
+            pusi: false,
+            tei: false,
+            tsc: 0,
+            cc: 0,
+            adaptation: None,
+            payload: &[],
+        }
+    }
+
+    pub(crate) fn pusi(mut self, pusi: bool) -> Self {
+        self.pusi = pusi;
+        self
+    }
+
+    pub(crate) fn tei(mut self, tei: bool) -> Self {
+        self.tei = tei;
+        self
+    }
+
+    pub(crate) fn tsc(mut self, tsc: u8) -> Self {
+        self.tsc = tsc & 0b11;
+        self
+    }
+
+    pub(crate) fn cc(mut self, cc: u8) -> Self {
+        self.cc = cc & 0x0F;
+        self
+    }
+
+    pub(crate) fn adaptation(mut self, adaptation: &'a [u8]) -> Self {
+        self.adaptation = Some(adaptation);
+        self
+    }
+
+    pub(crate) fn payload(mut self, payload: &'a [u8]) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    /// payload shorter than available space
+    /// is stuffed via adaptation-field;
+    /// panics if payload does not fit
+    pub(crate) fn build(&self) -> [u8; Packet::SZ] {
+        let mut af = self.adaptation.map(<[u8]>::to_vec);
+        let room = Packet::SZ - HEADER_SZ - af.as_ref().map_or(0, |af| 1 + af.len());
+
+        assert!(self.payload.len() <= room, "payload does not fit");
+
+        if self.payload.len() < room {
+            let af = af.get_or_insert_with(Vec::new);
+            let mut stuffing = room - self.payload.len();
+
+            if self.adaptation.is_none() {
+                // adaptation-field-length byte
+                stuffing -= 1;
+
+                if stuffing > 0 {
+                    // flags
+                    af.push(0x00);
+                    stuffing -= 1;
+                }
+            }
+
+            af.resize(af.len() + stuffing, 0xFF);
+        }
+
+        let mut raw = [0xFFu8; Packet::SZ];
+        raw[0] = SYNC_BYTE;
+        raw[1] = (self.tei as u8) << 7 | (self.pusi as u8) << 6 | (self.pid >> 8) as u8 & 0x1F;
+        raw[2] = self.pid as u8;
+        raw[3] = match (af.is_some(), self.payload.is_empty()) {
+            (true, true) => 0x20,
+            (true, false) => 0x30,
+            _ => 0x10,
+        } | self.tsc << 6
+            | self.cc;
+
+        let mut pos = HEADER_SZ;
+        if let Some(af) = af {
+            raw[pos] = af.len() as u8;
+            raw[pos + 1..pos + 1 + af.len()].copy_from_slice(&af);
+            pos += 1 + af.len();
+        }
+        raw[pos..pos + self.payload.len()].copy_from_slice(self.payload);
+
+        raw
+    }
+}
+
+/// splits sections and PES into ts-packets of one PID;
+/// keeps continuity-counter
+pub(crate) struct Packetizer {
+    pid: u16,
+    cc: u8,
+}
+
+impl Packetizer {
+    pub(crate) fn new(pid: u16) -> Packetizer {
+        Packetizer { pid, cc: 0 }
+    }
+
+    #[inline(always)]
+    fn next_cc(&mut self) -> u8 {
+        let cc = self.cc;
+        self.cc = (self.cc + 1) & 0x0F;
+        cc
+    }
+
+    /// section with pointer-field;
+    /// tail padded with 0xFF
+    pub(crate) fn section(&mut self, section: &[u8]) -> Vec<[u8; Packet::SZ]> {
+        let mut buf = Vec::with_capacity(1 + section.len());
+        buf.push(0x00);
+        buf.extend_from_slice(section);
+
+        buf.chunks(Packet::SZ - HEADER_SZ)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let mut raw = PacketBuilder::new(self.pid)
+                    .pusi(i == 0)
+                    .cc(self.next_cc())
+                    .payload(chunk)
+                    .build();
+
+                // sections are padded with 0xFF instead of adaptation stuffing
+                if chunk.len() < Packet::SZ - HEADER_SZ {
+                    raw[3] = 0x10 | (raw[3] & 0x0F);
+                    raw[HEADER_SZ..HEADER_SZ + chunk.len()].copy_from_slice(chunk);
+                    raw[HEADER_SZ + chunk.len()..].fill(0xFF);
+                }
+
+                raw
+            })
+            .collect()
+    }
+
+    /// PES packet;
+    /// adaptation (without length byte) goes into the first ts-packet,
+    /// tail is stuffed via adaptation-field
+    pub(crate) fn pes(&mut self, adaptation: Option<&[u8]>, pes: &[u8]) -> Vec<[u8; Packet::SZ]> {
+        let mut out = Vec::new();
+        let mut rest = pes;
+        let mut first = true;
+
+        while first || !rest.is_empty() {
+            let adaptation = adaptation.filter(|_| first);
+            let room = Packet::SZ - HEADER_SZ - adaptation.map_or(0, |af| 1 + af.len());
+            let (chunk, tail) = rest.split_at(room.min(rest.len()));
+
+            let mut builder = PacketBuilder::new(self.pid)
+                .pusi(first)
+                .cc(self.next_cc())
+                .payload(chunk);
+            if let Some(adaptation) = adaptation {
+                builder = builder.adaptation(adaptation);
+            }
+            out.push(builder.build());
+
+            rest = tail;
+            first = false;
+        }
+
+        out
+    }
+}
+
+/// long-form PSI section with valid CRC
+pub(crate) fn section(
+    table_id: u8,
+    ext: u16,
+    version_number: u8,
+    (section_number, last_section_number): (u8, u8),
+    body: &[u8],
+) -> Vec<u8> {
+    let section_length = 5 + body.len() + 4;
+
+    let mut buf = vec![
+        table_id,
+        0xB0 | (section_length >> 8) as u8,
+        section_length as u8,
+        (ext >> 8) as u8,
+        ext as u8,
+        0xC1 | ((version_number & 0x1F) << 1),
+        section_number,
+        last_section_number,
+    ];
+    buf.extend_from_slice(body);
+    buf.extend_from_slice(&crc32(&buf).to_be_bytes());
+
+    buf
+}
+
+/// PAT section; (program-number, pmt-pid)
+pub(crate) fn pat(
+    transport_stream_id: u16,
+    version_number: u8,
+    programs: &[(u16, u16)],
+) -> Vec<u8> {
+    let body = programs
+        .iter()
+        .flat_map(|(n, pid)| {
+            [
+                (n >> 8) as u8,
+                *n as u8,
+                0xE0 | (pid >> 8) as u8,
+                *pid as u8,
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    section(0x00, transport_stream_id, version_number, (0, 0), &body)
+}
+
+/// PMT section; (stream-type, elementary-pid)
+pub(crate) fn pmt(program_number: u16, pcr_pid: u16, streams: &[(u8, u16)]) -> Vec<u8> {
+    let mut body = vec![0xE0 | (pcr_pid >> 8) as u8, pcr_pid as u8, 0xF0, 0x00];
+    for (stream_type, pid) in streams {
+        body.extend_from_slice(&[
+            *stream_type,
+            0xE0 | (pid >> 8) as u8,
+            *pid as u8,
+            0xF0,
+            0x00,
+        ]);
+    }
+
+    section(0x02, program_number, 0, (0, 0), &body)
+}
+
+/// 33-bit timestamp with marker bits;
+/// prefix: 0b0010 - PTS only, 0b0011 - PTS of PTS+DTS, 0b0001 - DTS
+fn timestamp(prefix: u8, ts: u64) -> [u8; 5] {
+    [
+        prefix << 4 | ((ts >> 29) & 0x0E) as u8 | 0x01,
+        (ts >> 22) as u8,
+        ((ts >> 14) & 0xFE) as u8 | 0x01,
+        (ts >> 7) as u8,
+        ((ts << 1) & 0xFE) as u8 | 0x01,
+    ]
+}
+
+/// PES packet with optional PTS/DTS (90kHz)
+pub(crate) fn pes(stream_id: u8, pts: Option<u64>, dts: Option<u64>, payload: &[u8]) -> Vec<u8> {
+    let mut header = Vec::new();
+    let flags = match (pts, dts) {
+        (Some(pts), Some(dts)) => {
+            header.extend_from_slice(&timestamp(0b0011, pts));
+            header.extend_from_slice(&timestamp(0b0001, dts));
+            0xC0
+        }
+        (Some(pts), None) => {
+            header.extend_from_slice(&timestamp(0b0010, pts));
+            0x80
+        }
+        _ => 0x00,
+    };
+
+    let pes_packet_length = 3 + header.len() + payload.len();
+    // unbounded for video
+    let pes_packet_length = if pes_packet_length > 0xFFFF {
+        0
+    } else {
+        pes_packet_length
+    };
+
+    let mut buf = vec![
+        0x00,
+        0x00,
+        0x01,
+        stream_id,
+        (pes_packet_length >> 8) as u8,
+        pes_packet_length as u8,
+        0x80,
+        flags,
+        header.len() as u8,
+    ];
+    buf.extend_from_slice(&header);
+    buf.extend_from_slice(payload);
+
+    buf
+}
+
+/// adaptation-field (without length byte) with PCR (27MHz)
+pub(crate) fn adaptation_pcr(pcr: u64, random_access: bool) -> Vec<u8> {
+    let (base, ext) = (pcr / 300, pcr % 300);
+
+    vec![
+        0x10 | if random_access { 0x40 } else { 0x00 },
+        (base >> 25) as u8,
+        (base >> 17) as u8,
+        (base >> 9) as u8,
+        (base >> 1) as u8,
+        ((base & 1) << 7) as u8 | 0x7E | (ext >> 8) as u8,
+        ext as u8,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adaptation_pcr, crc32, pat, pes, pmt, PacketBuilder, Packetizer};
+    use crate::packet::Packet;
+    use crate::pes::PES;
+    use crate::section::{WithSyntaxSection, PAT, PMT};
+
+    #[test]
+    fn packet() {
+        let raw = PacketBuilder::new(0x1FFF)
+            .pusi(true)
+            .tei(true)
+            .cc(5)
+            .payload(&[0xAA; 10])
+            .build();
+        let pkt = Packet::new(&raw).unwrap();
+
+        assert_eq!(u16::from(pkt.pid()), 0x1FFF);
+        assert!(pkt.pusi());
+        assert!(pkt.tei());
+        assert_eq!(pkt.cc(), 5);
+        assert_eq!(pkt.buf_payload_pes().unwrap(), &[0xAA; 10]);
+    }
+
+    #[test]
+    fn packet_stuffing() {
+        for sz in [1, 182, 183, 184] {
+            let payload = vec![0xAA; sz];
+            let raw = PacketBuilder::new(0x100).payload(&payload).build();
+            let pkt = Packet::new(&raw).unwrap();
+
+            assert_eq!(pkt.buf_payload_pes().unwrap(), payload.as_slice());
+        }
+    }
+
+    #[test]
+    fn section_pat() {
+        let section = pat(1, 3, &[(0, 0x10), (1, 0x100), (2, 0x200)]);
+        let pkts = Packetizer::new(0x0000).section(&section);
+
+        assert_eq!(pkts.len(), 1);
+
+        let pkt = Packet::new(&pkts[0]).unwrap();
+        let pat = PAT::try_new(pkt.buf_payload_section().unwrap()).unwrap();
+
+        assert_eq!(pat.transport_stream_id(), 1);
+        assert_eq!(pat.version_number(), 3);
+        assert_eq!(
+            pat.program_map_pids().collect::<Vec<_>>(),
+            vec![(1, 0x100), (2, 0x200)]
+        );
+        assert_eq!(crc32(&section), 0);
+    }
+
+    #[test]
+    fn section_multiple_packets() {
+        let streams = (0..60).map(|i| (0x1B, 0x101 + i)).collect::<Vec<_>>();
+        let section = pmt(1, 0x101, &streams);
+        let pkts = Packetizer::new(0x100).section(&section);
+
+        assert_eq!(pkts.len(), 2);
+
+        let mut buf = Packet::new(&pkts[0])
+            .unwrap()
+            .buf_payload_section()
+            .unwrap()
+            .to_vec();
+        buf.extend_from_slice(Packet::new(&pkts[1]).unwrap().buf_payload_pes().unwrap());
+
+        let pmt = PMT::new(&buf);
+        assert_eq!(pmt.program_number(), 1);
+        assert_eq!(pmt.streams().count(), 60);
+        assert_eq!(Packet::new(&pkts[1]).unwrap().cc(), 1);
+    }
+
+    #[test]
+    fn pes_pts_dts() {
+        let raw = pes(0xE0, Some(0x1_2345_6789), Some(0x0_9876_5432), &[0xAA; 4]);
+        let pes = PES::new(&raw);
+
+        assert_eq!(pes.pts().map(|ts| ts.value()), Some(0x1_2345_6789));
+        assert_eq!(pes.dts().map(|ts| ts.value()), Some(0x0_9876_5432));
+        assert_eq!(pes.buf_seek_payload(), &[0xAA; 4]);
+    }
+
+    #[test]
+    fn pes_multiple_packets() {
+        let raw = pes(0xE0, Some(0), None, &[0xAA; 500]);
+        let af = adaptation_pcr(27_000_000, true);
+        let pkts = Packetizer::new(0x101).pes(Some(&af), &raw);
+
+        assert_eq!(pkts.len(), 3);
+
+        let first = Packet::new(&pkts[0]).unwrap();
+        let adapt = first.adaptation().unwrap().unwrap();
+        assert!(adapt.random_access_indicator());
+        assert_eq!(adapt.pcr().map(|pcr| pcr.value()), Some(27_000_000));
+
+        let sz = pkts
+            .iter()
+            .map(|raw| Packet::new(raw).unwrap().buf_payload_pes().unwrap().len())
+            .sum::<usize>();
+        assert_eq!(sz, raw.len());
+    }
+}
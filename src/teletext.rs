@@ -0,0 +1,563 @@
+//! EBU Teletext subtitles in PES (private_stream_1)
+//!
+//! ETSI EN 300 472 (data units), ETSI EN 300 706 (packets, character sets);
+//! level 1 pages with level 1.5 X/26 G2 / diacritic enhancements;
+//! no Hamming 24/18 error correction
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// Hamming 8/4 codewords of 0x0..=0xF
+/// (transmission bit order, LSB first)
+const HAMMING_8_4: [u8; 16] = [
+    0x15, 0x02, 0x49, 0x5E, 0x64, 0x73, 0x38, 0x2F, 0xD0, 0xC7, 0x8C, 0x9B, 0xA1, 0xB6, 0xFD, 0xEA,
+];
+
+/// single bit errors are corrected
+#[inline(always)]
+fn unham_8_4(b: u8) -> Option<u8> {
+    HAMMING_8_4
+        .iter()
+        .position(|c| (c ^ b).count_ones() <= 1)
+        .map(|d| d as u8)
+}
+
+/// data bits of Hamming 24/18 triplet
+#[inline(always)]
+fn unham_24_18(b: &[u8]) -> u32 {
+    let a = u32::from(b[0]) | (u32::from(b[1]) << 8) | (u32::from(b[2]) << 16);
+
+    ((a & 0x00_0004) >> 2)
+        | ((a & 0x00_0070) >> 3)
+        | ((a & 0x00_7F00) >> 4)
+        | ((a & 0x7F_0000) >> 5)
+}
+
+/// 7-bit character with odd parity;
+/// parity error is displayed as space
+#[inline(always)]
+fn unparity(b: u8) -> u8 {
+    if b.count_ones() % 2 == 1 {
+        b & 0x7F
+    } else {
+        0x20
+    }
+}
+
+/// national option sub-set positions in G0 latin
+const NATIONAL_POSITIONS: [u8; 13] = [
+    0x23, 0x24, 0x40, 0x5B, 0x5C, 0x5D, 0x5E, 0x5F, 0x60, 0x7B, 0x7C, 0x7D, 0x7E,
+];
+
+/// by C12 | C13 << 1 | C14 << 2
+const NATIONAL_SUBSETS: [[char; 13]; 8] = [
+    // english
+    [
+        '£', '$', '@', '←', '½', '→', '↑', '#', '―', '¼', '‖', '¾', '÷',
+    ],
+    // french
+    [
+        'é', 'ï', 'à', 'ë', 'ê', 'ù', 'î', '#', 'è', 'â', 'ô', 'û', 'ç',
+    ],
+    // swedish / finnish / hungarian
+    [
+        '#', '¤', 'É', 'Ä', 'Ö', 'Å', 'Ü', '_', 'é', 'ä', 'ö', 'å', 'ü',
+    ],
+    // czech / slovak
+    [
+        '#', 'ů', 'č', 'ť', 'ž', 'ý', 'í', 'ř', 'é', 'á', 'ě', 'ú', 'š',
+    ],
+    // german
+    [
+        '#', '$', '§', 'Ä', 'Ö', 'Ü', '^', '_', '°', 'ä', 'ö', 'ü', 'ß',
+    ],
+    // portuguese / spanish
+    [
+        'ç', '$', '¡', 'á', 'é', 'í', 'ó', 'ú', '¿', 'ü', 'ñ', 'è', 'à',
+    ],
+    // italian
+    [
+        '£', '$', 'é', '°', 'ç', '→', '↑', '#', 'ù', 'à', 'ò', 'è', 'ì',
+    ],
+    // not used - english
+    [
+        '£', '$', '@', '←', '½', '→', '↑', '#', '―', '¼', '‖', '¾', '÷',
+    ],
+];
+
+/// G2 latin 0x20..=0x7F;
+/// column 4 - diacritical marks (combining)
+const G2: [char; 96] = [
+    ' ', '¡', '¢', '£', '$', '¥', '#', '§', '¤', '‘', '“', '«', '←', '↑', '→', '↓', //
+    '°', '±', '²', '³', '×', 'µ', '¶', '·', '÷', '’', '”', '»', '¼', '½', '¾', '¿', //
+    ' ', '\u{300}', '\u{301}', '\u{302}', '\u{303}', '\u{304}', '\u{306}', '\u{307}', //
+    '\u{308}', ' ', '\u{30A}', '\u{327}', '_', '\u{30B}', '\u{328}', '\u{30C}', //
+    '―', '¹', '®', '©', '™', '♪', '€', '‰', 'α', ' ', ' ', ' ', '⅛', '⅜', '⅝', '⅞', //
+    'Ω', 'Æ', 'Đ', 'ª', 'Ħ', ' ', 'Ĳ', 'Ŀ', 'Ł', 'Ø', 'Œ', 'º', 'Þ', 'Ŧ', 'Ŋ', 'ŉ', //
+    'ĸ', 'æ', 'đ', 'ð', 'ħ', 'ı', 'ĳ', 'ŀ', 'ł', 'ø', 'œ', 'ß', 'þ', 'ŧ', 'ŋ', '■', //
+];
+
+/// G0 latin with national option sub-set;
+/// control (spacing attribute) codes are displayed as space
+#[inline(always)]
+fn g0(c: u8, national: u8) -> char {
+    match c {
+        0x00..=0x1F => ' ',
+        0x7F => '■',
+        _ => match NATIONAL_POSITIONS.iter().position(|p| *p == c) {
+            Some(i) => NATIONAL_SUBSETS[usize::from(national & 0x07)][i],
+            None => char::from(c),
+        },
+    }
+}
+
+/// decoded row
+#[derive(Clone, Debug, PartialEq)]
+pub struct TeletextLine {
+    /// 1..=24
+    pub row: u8,
+    pub text: String,
+}
+
+/// complete subtitle page
+#[derive(Clone, Debug, PartialEq)]
+pub struct TeletextPage {
+    /// magazine and page number, e.g. 0x888
+    pub page: u16,
+
+    /// PTS of PES with page header;
+    /// page is shown until the next page of the same number
+    pub pts: Option<Duration>,
+
+    /// non-empty rows;
+    /// empty page clears subtitles
+    pub lines: Vec<TeletextLine>,
+}
+
+/// page in reception
+struct PageBuf {
+    page: u16,
+    pts: Option<Duration>,
+    national: u8,
+    rows: [[u8; 40]; 25],
+    /// X/26 (row, column, char)
+    enhancements: Vec<(usize, usize, char)>,
+}
+
+impl PageBuf {
+    fn new(page: u16, pts: Option<Duration>, national: u8) -> PageBuf {
+        PageBuf {
+            page,
+            pts,
+            national,
+            rows: [[0x20; 40]; 25],
+            enhancements: Vec::new(),
+        }
+    }
+
+    fn page(&self) -> TeletextPage {
+        let mut rows = [[' '; 40]; 25];
+        let mut marks: Vec<(usize, usize, char)> = Vec::new();
+
+        for (row, raw) in self.rows.iter().enumerate() {
+            for (col, c) in raw.iter().enumerate() {
+                rows[row][col] = g0(*c, self.national);
+            }
+        }
+
+        for &(row, col, c) in self.enhancements.iter() {
+            match c {
+                // diacritical mark over G0 character
+                '\u{300}'..='\u{36F}' => marks.push((row, col, c)),
+                _ => rows[row][col] = c,
+            }
+        }
+
+        let lines = rows
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter_map(|(row, chars)| {
+                let mut text = String::new();
+                for (col, c) in chars.iter().enumerate() {
+                    text.push(*c);
+                    marks
+                        .iter()
+                        .filter(|(r, cl, _)| *r == row && *cl == col)
+                        .for_each(|(_, _, m)| text.push(*m));
+                }
+
+                let text = text.trim();
+                if text.is_empty() {
+                    None
+                } else {
+                    Some(TeletextLine {
+                        row: row as u8,
+                        text: String::from(text),
+                    })
+                }
+            })
+            .collect();
+
+        TeletextPage {
+            page: self.page,
+            pts: self.pts,
+            lines,
+        }
+    }
+}
+
+/// subtitle pages decoder;
+/// fed with reassembled PES payloads
+#[derive(Default)]
+pub struct TeletextDecoder {
+    /// None - all subtitle pages
+    page: Option<u16>,
+
+    /// per magazine
+    magazines: [Option<PageBuf>; 8],
+}
+
+impl TeletextDecoder {
+    const DATA_UNIT_ID_NON_SUBTITLE: u8 = 0x02;
+    const DATA_UNIT_ID_SUBTITLE: u8 = 0x03;
+    const DATA_UNIT_SZ: usize = 44;
+
+    const FRAMING_CODE: u8 = 0xE4;
+
+    pub fn new() -> TeletextDecoder {
+        Default::default()
+    }
+
+    /// decode single page (e.g. 0x888);
+    /// default: all pages with subtitle flag (C6)
+    #[inline(always)]
+    pub fn set_page(&mut self, page: Option<u16>) {
+        self.page = page;
+    }
+
+    /// feed PES payload (data_identifier onwards);
+    /// pages completed by payload are passed to on_page
+    pub fn push<F>(&mut self, payload: &[u8], pts: Option<Duration>, mut on_page: F)
+    where
+        F: FnMut(&TeletextPage),
+    {
+        // EBU data
+        if !payload.first().is_some_and(|d| (0x10..=0x1F).contains(d)) {
+            return;
+        }
+
+        let mut buf = &payload[1..];
+
+        while buf.len() >= 2 {
+            let (id, sz) = (buf[0], usize::from(buf[1]));
+            if buf.len() < 2 + sz {
+                break;
+            }
+
+            if (id == Self::DATA_UNIT_ID_NON_SUBTITLE || id == Self::DATA_UNIT_ID_SUBTITLE)
+                && sz == Self::DATA_UNIT_SZ
+                && buf[3] == Self::FRAMING_CODE
+            {
+                let mut pkt = [0u8; 42];
+                for (d, s) in pkt.iter_mut().zip(&buf[4..2 + sz]) {
+                    *d = s.reverse_bits();
+                }
+
+                self.packet(&pkt, pts, &mut on_page);
+            }
+
+            buf = &buf[2 + sz..];
+        }
+    }
+
+    /// feed demuxed PES
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    pub fn push_packet<F>(&mut self, pkt: &crate::demuxer::Packet, on_page: F)
+    where
+        F: FnMut(&TeletextPage),
+    {
+        self.push(pkt.buf.as_slice(), pkt.pts, on_page)
+    }
+
+    /// end of stream;
+    /// pages in reception are passed to on_page
+    pub fn flush<F>(&mut self, mut on_page: F)
+    where
+        F: FnMut(&TeletextPage),
+    {
+        for magazine in 0..self.magazines.len() {
+            self.finish(magazine, &mut on_page);
+        }
+    }
+
+    #[inline(always)]
+    fn finish<F>(&mut self, magazine: usize, on_page: &mut F)
+    where
+        F: FnMut(&TeletextPage),
+    {
+        if let Some(page) = self.magazines[magazine].take() {
+            // emit
+            on_page(&page.page());
+        }
+    }
+
+    /// magazine-and-packet-address + data-block
+    fn packet<F>(&mut self, pkt: &[u8; 42], pts: Option<Duration>, on_page: &mut F)
+    where
+        F: FnMut(&TeletextPage),
+    {
+        let (m, y) = match (unham_8_4(pkt[0]), unham_8_4(pkt[1])) {
+            (Some(a), Some(b)) => (a & 0x07, (a >> 3) | (b << 1)),
+            _ => return,
+        };
+        // magazine 8 is coded as 0
+        let magazine = usize::from(m);
+        let data = &pkt[2..];
+
+        match y {
+            0 => self.header(magazine, data, pts, on_page),
+            1..=24 => {
+                if let Some(page) = self.magazines[magazine].as_mut() {
+                    for (d, c) in page.rows[usize::from(y)].iter_mut().zip(data) {
+                        *d = unparity(*c);
+                    }
+                }
+            }
+            26 => {
+                if let Some(page) = self.magazines[magazine].as_mut() {
+                    Self::enhancements(page, data);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn header<F>(&mut self, magazine: usize, data: &[u8], pts: Option<Duration>, on_page: &mut F)
+    where
+        F: FnMut(&TeletextPage),
+    {
+        let mut d = [0u8; 8];
+        for (d, b) in d.iter_mut().zip(data) {
+            *d = match unham_8_4(*b) {
+                Some(v) => v,
+                None => return,
+            };
+        }
+
+        let serial = (d[7] & 0x01) != 0;
+        if serial {
+            self.flush(&mut *on_page);
+        } else {
+            self.finish(magazine, on_page);
+        }
+
+        // page 0xFF - time filling header, no page
+        let (units, tens) = (u16::from(d[0]), u16::from(d[1]));
+        if units > 9 || tens > 9 {
+            return;
+        }
+
+        let m = if magazine == 0 { 8 } else { magazine as u16 };
+        let page = (m << 8) | (tens << 4) | units;
+        let subtitle = (d[5] & 0x08) != 0;
+
+        let wanted = match self.page {
+            Some(p) => p == page,
+            None => subtitle,
+        };
+
+        if wanted {
+            self.magazines[magazine] = Some(PageBuf::new(page, pts, (d[7] >> 1) & 0x07));
+        }
+    }
+
+    /// X/26 triplets;
+    /// set-active-position, G2 character, G0 character with diacritical mark
+    fn enhancements(page: &mut PageBuf, data: &[u8]) {
+        // designation code
+        let triplets = &data[1..];
+        let mut row = 0;
+
+        for t in triplets.chunks_exact(3).map(unham_24_18) {
+            let address = (t & 0x3F) as usize;
+            let mode = ((t >> 6) & 0x1F) as u8;
+            let c = ((t >> 11) & 0x7F) as u8;
+
+            match (address, mode) {
+                // termination marker
+                (63, 0x1F) => break,
+                // set active position
+                (40..=63, 0x04) => row = if address == 40 { 24 } else { address - 40 },
+                (0..=39, 0x0F) if c >= 0x20 => {
+                    page.enhancements
+                        .push((row, address, G2[usize::from(c - 0x20)]));
+                }
+                (0..=39, 0x10..=0x1F) if c >= 0x20 => {
+                    page.enhancements.push((row, address, g0(c, page.national)));
+
+                    // mode 0x10 - no diacritical mark
+                    if mode > 0x10 {
+                        let mark = G2[usize::from(0x40 + (mode - 0x10) - 0x20)];
+                        page.enhancements.push((row, address, mark));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use super::{unham_8_4, TeletextDecoder, TeletextLine, TeletextPage, HAMMING_8_4};
+
+    fn ham(d: u8) -> u8 {
+        HAMMING_8_4[usize::from(d & 0x0F)]
+    }
+
+    /// odd parity
+    fn parity(c: u8) -> u8 {
+        if c.count_ones().is_multiple_of(2) {
+            c | 0x80
+        } else {
+            c
+        }
+    }
+
+    /// EBU teletext data unit
+    fn data_unit(magazine: u8, y: u8, data: &[u8; 40]) -> Vec<u8> {
+        let mut unit = vec![0x03, 0x2C, 0x00, 0xE4];
+        let mut pkt = vec![ham((magazine & 0x07) | ((y & 0x01) << 3)), ham(y >> 1)];
+        pkt.extend_from_slice(data);
+
+        unit.extend(pkt.iter().map(|b| b.reverse_bits()));
+        unit
+    }
+
+    /// header with subtitle flag
+    fn header(magazine: u8, page: u8, national: u8) -> Vec<u8> {
+        let mut data = [parity(b' '); 40];
+        data[..8].copy_from_slice(&[
+            ham(page & 0x0F),
+            ham(page >> 4),
+            ham(0),
+            ham(0),
+            ham(0),
+            ham(0x08),
+            ham(0),
+            ham(national << 1),
+        ]);
+        data_unit(magazine, 0, &data)
+    }
+
+    fn row(magazine: u8, y: u8, text: &[u8]) -> Vec<u8> {
+        let mut data = [parity(b' '); 40];
+        // start box (x2), text, end box
+        let line = [&[0x0B, 0x0B][..], text, &[0x0A]].concat();
+        for (d, c) in data[2..].iter_mut().zip(line) {
+            *d = parity(c);
+        }
+        data_unit(magazine, y, &data)
+    }
+
+    fn pes(units: &[Vec<u8>]) -> Vec<u8> {
+        let mut payload = vec![0x10];
+        for unit in units {
+            payload.extend_from_slice(unit);
+        }
+        // stuffing unit
+        payload.extend_from_slice(&[0xFF, 0x2C]);
+        payload.extend_from_slice(&[0xFF; 44]);
+        payload
+    }
+
+    #[test]
+    fn hamming_8_4() {
+        for d in 0..16 {
+            assert_eq!(unham_8_4(ham(d)), Some(d));
+            // single bit error
+            assert_eq!(unham_8_4(ham(d) ^ 0x10), Some(d));
+        }
+        // double bit error
+        assert_eq!(unham_8_4(ham(0) ^ 0x03), None);
+    }
+
+    #[test]
+    fn subtitle_pages() {
+        let mut decoder = TeletextDecoder::new();
+        let mut pages = Vec::new();
+        let ms = Duration::from_millis;
+
+        decoder.push(
+            &pes(&[
+                header(0, 0x88, 0),
+                row(0, 20, b"Hello,"),
+                row(0, 22, b"#1 world"),
+            ]),
+            Some(ms(1000)),
+            |p| pages.push(p.clone()),
+        );
+        assert!(pages.is_empty());
+
+        // german national sub-set: '[' -> 'A' umlaut
+        decoder.push(
+            &pes(&[header(0, 0x88, 4), row(0, 22, b"[rger")]),
+            Some(ms(3000)),
+            |p| pages.push(p.clone()),
+        );
+        decoder.flush(|p| pages.push(p.clone()));
+
+        assert_eq!(
+            pages,
+            vec![
+                TeletextPage {
+                    page: 0x888,
+                    pts: Some(ms(1000)),
+                    lines: vec![
+                        TeletextLine {
+                            row: 20,
+                            text: "Hello,".to_string()
+                        },
+                        TeletextLine {
+                            row: 22,
+                            text: "£1 world".to_string()
+                        },
+                    ],
+                },
+                TeletextPage {
+                    page: 0x888,
+                    pts: Some(ms(3000)),
+                    lines: vec![TeletextLine {
+                        row: 22,
+                        text: "Ärger".to_string()
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn page_filter() {
+        let mut decoder = TeletextDecoder::new();
+        decoder.set_page(Some(0x777));
+        let mut pages = Vec::new();
+
+        decoder.push(
+            &pes(&[header(0, 0x88, 0), row(0, 20, b"skipped")]),
+            None,
+            |p| pages.push(p.page),
+        );
+        decoder.push(
+            &pes(&[header(7, 0x77, 0), row(7, 20, b"kept")]),
+            None,
+            |p| pages.push(p.page),
+        );
+        decoder.flush(|p| pages.push(p.page));
+
+        assert_eq!(pages, vec![0x777]);
+    }
+}
@@ -0,0 +1,330 @@
+//! async input (tokio)
+//!
+//! Demuxer keeps sections in `Rc`, so it is driven on the current task
+//! (or `LocalSet`); demuxed data leaves through `mpsc` channel as owned
+//! `Send` events.
+use std::future::poll_fn;
+use std::mem;
+use std::pin::Pin;
+use std::time::Duration;
+
+use futures_core::Stream;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc::Sender;
+
+use crate::demuxer::{Demuxer, DemuxerEvents, Packet, Table};
+use crate::input::{Aligner, InputStats};
+use crate::packet::Packet as TsPacket;
+use crate::pes::StreamID;
+use crate::pid::PID;
+use crate::result::Result;
+use crate::subtable_id::SubtableID;
+
+/// owned PES packet
+#[derive(Clone, Debug)]
+pub struct TsPes {
+    pub pid: PID,
+    pub program_number: u16,
+    pub offset: usize,
+    pub stream_id: Option<StreamID>,
+    pub pts: Option<Duration>,
+    pub dts: Option<Duration>,
+    pub random_access: bool,
+    pub buf: Vec<u8>,
+}
+
+impl From<&Packet> for TsPes {
+    fn from(pkt: &Packet) -> Self {
+        TsPes {
+            pid: pkt.pid,
+            program_number: pkt.program_number,
+            offset: pkt.offset,
+            stream_id: pkt.stream_id,
+            pts: pkt.pts,
+            dts: pkt.dts,
+            random_access: pkt.random_access,
+            buf: pkt.buf.as_slice().to_vec(),
+        }
+    }
+}
+
+/// owned `DemuxerEvents` counterpart;
+/// tables are raw sections ordered by section-number
+#[derive(Clone, Debug)]
+pub enum TsEvent {
+    Table(SubtableID, Vec<Vec<u8>>),
+    /// (old-version-number, new subtable-id, new table);
+    /// followed by `Table` for the same table
+    TableUpdate(u8, SubtableID, Vec<Vec<u8>>),
+    Pes(TsPes),
+    CorruptPacket(PID),
+}
+
+fn table_sections(table: &Table) -> Vec<Vec<u8>> {
+    table.sections_bytes().map(|raw| raw.to_vec()).collect()
+}
+
+#[derive(Default)]
+struct Collector(Vec<TsEvent>);
+
+impl DemuxerEvents for Collector {
+    fn on_table(&mut self, id: SubtableID, table: &Table) {
+        self.0.push(TsEvent::Table(id, table_sections(table)));
+    }
+
+    fn on_table_update(&mut self, old_version: u8, id: SubtableID, table: &Table) {
+        self.0
+            .push(TsEvent::TableUpdate(old_version, id, table_sections(table)));
+    }
+
+    fn on_packet(&mut self, pkt: &Packet) {
+        self.0.push(TsEvent::Pes(TsPes::from(pkt)));
+    }
+
+    fn on_corrupt_packet(&mut self, pid: PID) {
+        self.0.push(TsEvent::CorruptPacket(pid));
+    }
+}
+
+/// chunked input demuxer;
+/// chunks need not be packet aligned (e.g. 7 packets UDP datagrams,
+/// arbitrary reads), split packet waits for the next chunk
+pub struct TsStream {
+    demuxer: Demuxer<Collector>,
+    aligner: Aligner,
+    /// unconsumed tail of previous chunk
+    tail: Vec<u8>,
+}
+
+impl Default for TsStream {
+    fn default() -> Self {
+        TsStream::new()
+    }
+}
+
+impl TsStream {
+    /// max UDP payload
+    const DATAGRAM_SZ: usize = 65_535;
+
+    pub fn new() -> TsStream {
+        TsStream {
+            demuxer: Demuxer::new(Default::default()),
+            aligner: Default::default(),
+            tail: Vec::with_capacity(TsPacket::SZ * 7),
+        }
+    }
+
+    /// demux chunk;
+    /// return: events emitted by chunk
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<TsEvent> {
+        self.demux(chunk, false)
+    }
+
+    /// end of input, trailing incomplete packet is skipped
+    pub fn finish(&mut self) -> Vec<TsEvent> {
+        self.demux(&[], true)
+    }
+
+    #[inline(always)]
+    pub fn stats(&self) -> &InputStats {
+        &self.aligner.stats
+    }
+
+    fn demux(&mut self, chunk: &[u8], eof: bool) -> Vec<TsEvent> {
+        if self.tail.is_empty() {
+            // aligned chunks are demuxed in place
+            let consumed = self.aligner.demux(&mut self.demuxer, chunk, eof);
+            self.tail.extend_from_slice(&chunk[consumed..]);
+        } else {
+            self.tail.extend_from_slice(chunk);
+            let consumed = self.aligner.demux(&mut self.demuxer, &self.tail, eof);
+            self.tail.drain(..consumed);
+        }
+
+        mem::take(&mut self.demuxer.events_mut().0)
+    }
+
+    /// send events;
+    /// return: receiver is alive
+    async fn send(tx: &Sender<TsEvent>, events: Vec<TsEvent>) -> bool {
+        for event in events {
+            // backpressure: wait for free slot
+            if tx.send(event).await.is_err() {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// demux until EOF or receiver is dropped
+    pub async fn read_from<R>(&mut self, mut reader: R, tx: &Sender<TsEvent>) -> Result<InputStats>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let mut buf = vec![0u8; 1024 * TsPacket::SZ];
+
+        loop {
+            let n = reader.read(&mut buf).await?;
+
+            let events = if n == 0 {
+                self.finish()
+            } else {
+                self.push(&buf[..n])
+            };
+
+            if !Self::send(tx, events).await || n == 0 {
+                break;
+            }
+        }
+
+        Ok(self.stats().clone())
+    }
+
+    /// demux chunks (e.g. `Bytes` of UDP/SRT socket)
+    /// until stream end or receiver is dropped
+    pub async fn recv_from<S, B>(&mut self, mut stream: S, tx: &Sender<TsEvent>) -> InputStats
+    where
+        S: Stream<Item = B> + Unpin,
+        B: AsRef<[u8]>,
+    {
+        loop {
+            let chunk = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await;
+
+            let eof = chunk.is_none();
+
+            let events = match chunk {
+                Some(chunk) => self.push(chunk.as_ref()),
+                None => self.finish(),
+            };
+
+            if !Self::send(tx, events).await || eof {
+                break;
+            }
+        }
+
+        self.stats().clone()
+    }
+
+    /// demux datagrams until receiver is dropped
+    pub async fn recv_udp(
+        &mut self,
+        socket: &UdpSocket,
+        tx: &Sender<TsEvent>,
+    ) -> Result<InputStats> {
+        let mut buf = vec![0u8; Self::DATAGRAM_SZ];
+
+        loop {
+            let n = socket.recv(&mut buf).await?;
+
+            if !Self::send(tx, self.push(&buf[..n])).await {
+                break;
+            }
+        }
+
+        Ok(self.stats().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use futures_core::Stream;
+    use tokio::sync::mpsc;
+
+    use super::{TsEvent, TsStream};
+    use crate::synthetic::{self, Packetizer};
+
+    fn capture() -> Vec<u8> {
+        let mut buf = Vec::new();
+
+        for raw in Packetizer::new(0x0000).section(&synthetic::pat(1, 0, &[(1, 0x100)])) {
+            buf.extend_from_slice(&raw);
+        }
+        for raw in Packetizer::new(0x0100).section(&synthetic::pmt(1, 0x101, &[(0x1B, 0x101)])) {
+            buf.extend_from_slice(&raw);
+        }
+
+        buf
+    }
+
+    fn tables(events: &[TsEvent]) -> usize {
+        events
+            .iter()
+            .filter(|e| matches!(e, TsEvent::Table(..)))
+            .count()
+    }
+
+    struct Chunks(Vec<Vec<u8>>);
+
+    impl Stream for Chunks {
+        type Item = Vec<u8>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Vec<u8>>> {
+            Poll::Ready(if self.0.is_empty() {
+                None
+            } else {
+                Some(self.0.remove(0))
+            })
+        }
+    }
+
+    #[test]
+    fn push_split_packets() {
+        let capture = capture();
+        let mut stream = TsStream::new();
+        let mut events = Vec::new();
+
+        for chunk in capture.chunks(100) {
+            events.extend(stream.push(chunk));
+        }
+        events.extend(stream.finish());
+
+        assert_eq!(tables(&events), 2);
+        assert_eq!(stream.stats().packets, 2);
+        assert_eq!(stream.stats().skipped, 0);
+    }
+
+    #[tokio::test]
+    async fn read_from() {
+        let capture = capture();
+        let (tx, mut rx) = mpsc::channel(1);
+
+        let mut stream = TsStream::new();
+        let (stats, events) = tokio::join!(stream.read_from(&capture[..], &tx), async {
+            let mut events = Vec::new();
+            while events.len() < 2 {
+                events.push(rx.recv().await.unwrap());
+            }
+            events
+        });
+
+        assert_eq!(stats.unwrap().packets, 2);
+        assert_eq!(tables(&events), 2);
+    }
+
+    #[tokio::test]
+    async fn recv_from() {
+        let capture = capture();
+        let (tx, mut rx) = mpsc::channel(8);
+
+        // datagram-like chunks
+        let chunks = Chunks(capture.chunks(188).map(<[u8]>::to_vec).collect());
+        let stats = TsStream::new().recv_from(chunks, &tx).await;
+        drop(tx);
+
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+
+        assert_eq!(stats.packets, 2);
+        match &events[0] {
+            TsEvent::Table(_, sections) => assert_eq!(sections.len(), 1),
+            e => panic!("expected table, got {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! tuning table from NIT delivery system descriptors
+//!
+//! ETSI EN 300 468 V1.15.1 (6.2.13)
+use alloc::vec::Vec;
+
+use crate::descriptor::{DescDVB0x43, DescDVB0x44, DescDVB0x5A, ParsedDescriptor};
+use crate::result::Result;
+use crate::section::NIT;
+
+/// DVB-S/S2
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Satellite {
+    pub frequency_hz: u64,
+    /// 0.1 degree units
+    pub orbital_position: u16,
+    /// false - west
+    pub east: bool,
+    /// 0 - horizontal, 1 - vertical, 2 - left, 3 - right
+    pub polarization: u8,
+    /// false - DVB-S, true - DVB-S2
+    pub s2: bool,
+    pub modulation_type: u8,
+    pub roll_off: u8,
+    pub symbol_rate: u32,
+    pub fec_inner: u8,
+}
+
+/// DVB-C
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cable {
+    pub frequency_hz: u64,
+    pub fec_outer: u8,
+    pub modulation: u8,
+    pub symbol_rate: u32,
+    pub fec_inner: u8,
+}
+
+/// DVB-T
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Terrestrial {
+    pub frequency_hz: u64,
+    /// 0 - 8MHz, 1 - 7MHz, 2 - 6MHz, 3 - 5MHz
+    pub bandwidth: u8,
+    pub constellation: u8,
+    pub hierarchy_information: u8,
+    pub code_rate_hp_stream: u8,
+    pub code_rate_lp_stream: u8,
+    pub guard_interval: u8,
+    pub transmission_mode: u8,
+}
+
+/// delivery system parameters of transport stream
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Delivery {
+    Satellite(Satellite),
+    Cable(Cable),
+    Terrestrial(Terrestrial),
+}
+
+impl<'buf> From<&DescDVB0x43<'buf>> for Satellite {
+    fn from(d: &DescDVB0x43<'buf>) -> Self {
+        Satellite {
+            // 10kHz units
+            frequency_hz: u64::from(d.frequency()) * 10_000,
+            orbital_position: d.orbital_position(),
+            east: d.west_east_flag(),
+            polarization: d.polarization(),
+            s2: d.modulation_system(),
+            modulation_type: d.modulation_type(),
+            roll_off: d.roll_off(),
+            // 100symbol/s units
+            symbol_rate: d.symbol_rate() * 100,
+            fec_inner: d.fec_inner(),
+        }
+    }
+}
+
+impl<'buf> From<&DescDVB0x44<'buf>> for Cable {
+    fn from(d: &DescDVB0x44<'buf>) -> Self {
+        Cable {
+            // 100Hz units
+            frequency_hz: u64::from(d.frequency()) * 100,
+            fec_outer: d.fec_outer(),
+            modulation: d.modulation(),
+            symbol_rate: d.symbol_rate() * 100,
+            fec_inner: d.fec_inner(),
+        }
+    }
+}
+
+impl<'buf> From<&DescDVB0x5A<'buf>> for Terrestrial {
+    fn from(d: &DescDVB0x5A<'buf>) -> Self {
+        Terrestrial {
+            // 10Hz units
+            frequency_hz: u64::from(d.centre_frequency()) * 10,
+            bandwidth: d.bandwidth(),
+            constellation: d.constellation(),
+            hierarchy_information: d.hierarchy_information(),
+            code_rate_hp_stream: d.code_rate_hp_stream(),
+            code_rate_lp_stream: d.code_rate_lp_stream(),
+            guard_interval: d.guard_interval(),
+            transmission_mode: d.transmission_mode(),
+        }
+    }
+}
+
+/// NIT transport stream loop entry with delivery system
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TuningInfo {
+    pub transport_stream_id: u16,
+    pub original_network_id: u16,
+    pub delivery: Delivery,
+}
+
+impl TuningInfo {
+    /// walk transport stream loop of all NIT sections;
+    /// transport streams without (valid) delivery system descriptor are skipped
+    pub fn from_nits<'buf, I>(nits: I) -> Vec<TuningInfo>
+    where
+        I: IntoIterator<Item = NIT<'buf>>,
+    {
+        let mut out = Vec::new();
+
+        for nit in nits {
+            for stream in nit.streams().filter_map(Result::ok) {
+                let delivery = stream
+                    .descriptors()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Result::ok)
+                    .find_map(|d| match d.try_parsed() {
+                        Ok(ParsedDescriptor::SatelliteDeliverySystem(d)) => {
+                            Some(Delivery::Satellite(Satellite::from(&d)))
+                        }
+                        Ok(ParsedDescriptor::CableDeliverySystem(d)) => {
+                            Some(Delivery::Cable(Cable::from(&d)))
+                        }
+                        Ok(ParsedDescriptor::TerrestrialDeliverySystem(d)) => {
+                            Some(Delivery::Terrestrial(Terrestrial::from(&d)))
+                        }
+                        _ => None,
+                    });
+
+                if let Some(delivery) = delivery {
+                    out.push(TuningInfo {
+                        transport_stream_id: stream.transport_stream_id(),
+                        original_network_id: stream.original_network_id(),
+                        delivery,
+                    });
+                }
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Delivery, TuningInfo};
+    use crate::section::NIT;
+
+    #[rustfmt::skip]
+    const NIT_RAW: [u8; 60] = [
+        0x40, 0xF0, 0x39, 0x00, 0x01, 0xC1, 0x00, 0x00,
+        // network descriptors
+        0xF0, 0x00,
+        // transport stream loop
+        0xF0, 0x2C,
+        // tsid 1: satellite 11.72748 GHz, 19.2E, 27.5 Msym/s
+        0x00, 0x01, 0x00, 0x01, 0xF0, 0x0D,
+        0x43, 0x0B, 0x01, 0x17, 0x27, 0x48, 0x01, 0x92, 0xA6, 0x02, 0x75, 0x00, 0x03,
+        // tsid 2: terrestrial 538 MHz
+        0x00, 0x02, 0x00, 0x01, 0xF0, 0x0D,
+        0x5A, 0x0B, 0x03, 0x34, 0xEC, 0x40, 0x1F, 0x81, 0x1A, 0xFF, 0xFF, 0xFF, 0xFF,
+        // tsid 3: no delivery system
+        0x00, 0x03, 0x00, 0x01, 0xF0, 0x00,
+        // crc
+        0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn from_nits() {
+        let tuning = TuningInfo::from_nits(vec![NIT::new(&NIT_RAW)]);
+
+        assert_eq!(tuning.len(), 2);
+
+        assert_eq!(tuning[0].transport_stream_id, 1);
+        assert_eq!(tuning[0].original_network_id, 1);
+        match &tuning[0].delivery {
+            Delivery::Satellite(s) => {
+                assert_eq!(s.frequency_hz, 11_727_480_000);
+                assert_eq!(s.orbital_position, 192);
+                assert!(s.east);
+                assert!(s.s2);
+                assert_eq!(s.symbol_rate, 27_500_000);
+            }
+            d => panic!("expected satellite, got {:?}", d),
+        }
+
+        assert_eq!(tuning[1].transport_stream_id, 2);
+        match &tuning[1].delivery {
+            Delivery::Terrestrial(t) => {
+                assert_eq!(t.frequency_hz, 538_000_000);
+                assert_eq!(t.bandwidth, 0);
+                assert_eq!(t.transmission_mode, 1);
+            }
+            d => panic!("expected terrestrial, got {:?}", d),
+        }
+    }
+}
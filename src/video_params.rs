@@ -0,0 +1,395 @@
+//! resolution and frame rate from sequence parameter set
+//!
+//! ITU-T H.264 (7.3.2.1.1, E.1.1), ITU-T H.265 (7.3.2.2.1)
+use alloc::vec::Vec;
+
+use crate::bits::Bits;
+#[cfg(feature = "std")]
+use crate::h264::{NALs, NAL};
+#[cfg(feature = "std")]
+use crate::stream_type::StreamType;
+
+/// strip emulation-prevention-three-byte
+fn rbsp(nal: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(nal.len());
+    let mut zeros = 0;
+
+    for &b in nal {
+        if zeros >= 2 && b == 0x03 {
+            zeros = 0;
+            continue;
+        }
+
+        zeros = if b == 0 { zeros + 1 } else { 0 };
+        out.push(b);
+    }
+
+    out
+}
+
+/// (SubWidthC, SubHeightC)
+#[inline(always)]
+fn chroma_sub(chroma_format_idc: u32) -> (u32, u32) {
+    match chroma_format_idc {
+        1 => (2, 2),
+        2 => (2, 1),
+        _ => (1, 1),
+    }
+}
+
+/// coded video resolution and frame rate
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VideoParams {
+    pub width: u32,
+    pub height: u32,
+    /// VUI timing info;
+    /// None - not signaled (always None for H.265)
+    pub fps: Option<f64>,
+}
+
+impl VideoParams {
+    /// first SPS of H.264/H.265 elementary stream (Annex B) chunk
+    #[cfg(feature = "std")]
+    pub(crate) fn from_es(stream_type: &StreamType, es: &[u8]) -> Option<VideoParams> {
+        match stream_type {
+            StreamType::H264 => NALs::new(es)
+                .filter(|nal| nal.nal_unit_type() == NAL::TYPE_SPS)
+                .find_map(|nal| Self::from_h264_sps(nal.buf())),
+            StreamType::H265 => NALs::new(es)
+                .filter(|nal| (nal.buf()[0] >> 1) & 0b0011_1111 == 33)
+                .find_map(|nal| Self::from_h265_sps(nal.buf())),
+            _ => None,
+        }
+    }
+
+    /// H.264 SPS NAL unit (with NAL header, without start code)
+    pub fn from_h264_sps(nal: &[u8]) -> Option<VideoParams> {
+        if nal.first()? & 0b0001_1111 != 7 {
+            return None;
+        }
+
+        let rbsp = rbsp(&nal[1..]);
+        let mut b = Bits::new(&rbsp);
+
+        let profile_idc = b.u(8)?;
+        // constraint flags, level-idc
+        b.skip(16)?;
+        // seq-parameter-set-id
+        b.ue()?;
+
+        let mut chroma_format_idc = 1;
+        if matches!(
+            profile_idc,
+            100 | 110 | 122 | 244 | 44 | 83 | 86 | 118 | 128 | 138 | 139 | 134 | 135
+        ) {
+            chroma_format_idc = b.ue()?;
+            let mut separate_colour_plane = false;
+            if chroma_format_idc == 3 {
+                separate_colour_plane = b.flag()?;
+            }
+            if separate_colour_plane {
+                // ChromaArrayType 0
+                chroma_format_idc = 0;
+            }
+            // bit-depth luma/chroma
+            b.ue()?;
+            b.ue()?;
+            // qpprime-y-zero-transform-bypass
+            b.skip(1)?;
+
+            if b.flag()? {
+                let lists = if chroma_format_idc != 3 { 8 } else { 12 };
+                for i in 0..lists {
+                    if b.flag()? {
+                        Self::skip_scaling_list(&mut b, if i < 6 { 16 } else { 64 })?;
+                    }
+                }
+            }
+        }
+
+        // log2-max-frame-num-minus4
+        b.ue()?;
+        match b.ue()? {
+            0 => {
+                // log2-max-pic-order-cnt-lsb-minus4
+                b.ue()?;
+            }
+            1 => {
+                b.skip(1)?;
+                b.se()?;
+                b.se()?;
+                for _ in 0..b.ue()? {
+                    b.se()?;
+                }
+            }
+            _ => {}
+        }
+        // max-num-ref-frames, gaps-in-frame-num-value-allowed
+        b.ue()?;
+        b.skip(1)?;
+
+        let width_mbs = b.ue()? + 1;
+        let height_map_units = b.ue()? + 1;
+        let frame_mbs_only = b.flag()?;
+        if !frame_mbs_only {
+            // mb-adaptive-frame-field
+            b.skip(1)?;
+        }
+        // direct-8x8-inference
+        b.skip(1)?;
+
+        let (crop_x, crop_y) = if b.flag()? {
+            let (left, right, top, bottom) = (b.ue()?, b.ue()?, b.ue()?, b.ue()?);
+            (left + right, top + bottom)
+        } else {
+            (0, 0)
+        };
+
+        let (sub_width, sub_height) = chroma_sub(chroma_format_idc);
+        let frame_height_mul = if frame_mbs_only { 1 } else { 2 };
+
+        let width = (width_mbs * 16).checked_sub(crop_x * sub_width)?;
+        let height = (height_map_units * 16 * frame_height_mul)
+            .checked_sub(crop_y * sub_height * frame_height_mul)?;
+
+        let fps = if b.flag()? {
+            Self::h264_vui_fps(&mut b)
+        } else {
+            None
+        };
+
+        Some(VideoParams { width, height, fps })
+    }
+
+    fn skip_scaling_list(b: &mut Bits, sz: usize) -> Option<()> {
+        let mut last = 8i32;
+        let mut next = 8i32;
+
+        for _ in 0..sz {
+            if next != 0 {
+                next = (last + b.se()? + 256) % 256;
+            }
+            if next != 0 {
+                last = next;
+            }
+        }
+
+        Some(())
+    }
+
+    /// VUI up to timing info
+    fn h264_vui_fps(b: &mut Bits) -> Option<f64> {
+        // aspect-ratio-info
+        if b.flag()? && b.u(8)? == 255 {
+            // extended SAR
+            b.skip(32)?;
+        }
+        // overscan-info
+        if b.flag()? {
+            b.skip(1)?;
+        }
+        // video-signal-type
+        if b.flag()? {
+            b.skip(4)?;
+            if b.flag()? {
+                // colour description
+                b.skip(24)?;
+            }
+        }
+        // chroma-loc-info
+        if b.flag()? {
+            b.ue()?;
+            b.ue()?;
+        }
+
+        if !b.flag()? {
+            return None;
+        }
+
+        let num_units_in_tick = b.u(32)?;
+        let time_scale = b.u(32)?;
+
+        if num_units_in_tick == 0 {
+            return None;
+        }
+
+        // field rate; two ticks per frame
+        Some(f64::from(time_scale) / (2.0 * f64::from(num_units_in_tick)))
+    }
+
+    /// H.265 SPS NAL unit (with NAL header, without start code)
+    pub fn from_h265_sps(nal: &[u8]) -> Option<VideoParams> {
+        if (nal.first()? >> 1) & 0b0011_1111 != 33 {
+            return None;
+        }
+
+        let rbsp = rbsp(nal.get(2..)?);
+        let mut b = Bits::new(&rbsp);
+
+        // sps-video-parameter-set-id
+        b.skip(4)?;
+        let max_sub_layers_minus1 = b.u(3)? as usize;
+        // temporal-id-nesting
+        b.skip(1)?;
+
+        // profile-tier-level: general profile (88) + level-idc (8)
+        b.skip(96)?;
+        let mut sub_layers = [(false, false); 8];
+        for sub_layer in sub_layers.iter_mut().take(max_sub_layers_minus1) {
+            *sub_layer = (b.flag()?, b.flag()?);
+        }
+        if max_sub_layers_minus1 > 0 {
+            b.skip(2 * (8 - max_sub_layers_minus1))?;
+        }
+        for &(profile, level) in sub_layers.iter().take(max_sub_layers_minus1) {
+            if profile {
+                b.skip(88)?;
+            }
+            if level {
+                b.skip(8)?;
+            }
+        }
+
+        // sps-seq-parameter-set-id
+        b.ue()?;
+        let chroma_format_idc = b.ue()?;
+        if chroma_format_idc == 3 {
+            // separate-colour-plane
+            b.skip(1)?;
+        }
+
+        let mut width = b.ue()?;
+        let mut height = b.ue()?;
+
+        if b.flag()? {
+            let (sub_width, sub_height) = chroma_sub(chroma_format_idc);
+            let (left, right, top, bottom) = (b.ue()?, b.ue()?, b.ue()?, b.ue()?);
+
+            width = width.checked_sub((left + right) * sub_width)?;
+            height = height.checked_sub((top + bottom) * sub_height)?;
+        }
+
+        Some(VideoParams {
+            width,
+            height,
+            fps: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rbsp, VideoParams};
+
+    /// test bitstream writer
+    #[derive(Default)]
+    struct Writer {
+        buf: Vec<u8>,
+        n: usize,
+    }
+
+    impl Writer {
+        fn u(&mut self, n: usize, v: u32) -> &mut Self {
+            for i in (0..n).rev() {
+                if self.n.is_multiple_of(8) {
+                    self.buf.push(0);
+                }
+                let bit = ((u64::from(v) >> i) & 1) as u8;
+                *self.buf.last_mut().unwrap() |= bit << (7 - self.n % 8);
+                self.n += 1;
+            }
+            self
+        }
+
+        fn ue(&mut self, v: u32) -> &mut Self {
+            let v = v + 1;
+            let bits = 32 - v.leading_zeros() as usize;
+            self.u(bits - 1, 0).u(bits, v)
+        }
+
+        /// rbsp-stop-one-bit
+        fn finish(&mut self) -> Vec<u8> {
+            self.u(1, 1);
+            self.buf.clone()
+        }
+    }
+
+    #[test]
+    fn rbsp_emulation_prevention() {
+        assert_eq!(
+            rbsp(&[0x00, 0x00, 0x03, 0x01, 0x00, 0x00, 0x03, 0x00, 0x03]),
+            vec![0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x03]
+        );
+    }
+
+    #[test]
+    fn h264_sps() {
+        let mut w = Writer::default();
+        // NAL header, baseline, level 4.0
+        w.u(8, 0x67).u(8, 66).u(8, 0).u(8, 40);
+        // sps-id, log2-max-frame-num, poc-type 0, log2-max-poc-lsb
+        w.ue(0).ue(0).ue(0).ue(0);
+        // max-num-ref-frames, gaps
+        w.ue(1).u(1, 0);
+        // 1920x1088, frame-mbs-only, direct-8x8
+        w.ue(119).ue(67).u(1, 1).u(1, 1);
+        // cropping: bottom 4 (8 lines)
+        w.u(1, 1).ue(0).ue(0).ue(0).ue(4);
+        // VUI: aspect, overscan, video-signal, chroma-loc are absent
+        w.u(1, 1).u(1, 0).u(1, 0).u(1, 0).u(1, 0);
+        // timing: 1001 / 60000, fixed-frame-rate
+        w.u(1, 1).u(32, 1001).u(32, 60000).u(1, 1);
+        let sps = w.finish();
+
+        let p = VideoParams::from_h264_sps(&sps).unwrap();
+
+        assert_eq!((p.width, p.height), (1920, 1080));
+        assert!((p.fps.unwrap() - 29.97).abs() < 0.01);
+
+        // not SPS
+        assert_eq!(VideoParams::from_h264_sps(&[0x68, 0xCE]), None);
+        // truncated
+        assert_eq!(VideoParams::from_h264_sps(&sps[..6]), None);
+    }
+
+    #[test]
+    fn h264_sps_high_interlaced() {
+        let mut w = Writer::default();
+        // NAL header, high, level 4.0
+        w.u(8, 0x67).u(8, 100).u(8, 0).u(8, 40);
+        // sps-id, chroma 4:2:0, bit-depths, qpprime, no scaling matrix
+        w.ue(0).ue(1).ue(0).ue(0).u(1, 0).u(1, 0);
+        // log2-max-frame-num, poc-type 2
+        w.ue(0).ue(2);
+        w.ue(1).u(1, 0);
+        // 720x576 interlaced: 45 mbs x 18 map units, mbaff, direct-8x8
+        w.ue(44).ue(17).u(1, 0).u(1, 1).u(1, 1);
+        // no cropping, no VUI
+        w.u(1, 0).u(1, 0);
+        let sps = w.finish();
+
+        let p = VideoParams::from_h264_sps(&sps).unwrap();
+
+        assert_eq!((p.width, p.height, p.fps), (720, 576, None));
+    }
+
+    #[test]
+    fn h265_sps() {
+        let mut w = Writer::default();
+        // NAL header (type 33)
+        w.u(8, 0x42).u(8, 0x01);
+        // vps-id, max-sub-layers-minus1 0, temporal-id-nesting
+        w.u(4, 0).u(3, 0).u(1, 1);
+        // profile-tier-level
+        w.u(32, 0x0160_0000).u(32, 0).u(24, 0).u(8, 120);
+        // sps-id, chroma 4:2:0, 1920x1088
+        w.ue(0).ue(1).ue(1920).ue(1088);
+        // conformance window: bottom 4 (8 lines)
+        w.u(1, 1).ue(0).ue(0).ue(0).ue(4);
+        let sps = w.finish();
+
+        let p = VideoParams::from_h265_sps(&sps).unwrap();
+
+        assert_eq!((p.width, p.height, p.fps), (1920, 1080, None));
+    }
+}